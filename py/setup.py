@@ -16,6 +16,7 @@ setup(
    url='https://github.com/nautilus-project/nautilus',
    description='Python client for Nautilus programs on Solana',
    install_requires=[
+       "borsh-construct",
        "pytest",
        "solana",
        "solders",
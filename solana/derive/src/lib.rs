@@ -1,8 +1,13 @@
 //! Nautilus' macros used to power its abstraction.
-use nautilus_syn::{entry::NautilusEntrypoint, object::NautilusObject};
+use nautilus_syn::{
+    cpi::NautilusCpi,
+    entry::{discriminator::DiscriminatorScheme, NautilusEntrypoint},
+    error::NautilusCustomError,
+    object::NautilusObject,
+};
 use proc_macro::TokenStream;
 use quote::ToTokens;
-use syn::{parse_macro_input, ItemStruct};
+use syn::{parse_macro_input, ItemEnum, ItemMod, ItemStruct, LitStr};
 
 extern crate proc_macro;
 
@@ -14,37 +19,94 @@ extern crate proc_macro;
 /// Parses the annotated module into a `syn::ItemMod` and converts that to a
 /// `nautilus_syn::NautilusEntrypoint` to build the program's entrypoint,
 /// processor, and IDL.
+///
+/// Instruction discriminants default to the order the functions are declared
+/// in. Use `#[nautilus(discriminator = "hash")]` to instead derive them from a
+/// hash of each function's name, and `#[discriminant = N]` on a function to pin
+/// its discriminant to a single byte.
 #[proc_macro_attribute]
-pub fn nautilus(_: TokenStream, input: TokenStream) -> TokenStream {
-    parse_macro_input!(input as NautilusEntrypoint)
-        .to_token_stream()
-        .into()
+pub fn nautilus(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let discriminator_scheme = parse_macro_input!(attr as DiscriminatorScheme);
+    let item_mod = parse_macro_input!(input as ItemMod);
+    match NautilusEntrypoint::new(item_mod, discriminator_scheme) {
+        Ok(entrypoint) => entrypoint.to_token_stream().into(),
+        Err(e) => e.to_compile_error().into(),
+    }
 }
 
 /// The derive macro to implement the required traits to allow for the annotated
 /// struct to serve as the data type for a Nautilus record - allowing it to be
 /// used as `T` inside of `Record<'_, T>`.
-#[proc_macro_derive(Table, attributes(default_instructions, primary_key, authority))]
+#[proc_macro_derive(
+    Table,
+    attributes(
+        default_instructions,
+        primary_key,
+        authority,
+        index,
+        unique,
+        foreign_key,
+        version,
+        migrate,
+        max_len
+    )
+)]
 pub fn nautilus_table(input: TokenStream) -> TokenStream {
     let item_struct = parse_macro_input!(input as ItemStruct);
-    NautilusObject::from_item_struct(
+    match NautilusObject::from_item_struct(
         item_struct,
         nautilus_syn::object::NautilusObjectType::Record,
-    )
-    .to_token_stream()
-    .into()
+    ) {
+        Ok(object) => object.to_token_stream().into(),
+        Err(e) => e.to_compile_error().into(),
+    }
 }
 
 /// The derive macro to implement the required traits to allow for the annotated
 /// struct to serve as the data type for a Nautilus account - allowing it to be
 /// used as `T` inside of `Account<'_, T>`.
-#[proc_macro_derive(State, attributes(seeds, authority))]
+#[proc_macro_derive(
+    State,
+    attributes(seeds, authority, version, migrate, zero_copy, max_len)
+)]
 pub fn nautilus_account(input: TokenStream) -> TokenStream {
     let item_struct = parse_macro_input!(input as ItemStruct);
-    NautilusObject::from_item_struct(
+    match NautilusObject::from_item_struct(
         item_struct,
         nautilus_syn::object::NautilusObjectType::Account,
-    )
-    .to_token_stream()
-    .into()
+    ) {
+        Ok(object) => object.to_token_stream().into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// The derive macro to implement the required traits to allow for the annotated
+/// enum to serve as a program's custom errors - allowing its variants to be
+/// returned as a `ProgramError` and listed in the IDL.
+///
+/// Codes start at `6000` in declaration order, unless the enum declares another
+/// offset with `#[error_offset(..)]`. Each variant's message is declared with
+/// `#[msg(..)]`.
+#[proc_macro_derive(NautilusError, attributes(error_offset, msg))]
+pub fn nautilus_error(input: TokenStream) -> TokenStream {
+    let item_enum = parse_macro_input!(input as ItemEnum);
+    match NautilusCustomError::from_item_enum(&item_enum) {
+        Ok(custom_error) => custom_error.to_token_stream().into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// The procedural macro to call another Nautilus program through typed
+/// Cross-Program Invocations (CPI), built from the program's IDL.
+///
+/// Reads the IDL at the provided path - relative to the crate's root - and
+/// builds a module named after the program, with a CPI function for each of
+/// its instructions, ie. `declare_program!("idls/other_program.json")`.
+#[proc_macro]
+pub fn declare_program(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    match NautilusCpi::from_path(&path) {
+        Ok(cpi) => cpi.to_token_stream().into(),
+        Err(e) => e.to_compile_error().into(),
+    }
 }
@@ -0,0 +1,394 @@
+//! Converts a JSON IDL to an Anchor-compatible JSON IDL.
+//!
+//! The exported IDL follows Anchor's IDL spec (`0.1.0`, introduced in Anchor
+//! `0.30`), which allows instructions and accounts to declare explicit
+//! discriminators - so Nautilus' instruction discriminants (single-byte or
+//! hashed) and 8-byte account discriminators can be represented as-is.
+//!
+//! Anything Anchor has no notion of (the original account types, table
+//! configs, seeds, default instructions) is kept under the `nautilus`
+//! extension key so that no information is lost.
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+use convert_case::{Case, Casing};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    idl_error::IdlErrorCode,
+    idl_instruction::{IdlInstruction, IdlInstructionAccount, IdlInstructionDiscriminant},
+    idl_nautilus_config::IdlTypeDefNautilusConfig,
+    idl_type::IdlType,
+    idl_type_def::{IdlTypeDef, IdlTypeDefType, IdlTypeEnumFields, IdlTypeStructField},
+    util::{account_discrim_str, account_discriminator},
+    Idl,
+};
+
+/// The version of Anchor's IDL spec this converter targets.
+pub const ANCHOR_IDL_SPEC: &str = "0.1.0";
+
+pub trait AnchorIdlWrite {
+    fn write_to_anchor_json(&self, dir_path: &str) -> std::io::Result<()>;
+}
+
+pub trait AnchorConverter {
+    fn to_anchor_idl(&self) -> AnchorIdl;
+}
+
+/// An Anchor IDL, with the Nautilus-specific data under `nautilus`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AnchorIdl {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub address: String,
+    pub metadata: AnchorIdlMetadata,
+    pub instructions: Vec<AnchorIdlInstruction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<AnchorIdlAccount>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<AnchorIdlTypeDef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<IdlErrorCode>,
+    pub nautilus: AnchorIdlNautilusExtension,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AnchorIdlMetadata {
+    pub name: String,
+    pub version: String,
+    pub spec: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AnchorIdlInstruction {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+    pub accounts: Vec<AnchorIdlInstructionAccount>,
+    pub args: Vec<AnchorIdlField>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AnchorIdlInstructionAccount {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub docs: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub writable: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub signer: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub optional: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pda: Option<Value>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AnchorIdlAccount {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AnchorIdlTypeDef {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serialization: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repr: Option<Value>,
+    #[serde(rename = "type")]
+    pub ty: Value,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AnchorIdlField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: Value,
+}
+
+/// Everything in a Nautilus IDL that has no equivalent in an Anchor IDL.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnchorIdlNautilusExtension {
+    pub origin: String,
+    pub instructions: Vec<AnchorIdlNautilusInstruction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<AnchorIdlNautilusAccount>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnchorIdlNautilusInstruction {
+    pub name: String,
+    pub discriminant: IdlInstructionDiscriminant,
+    pub accounts: Vec<AnchorIdlNautilusInstructionAccount>,
+    /// Anchor IDLs can't declare remaining accounts, so they're only kept
+    /// here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining_accounts: Option<IdlInstructionAccount>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnchorIdlNautilusInstructionAccount {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub account_type: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnchorIdlNautilusAccount {
+    pub name: String,
+    pub config: IdlTypeDefNautilusConfig,
+}
+
+impl AnchorIdlWrite for Idl {
+    fn write_to_anchor_json(&self, dir_path: &str) -> std::io::Result<()> {
+        if dir_path != "." {
+            fs::create_dir_all(dir_path)?;
+        }
+
+        let anchor_idl_path =
+            Path::join(Path::new(dir_path), format!("{}.anchor.json", &self.name));
+
+        let mut file = File::create(anchor_idl_path)?;
+        let json_string = serde_json::to_string(&self.to_anchor_idl())?;
+        file.write_all(json_string.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+impl AnchorConverter for Idl {
+    fn to_anchor_idl(&self) -> AnchorIdl {
+        AnchorIdl {
+            address: self.metadata.address.clone().unwrap_or_default(),
+            metadata: AnchorIdlMetadata {
+                name: self.name.to_case(Case::Snake),
+                version: self.version.clone(),
+                spec: ANCHOR_IDL_SPEC.to_string(),
+                description: None,
+            },
+            instructions: self.instructions.iter().map(anchor_instruction).collect(),
+            accounts: self
+                .accounts
+                .iter()
+                .map(|a| AnchorIdlAccount {
+                    name: a.name.clone(),
+                    discriminator: account_discriminator(&discrim_str(a)).to_vec(),
+                })
+                .collect(),
+            types: self
+                .accounts
+                .iter()
+                .chain(self.types.iter())
+                .map(anchor_type_def)
+                .collect(),
+            errors: self.errors.clone(),
+            nautilus: AnchorIdlNautilusExtension {
+                origin: self.metadata.origin.clone(),
+                instructions: self
+                    .instructions
+                    .iter()
+                    .map(|ix| AnchorIdlNautilusInstruction {
+                        name: ix.name.to_case(Case::Snake),
+                        discriminant: ix.discriminant.clone(),
+                        accounts: ix
+                            .accounts
+                            .iter()
+                            .map(|a| AnchorIdlNautilusInstructionAccount {
+                                name: a.name.to_case(Case::Snake),
+                                account_type: a.account_type.clone(),
+                            })
+                            .collect(),
+                        remaining_accounts: ix.remaining_accounts.clone(),
+                    })
+                    .collect(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .filter_map(|a| {
+                        a.config.as_ref().map(|config| AnchorIdlNautilusAccount {
+                            name: a.name.clone(),
+                            config: config.clone(),
+                        })
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+fn anchor_instruction(ix: &IdlInstruction) -> AnchorIdlInstruction {
+    AnchorIdlInstruction {
+        name: ix.name.to_case(Case::Snake),
+        discriminator: ix.discriminant.to_bytes(),
+        accounts: ix.accounts.iter().map(anchor_instruction_account).collect(),
+        args: ix
+            .args
+            .iter()
+            .map(|arg| AnchorIdlField {
+                name: arg.name.to_case(Case::Snake),
+                ty: anchor_type(&arg.arg_type),
+            })
+            .collect(),
+    }
+}
+
+/// Accounts whose address is known ahead of time are given an `address` (or,
+/// for the Nautilus Index and table counters, a `pda`) so Anchor clients can resolve them
+/// automatically. Sysvars carry their address in the IDL already. The token
+/// program is left unresolved, since it can be either the Token Program or the
+/// Token-2022 Program.
+fn anchor_instruction_account(account: &IdlInstructionAccount) -> AnchorIdlInstructionAccount {
+    let address = match account.account_type.as_str() {
+        "systemProgram" => Some("11111111111111111111111111111111"),
+        "associatedTokenProgram" => Some("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+        "tokenMetadataProgram" => Some("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"),
+        _ => account.address.as_deref(),
+    };
+    let pda = match account.account_type.as_str() {
+        "index" => Some(json!({
+            "seeds": [
+                { "kind": "const", "value": b"nautilus_index".to_vec() },
+                { "kind": "const", "value": [0] },
+            ]
+        })),
+        // Table counters are named `<table>_counter`.
+        "counter" => account.name.strip_suffix("_counter").map(|table_name| {
+            json!({
+                "seeds": [
+                    { "kind": "const", "value": b"nautilus_counter".to_vec() },
+                    { "kind": "const", "value": table_name.as_bytes().to_vec() },
+                ]
+            })
+        }),
+        _ => None,
+    };
+    AnchorIdlInstructionAccount {
+        name: account.name.to_case(Case::Snake),
+        docs: match account.desc.is_empty() {
+            true => vec![],
+            false => vec![account.desc.clone()],
+        },
+        writable: account.is_mut,
+        signer: account.is_signer,
+        optional: account.is_optional,
+        address: address.map(|a| a.to_string()),
+        pda,
+    }
+}
+
+fn anchor_type_def(type_def: &IdlTypeDef) -> AnchorIdlTypeDef {
+    let ty = match &type_def.idl_type {
+        IdlTypeDefType::Struct { fields } => {
+            let mut anchor_fields = anchor_fields(fields);
+            // A versioned account's version byte follows its discriminator.
+            if let Some(IdlTypeDefNautilusConfig {
+                version: Some(_), ..
+            }) = type_def.config.as_ref()
+            {
+                anchor_fields.insert(
+                    0,
+                    AnchorIdlField {
+                        name: "schema_version".to_string(),
+                        ty: json!("u8"),
+                    },
+                );
+            }
+            json!({
+                "kind": "struct",
+                "fields": anchor_fields,
+            })
+        }
+        IdlTypeDefType::Enum { variants } => json!({
+            "kind": "enum",
+            "variants": variants
+                .iter()
+                .map(|variant| match &variant.fields {
+                    Some(IdlTypeEnumFields::Named(fields)) => json!({
+                        "name": variant.name,
+                        "fields": anchor_fields(fields),
+                    }),
+                    None => json!({ "name": variant.name }),
+                })
+                .collect::<Vec<Value>>(),
+        }),
+    };
+    // Zero-copy accounts are laid out as `#[repr(C)]` plain-old-data.
+    let zero_copy = matches!(
+        type_def.config.as_ref(),
+        Some(IdlTypeDefNautilusConfig {
+            zero_copy: true,
+            ..
+        })
+    );
+    AnchorIdlTypeDef {
+        name: type_def.name.clone(),
+        serialization: zero_copy.then(|| "bytemuck".to_string()),
+        repr: zero_copy.then(|| json!({ "kind": "c" })),
+        ty,
+    }
+}
+
+fn anchor_fields(fields: &[IdlTypeStructField]) -> Vec<AnchorIdlField> {
+    fields
+        .iter()
+        .map(|field| AnchorIdlField {
+            name: field.name.clone(),
+            ty: anchor_type(&field.field_data_type),
+        })
+        .collect()
+}
+
+/// Maps an IDL type to Anchor's type notation.
+///
+/// Sets share Borsh's `Vec` encoding, so they're exported as vectors. Tuples
+/// and maps have no Anchor equivalent and keep their Nautilus notation.
+fn anchor_type(idl_type: &IdlType) -> Value {
+    match idl_type {
+        IdlType::Array(ty, size) => json!({ "array": [anchor_type(ty), size] }),
+        IdlType::Bool => json!("bool"),
+        IdlType::Bytes => json!("bytes"),
+        IdlType::Defined(name) => json!({ "defined": { "name": name } }),
+        IdlType::I128 => json!("i128"),
+        IdlType::I16 => json!("i16"),
+        IdlType::I32 => json!("i32"),
+        IdlType::I64 => json!("i64"),
+        IdlType::I8 => json!("i8"),
+        IdlType::Option(ty) => json!({ "option": anchor_type(ty) }),
+        IdlType::PublicKey => json!("pubkey"),
+        IdlType::String => json!("string"),
+        IdlType::U128 => json!("u128"),
+        IdlType::U16 => json!("u16"),
+        IdlType::U32 => json!("u32"),
+        IdlType::U64 => json!("u64"),
+        IdlType::U8 => json!("u8"),
+        IdlType::Vec(ty) | IdlType::HashSet(ty) | IdlType::BTreeSet(ty) => {
+            json!({ "vec": anchor_type(ty) })
+        }
+        IdlType::Tuple(_) | IdlType::HashMap(..) | IdlType::BTreeMap(..) => {
+            serde_json::to_value(idl_type).unwrap_or(Value::Null)
+        }
+    }
+}
+
+/// The string an account type's discriminator is derived from.
+fn discrim_str(type_def: &IdlTypeDef) -> String {
+    match &type_def.config {
+        Some(config) => account_discrim_str(type_def, config),
+        None => type_def.name.to_case(Case::Snake),
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
@@ -1,2 +1,4 @@
+pub mod anchor;
 pub mod py;
+pub mod rust;
 pub mod ts;
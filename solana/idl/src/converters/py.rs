@@ -5,13 +5,54 @@ use std::{
     path::Path,
 };
 
+use convert_case::{Case, Casing};
+
 use crate::{
-    idl_instruction::IdlInstruction,
+    idl_error::IdlErrorCode,
+    idl_instruction::{IdlInstruction, IdlInstructionDiscriminantValue},
+    idl_nautilus_config::{IdlSeed, IdlTypeDefNautilusConfig},
     idl_type::IdlType,
-    idl_type_def::{IdlTypeDef, IdlTypeDefType},
+    idl_type_def::{IdlTypeDef, IdlTypeDefType, IdlTypeEnumFields, IdlTypeStructField},
+    util::{account_discrim_str, account_discriminator, struct_field_type},
     Idl,
 };
 
+/// The imports and Borsh helpers the generated bindings are built on.
+const PY_BORSH_RUNTIME: &str = r#"from __future__ import annotations
+
+import hashlib
+import typing
+from dataclasses import dataclass, fields
+
+import borsh_construct as borsh
+from construct import Adapter, Bytes
+from solders.instruction import AccountMeta, Instruction
+from solders.pubkey import Pubkey
+
+
+class _PubkeyAdapter(Adapter):
+    def _decode(self, obj, context, path) -> Pubkey:
+        return Pubkey.from_bytes(obj)
+
+    def _encode(self, obj, context, path) -> bytes:
+        return bytes(obj)
+
+
+class _DataclassAdapter(Adapter):
+    def __init__(self, cls, subcon):
+        super().__init__(subcon)
+        self.cls = cls
+
+    def _decode(self, obj, context, path):
+        return self.cls(**{f.name: obj[f.name] for f in fields(self.cls)})
+
+    def _encode(self, obj, context, path):
+        return {f.name: getattr(obj, f.name) for f in fields(self.cls)}
+
+
+BorshPubkey = _PubkeyAdapter(Bytes(32))
+"#;
+
 pub trait PythonIdlWrite {
     fn write_to_py(&self, dir_path: &str) -> std::io::Result<()>;
 }
@@ -26,7 +67,11 @@ impl PythonIdlWrite for Idl {
             fs::create_dir_all(dir_path)?;
         }
 
-        let py_idl_path = Path::join(Path::new(dir_path), &format!("{}.py", &self.name));
+        // Python module names can't contain dashes.
+        let py_idl_path = Path::join(
+            Path::new(dir_path),
+            format!("{}.py", &self.name.to_case(Case::Snake)),
+        );
 
         let mut file = File::create(py_idl_path)?;
         let python_string = self.to_python_string();
@@ -38,19 +83,146 @@ impl PythonIdlWrite for Idl {
 
 impl PythonConverter for Idl {
     fn to_python_string(&self) -> String {
-        // TODO: Lay down schema and add instructions/configs:
-        let mut all_types = self.accounts.clone();
-        all_types.extend(self.types.clone());
-        let all_types_strings: Vec<String> =
-            all_types.iter().map(|t| t.to_python_string()).collect();
-        let res = all_types_strings.join("\n");
-        res
+        let mut sections = vec![
+            format!(
+                "# Python bindings for the `{}` Nautilus program.\n\
+                #\n\
+                # Generated from the program's IDL.\n",
+                self.name
+            ),
+            PY_BORSH_RUNTIME.to_string(),
+        ];
+        let has_tables = self
+            .accounts
+            .iter()
+            .any(|a| a.config.as_ref().map_or(false, |c| c.table_name.is_some()));
+        if has_tables {
+            sections.push(
+                "\n\
+                def derive_nautilus_index_address(program_id: Pubkey) -> typing.Tuple[Pubkey, int]:\n    \
+                    return Pubkey.find_program_address([b\"nautilus_index\", bytes([0])], program_id)\n"
+                    .to_string(),
+            );
+        }
+        let mut all_types = self.types.clone();
+        all_types.extend(self.accounts.clone());
+        py_ordered_type_defs(all_types)
+            .iter()
+            .for_each(|t| sections.push(t.to_python_string()));
+        self.accounts.iter().for_each(|a| {
+            if let Some(config) = &a.config {
+                sections.push(py_account_helpers(a, config));
+            }
+        });
+        self.instructions
+            .iter()
+            .for_each(|ix| sections.push(ix.to_python_string()));
+        if !self.errors.is_empty() {
+            sections.push(py_errors(&self.errors));
+        }
+        sections.join("\n")
     }
 }
 
 impl PythonConverter for IdlInstruction {
     fn to_python_string(&self) -> String {
-        todo!()
+        let snake_name = self.name.to_case(Case::Snake);
+        let pascal_name = self.name.to_case(Case::Pascal);
+        let accounts_fields = match self.accounts.is_empty() {
+            true => "    pass\n".to_string(),
+            false => self
+                .accounts
+                .iter()
+                .map(|a| match a.is_optional {
+                    true => format!("    {}: typing.Optional[Pubkey]\n", py_ident(&a.name)),
+                    false => format!("    {}: Pubkey\n", py_ident(&a.name)),
+                })
+                .collect::<String>(),
+        };
+        let (discriminant_layout, discriminant_value) = match &self.discriminant.value {
+            IdlInstructionDiscriminantValue::U8(value) => ("borsh.U8", value.to_string()),
+            IdlInstructionDiscriminantValue::Hashed(value) => {
+                ("Bytes(8)", format!("bytes({:?})", value))
+            }
+        };
+        let args_layout = std::iter::once(format!("\"discriminant\" / {}", discriminant_layout))
+            .chain(
+                self.args
+                    .iter()
+                    .map(|arg| format!("\"{}\" / {}", arg.name, py_layout(&arg.arg_type))),
+            )
+            .collect::<Vec<String>>()
+            .join(", ");
+        let args_params = self
+            .args
+            .iter()
+            .map(|arg| {
+                format!(
+                    ", {}: {}",
+                    py_ident(&arg.name),
+                    arg.arg_type.to_python_string()
+                )
+            })
+            .collect::<String>();
+        let args_values = std::iter::once(format!("\"discriminant\": {}", discriminant_value))
+            .chain(
+                self.args
+                    .iter()
+                    .map(|arg| format!("\"{}\": {}", arg.name, py_ident(&arg.name))),
+            )
+            .collect::<Vec<String>>()
+            .join(", ");
+        let keys = self
+            .accounts
+            .iter()
+            .map(|a| match a.is_optional {
+                // An omitted optional account is passed as the program ID.
+                true => format!(
+                    "        AccountMeta(pubkey=accounts.{name}, is_signer={}, is_writable={}) \
+                        if accounts.{name} is not None \
+                        else AccountMeta(pubkey=program_id, is_signer=False, is_writable=False),\n",
+                    py_bool(a.is_signer),
+                    py_bool(a.is_mut),
+                    name = py_ident(&a.name),
+                ),
+                false => format!(
+                    "        AccountMeta(pubkey=accounts.{}, is_signer={}, is_writable={}),\n",
+                    py_ident(&a.name),
+                    py_bool(a.is_signer),
+                    py_bool(a.is_mut)
+                ),
+            })
+            .collect::<String>();
+        // Remaining accounts are appended after the instruction's accounts.
+        let (remaining_param, remaining_keys) = match &self.remaining_accounts {
+            Some(a) => (
+                ", remaining_accounts: typing.Optional[typing.List[Pubkey]] = None".to_string(),
+                format!(
+                    "    keys += [AccountMeta(pubkey=pubkey, is_signer={}, is_writable={}) for pubkey in remaining_accounts or []]\n",
+                    py_bool(a.is_signer),
+                    py_bool(a.is_mut)
+                ),
+            ),
+            None => (String::new(), String::new()),
+        };
+        format!(
+            "\n\
+            @dataclass\n\
+            class {pascal_name}Accounts:\n\
+            {accounts_fields}\
+            \n\
+            \n\
+            {pascal_name}ArgsLayout = borsh.CStruct({args_layout})\n\
+            \n\
+            \n\
+            def {snake_name}_instruction(program_id: Pubkey, accounts: {pascal_name}Accounts{args_params}{remaining_param}) -> Instruction:\n    \
+                data = {pascal_name}ArgsLayout.build({{{args_values}}})\n    \
+                keys = [\n\
+            {keys}    \
+                ]\n\
+            {remaining_keys}    \
+                return Instruction(program_id, data, keys)\n"
+        )
     }
 }
 
@@ -58,21 +230,55 @@ impl PythonConverter for IdlTypeDef {
     fn to_python_string(&self) -> String {
         match &self.idl_type {
             IdlTypeDefType::Struct { fields } => {
-                let fields_str = fields
+                let fields_str = match fields.is_empty() {
+                    true => "    pass\n".to_string(),
+                    false => fields
+                        .iter()
+                        .map(|field| {
+                            format!(
+                                "    {}: {}\n",
+                                field.name,
+                                field.field_data_type.to_python_string()
+                            )
+                        })
+                        .collect::<String>(),
+                };
+
+                format!(
+                    "\n\
+                    @dataclass\n\
+                    class {name}:\n\
+                    {fields_str}\
+                    \n\
+                    \n\
+                    {name}Layout = _DataclassAdapter({name}, borsh.CStruct({layout}))\n",
+                    name = self.name,
+                    layout = py_struct_layout(fields),
+                )
+            }
+            IdlTypeDefType::Enum { variants } => {
+                let variants_str = variants
                     .iter()
-                    .map(|field| {
-                        format!(
-                            "   {}: {}",
-                            field.name,
-                            field.field_data_type.to_python_string()
-                        )
+                    .map(|variant| match &variant.fields {
+                        Some(IdlTypeEnumFields::Named(fields)) => format!(
+                            "    \"{}\" / borsh.CStruct({}),\n",
+                            variant.name,
+                            py_struct_layout(fields)
+                        ),
+                        None => format!("    \"{}\",\n", variant.name),
                     })
-                    .collect::<Vec<String>>()
-                    .join("\n");
+                    .collect::<String>();
 
-                format!("class {}:\n{}\n", self.name, fields_str)
+                format!(
+                    "\n\
+                    {name}Layout = borsh.Enum(\n\
+                    {variants_str}    \
+                        enum_name=\"{name}\",\n\
+                    )\n\
+                    {name} = {name}Layout.enum\n",
+                    name = self.name,
+                )
             }
-            IdlTypeDefType::Enum { .. } => String::new(), // TODO: Python enums not supported yet
         }
     }
 }
@@ -80,8 +286,8 @@ impl PythonConverter for IdlTypeDef {
 impl PythonConverter for IdlType {
     fn to_python_string(&self) -> String {
         match self {
-            IdlType::Array(inner_type, size) => {
-                format!("[{}; {}]", inner_type.to_python_string(), size)
+            IdlType::Array(inner_type, _) => {
+                format!("typing.List[{}]", inner_type.to_python_string())
             }
             IdlType::Bool => "bool".to_string(),
             IdlType::Bytes => "bytes".to_string(),
@@ -91,35 +297,326 @@ impl PythonConverter for IdlType {
             IdlType::I32 => "int".to_string(),
             IdlType::I64 => "int".to_string(),
             IdlType::I8 => "int".to_string(),
-            IdlType::Option(inner_type) => format!("Optional[{}]", inner_type.to_python_string()),
+            IdlType::Option(inner_type) => {
+                format!("typing.Optional[{}]", inner_type.to_python_string())
+            }
             IdlType::Tuple(types) => format!(
-                "Tuple[{}]",
+                "typing.Tuple[{}]",
                 types
                     .iter()
                     .map(|t| t.to_python_string())
                     .collect::<Vec<String>>()
                     .join(", ")
             ),
-            IdlType::PublicKey => "PublicKey".to_string(),
+            IdlType::PublicKey => "Pubkey".to_string(),
             IdlType::String => "str".to_string(),
             IdlType::U128 => "int".to_string(),
             IdlType::U16 => "int".to_string(),
             IdlType::U32 => "int".to_string(),
             IdlType::U64 => "int".to_string(),
             IdlType::U8 => "int".to_string(),
-            IdlType::Vec(inner_type) => format!("List[{}]", inner_type.to_python_string()),
+            IdlType::Vec(inner_type) => format!("typing.List[{}]", inner_type.to_python_string()),
             IdlType::HashMap(key_type, value_type) => format!(
-                "Dict[{}, {}]",
+                "typing.Dict[{}, {}]",
                 key_type.to_python_string(),
                 value_type.to_python_string()
             ),
             IdlType::BTreeMap(key_type, value_type) => format!(
-                "Dict[{}, {}]",
+                "typing.Dict[{}, {}]",
                 key_type.to_python_string(),
                 value_type.to_python_string()
             ),
-            IdlType::HashSet(value_type) => format!("Set[{}]", value_type.to_python_string()),
-            IdlType::BTreeSet(value_type) => format!("Set[{}]", value_type.to_python_string()),
+            IdlType::HashSet(value_type) => {
+                format!("typing.Set[{}]", value_type.to_python_string())
+            }
+            IdlType::BTreeSet(value_type) => {
+                format!("typing.Set[{}]", value_type.to_python_string())
+            }
         }
     }
 }
+
+/// Builds the `borsh_construct` layout for an IDL type.
+fn py_layout(idl_type: &IdlType) -> String {
+    match idl_type {
+        IdlType::Array(inner_type, size) => format!("{}[{}]", py_layout(inner_type), size),
+        IdlType::Bool => "borsh.Bool".to_string(),
+        IdlType::Bytes => "borsh.Bytes".to_string(),
+        IdlType::Defined(name) => format!("{}Layout", name),
+        IdlType::I128 => "borsh.I128".to_string(),
+        IdlType::I16 => "borsh.I16".to_string(),
+        IdlType::I32 => "borsh.I32".to_string(),
+        IdlType::I64 => "borsh.I64".to_string(),
+        IdlType::I8 => "borsh.I8".to_string(),
+        IdlType::Option(inner_type) => format!("borsh.Option({})", py_layout(inner_type)),
+        IdlType::Tuple(types) => format!(
+            "borsh.TupleStruct({})",
+            types
+                .iter()
+                .map(py_layout)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        IdlType::PublicKey => "BorshPubkey".to_string(),
+        IdlType::String => "borsh.String".to_string(),
+        IdlType::U128 => "borsh.U128".to_string(),
+        IdlType::U16 => "borsh.U16".to_string(),
+        IdlType::U32 => "borsh.U32".to_string(),
+        IdlType::U64 => "borsh.U64".to_string(),
+        IdlType::U8 => "borsh.U8".to_string(),
+        IdlType::Vec(inner_type) => format!("borsh.Vec({})", py_layout(inner_type)),
+        IdlType::HashMap(key_type, value_type) | IdlType::BTreeMap(key_type, value_type) => {
+            format!(
+                "borsh.HashMap({}, {})",
+                py_layout(key_type),
+                py_layout(value_type)
+            )
+        }
+        IdlType::HashSet(value_type) | IdlType::BTreeSet(value_type) => {
+            format!("borsh.HashSet({})", py_layout(value_type))
+        }
+    }
+}
+
+/// Builds the comma-separated named fields for a `borsh.CStruct(..)`.
+fn py_struct_layout(fields: &[IdlTypeStructField]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("\"{}\" / {}", field.name, py_layout(&field.field_data_type)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Orders type definitions so that every layout is declared after the layouts
+/// it's built from, since Python evaluates them top to bottom.
+fn py_ordered_type_defs(type_defs: Vec<IdlTypeDef>) -> Vec<IdlTypeDef> {
+    fn defined_names(idl_type: &IdlType, names: &mut Vec<String>) {
+        match idl_type {
+            IdlType::Defined(name) => names.push(name.clone()),
+            IdlType::Array(inner, _)
+            | IdlType::Option(inner)
+            | IdlType::Vec(inner)
+            | IdlType::HashSet(inner)
+            | IdlType::BTreeSet(inner) => defined_names(inner, names),
+            IdlType::HashMap(key, value) | IdlType::BTreeMap(key, value) => {
+                defined_names(key, names);
+                defined_names(value, names);
+            }
+            IdlType::Tuple(types) => types.iter().for_each(|t| defined_names(t, names)),
+            _ => (),
+        }
+    }
+    let dependencies = |type_def: &IdlTypeDef| {
+        let mut names = vec![];
+        match &type_def.idl_type {
+            IdlTypeDefType::Struct { fields } => fields
+                .iter()
+                .for_each(|f| defined_names(&f.field_data_type, &mut names)),
+            IdlTypeDefType::Enum { variants } => variants.iter().for_each(|v| {
+                if let Some(IdlTypeEnumFields::Named(fields)) = &v.fields {
+                    fields
+                        .iter()
+                        .for_each(|f| defined_names(&f.field_data_type, &mut names))
+                }
+            }),
+        }
+        names
+    };
+    let mut remaining = type_defs;
+    let mut ordered: Vec<IdlTypeDef> = vec![];
+    while !remaining.is_empty() {
+        let position = remaining
+            .iter()
+            .position(|t| {
+                dependencies(t).iter().all(|name| {
+                    ordered.iter().any(|o| &o.name == name)
+                        || !remaining.iter().any(|r| &r.name == name)
+                })
+            })
+            .unwrap_or(0); // Circular definitions can't be ordered, so keep going.
+        ordered.push(remaining.remove(position));
+    }
+    ordered
+}
+
+/// Builds the discriminator-checked decoder and the address derivation helper
+/// for an account type - either a `Table` record or a `State` account.
+fn py_account_helpers(type_def: &IdlTypeDef, config: &IdlTypeDefNautilusConfig) -> String {
+    let fields = match &type_def.idl_type {
+        IdlTypeDefType::Struct { fields } => fields.clone(),
+        IdlTypeDefType::Enum { .. } => vec![],
+    };
+    let snake_name = type_def.name.to_case(Case::Snake);
+    let discrim_str = account_discrim_str(type_def, config);
+    let discriminator = account_discriminator(&discrim_str)
+        .iter()
+        .map(|b| b.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    let (seeds_params, seeds_items) = match &config.table_name {
+        Some(table_name) => {
+            let mut seeds_params = vec![];
+            let mut seeds_items = vec![format!("b\"{}\"", table_name)];
+            config.primary_key_fields().iter().for_each(|primary_key| {
+                let primary_key_param = py_ident(primary_key);
+                let primary_key_ty = struct_field_type(&fields, primary_key);
+                seeds_items.push(py_key_seed(&primary_key_ty, &primary_key_param));
+                seeds_params.push(format!(
+                    "{}: {}",
+                    primary_key_param,
+                    primary_key_ty.to_python_string()
+                ));
+            });
+            (seeds_params, seeds_items)
+        }
+        None => {
+            let mut seeds_params = vec![];
+            let mut seeds_items = vec![];
+            config.seeds.iter().for_each(|seed| {
+                let (key, ty) = match seed {
+                    IdlSeed::Lit { value } => {
+                        seeds_items.push(format!("b\"{}\"", value));
+                        return;
+                    }
+                    IdlSeed::Field { key } => (key, struct_field_type(&fields, key)),
+                    IdlSeed::Param { key, value } => (key, value.clone()),
+                };
+                let param = py_ident(key);
+                seeds_params.push(format!("{}: {}", param, ty.to_python_string()));
+                seeds_items.push(format!("{}.build({})", py_layout(&ty), param));
+            });
+            (seeds_params, seeds_items)
+        }
+    };
+    let derive_fn = match seeds_items.is_empty() {
+        true => String::new(),
+        false => format!(
+            "\n\
+            \n\
+            def derive_{snake_name}_address(program_id: Pubkey{params}) -> typing.Tuple[Pubkey, int]:\n    \
+                return Pubkey.find_program_address([{items}], program_id)\n",
+            params = seeds_params
+                .iter()
+                .map(|p| format!(", {}", p))
+                .collect::<String>(),
+            items = seeds_items.join(", "),
+        ),
+    };
+    let counter_fn = match (&config.table_name, config.table_counter) {
+        (Some(table_name), true) => format!(
+            "\n\
+            \n\
+            def derive_{snake_name}_counter_address(program_id: Pubkey) -> typing.Tuple[Pubkey, int]:\n    \
+                return Pubkey.find_program_address([b\"nautilus_counter\", b\"{table_name}\"], program_id)\n",
+        ),
+        _ => String::new(),
+    };
+    let lookup_fns = config
+        .lookups
+        .iter()
+        .map(|lookup| {
+            let param = py_ident(&lookup.field);
+            let ty = struct_field_type(&fields, &lookup.field);
+            format!(
+                "\n\
+                \n\
+                def derive_{snake_name}_{field_snake}_lookup_address(program_id: Pubkey, {param}: {ty}) -> typing.Tuple[Pubkey, int]:\n    \
+                    return Pubkey.find_program_address([b\"nautilus_lookup\", b\"{table}\", b\"{field}\", hashlib.sha256({seed}).digest()], program_id)\n",
+                field_snake = lookup.field.to_case(Case::Snake),
+                ty = ty.to_python_string(),
+                table = config.table_name.clone().unwrap_or_default(),
+                field = lookup.field,
+                seed = py_key_seed(&ty, &param),
+            )
+        })
+        .collect::<String>();
+    // A versioned account's data is only decoded in its current layout, since
+    // older layouts are migrated by the program.
+    let (version_const, version_check, data_offset) = match config.version {
+        Some(version) => (
+            format!("{}_VERSION = {}\n", type_def.name.to_case(Case::UpperSnake), version),
+            format!(
+                "    if data[8] != {upper}_VERSION:\n        \
+                    raise ValueError(f\"Unsupported {name} version {{data[8]}}, the account must be migrated first\")\n",
+                name = type_def.name,
+                upper = type_def.name.to_case(Case::UpperSnake),
+            ),
+            9,
+        ),
+        None => (String::new(), String::new(), 8),
+    };
+    format!(
+        "\n\
+        {upper}_DISCRIMINATOR = bytes([{discriminator}])\n\
+        {version_const}\
+        \n\
+        \n\
+        def decode_{snake_name}_account(data: bytes) -> {name}:\n    \
+            if data[:8] != {upper}_DISCRIMINATOR:\n        \
+                raise ValueError(\"Discriminator mismatch for account data: {name}\")\n\
+            {version_check}    \
+            return {name}Layout.parse(data[{data_offset}:])\n\
+        {derive_fn}{counter_fn}{lookup_fns}",
+        name = type_def.name,
+        upper = type_def.name.to_case(Case::UpperSnake),
+    )
+}
+
+/// Encodes a primary key or indexed field's value into the bytes used as its
+/// PDA seed, matching how the program converts it.
+fn py_key_seed(ty: &IdlType, param: &str) -> String {
+    match ty {
+        IdlType::String => format!("{}.encode()", param),
+        IdlType::PublicKey => format!("bytes({})", param),
+        _ => format!("{}.build({})", py_layout(ty), param),
+    }
+}
+
+/// Converts an IDL name into a snake case Python identifier, escaping any
+/// Python keywords.
+fn py_ident(name: &str) -> String {
+    let ident = name.to_case(Case::Snake);
+    match ident.as_str() {
+        "and" | "as" | "assert" | "async" | "await" | "break" | "class" | "continue" | "def"
+        | "del" | "elif" | "else" | "except" | "finally" | "for" | "from" | "global" | "if"
+        | "import" | "in" | "is" | "lambda" | "nonlocal" | "not" | "or" | "pass" | "raise"
+        | "return" | "try" | "while" | "with" | "yield" => format!("{}_", ident),
+        _ => ident,
+    }
+}
+
+fn py_bool(value: bool) -> &'static str {
+    match value {
+        true => "True",
+        false => "False",
+    }
+}
+
+/// The program's custom errors, and a lookup from the code of a failed
+/// transaction's `ProgramError::Custom(code)`.
+fn py_errors(errors: &[IdlErrorCode]) -> String {
+    format!(
+        "\n\
+        @dataclass\n\
+        class ProgramErrorCode:\n    \
+            code: int\n    \
+            name: str\n    \
+            msg: str\n\
+        \n\
+        \n\
+        PROGRAM_ERRORS: typing.List[ProgramErrorCode] = [\n{}]\n\
+        \n\
+        \n\
+        def error_from_code(code: int) -> typing.Optional[ProgramErrorCode]:\n    \
+            return next((e for e in PROGRAM_ERRORS if e.code == code), None)\n",
+        errors
+            .iter()
+            .map(|e| format!(
+                "    ProgramErrorCode({}, {:?}, {}),\n",
+                e.code,
+                e.name,
+                serde_json::to_string(e.msg.as_ref().unwrap_or(&e.name)).unwrap()
+            ))
+            .collect::<String>(),
+    )
+}
@@ -0,0 +1,485 @@
+//! Converts a JSON IDL to a Rust client module.
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+use convert_case::{Case, Casing};
+
+use crate::{
+    idl_instruction::{IdlInstruction, IdlInstructionAccount},
+    idl_nautilus_config::{IdlSeed, IdlTypeDefNautilusConfig},
+    idl_type::IdlType,
+    idl_type_def::{IdlTypeDef, IdlTypeDefType, IdlTypeEnumFields},
+    util::{account_discrim_str, struct_field_type},
+    Idl,
+};
+
+pub trait RustIdlWrite {
+    fn write_to_rs(&self, dir_path: &str) -> std::io::Result<()>;
+}
+
+pub trait RustConverter {
+    fn to_rust_string(&self) -> String;
+}
+
+impl RustIdlWrite for Idl {
+    fn write_to_rs(&self, dir_path: &str) -> std::io::Result<()> {
+        if dir_path != "." {
+            fs::create_dir_all(dir_path)?;
+        }
+
+        let rs_idl_path = Path::join(Path::new(dir_path), format!("{}.rs", &self.name));
+
+        let mut file = File::create(rs_idl_path)?;
+        let rust_string = self.to_rust_string();
+        file.write_all(rust_string.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+impl RustConverter for Idl {
+    fn to_rust_string(&self) -> String {
+        // `BorshDeserialize` is only needed to decode types.
+        let borsh_imports = match self.accounts.is_empty() && self.types.is_empty() {
+            true => "BorshSerialize",
+            false => "{BorshDeserialize, BorshSerialize}",
+        };
+        let mut sections = vec![format!(
+            "//! Rust client for the `{}` Nautilus program.\n\
+            //!\n\
+            //! Generated from the program's IDL.\n\
+            use borsh::{};\n\
+            use solana_program::{{\n    \
+                instruction::{{AccountMeta, Instruction}},\n    \
+                pubkey::Pubkey,\n\
+            }};\n",
+            self.name, borsh_imports
+        )];
+        sections.push(
+            "/// Computes the 8-byte discriminator prefixed to the data of a Nautilus account.\n\
+            pub fn discriminator(discrim_str: &str) -> [u8; 8] {\n    \
+                let mut discriminator = [0u8; 8];\n    \
+                let preimage = format!(\"global:{}\", discrim_str);\n    \
+                discriminator\n        \
+                    .copy_from_slice(&solana_program::hash::hash(preimage.as_bytes()).to_bytes()[..8]);\n    \
+                discriminator\n\
+            }\n"
+                .to_string(),
+        );
+        let has_tables = self
+            .accounts
+            .iter()
+            .any(|a| a.config.as_ref().map_or(false, |c| c.table_name.is_some()));
+        if has_tables {
+            sections.push(
+                "/// Derives the address of the program's Nautilus Index.\n\
+                pub fn nautilus_index_pda(program_id: &Pubkey) -> (Pubkey, u8) {\n    \
+                    Pubkey::find_program_address(&[b\"nautilus_index\", &[0]], program_id)\n\
+                }\n"
+                .to_string(),
+            );
+        }
+        self.accounts
+            .iter()
+            .chain(self.types.iter())
+            .for_each(|t| sections.push(t.to_rust_string()));
+        self.accounts.iter().for_each(|a| {
+            if let Some(config) = &a.config {
+                sections.push(rust_account_impl(a, config));
+            }
+        });
+        self.instructions
+            .iter()
+            .for_each(|ix| sections.push(ix.to_rust_string()));
+        sections.join("\n")
+    }
+}
+
+impl RustConverter for IdlInstruction {
+    fn to_rust_string(&self) -> String {
+        let accounts_struct_name = format!("{}Accounts", self.name.to_case(Case::Pascal));
+        let accounts_fields = self
+            .accounts
+            .iter()
+            .map(|a| {
+                format!(
+                    "    /// {}\n    pub {}: {},\n",
+                    a.desc,
+                    rust_ident(&a.name),
+                    if a.is_optional {
+                        "Option<Pubkey>"
+                    } else {
+                        "Pubkey"
+                    }
+                )
+            })
+            .collect::<String>();
+        let args_params = self
+            .args
+            .iter()
+            .map(|arg| {
+                format!(
+                    ",\n    {}: {}",
+                    rust_ident(&arg.name),
+                    arg.arg_type.to_rust_string()
+                )
+            })
+            .collect::<String>();
+        let discriminant = self
+            .discriminant
+            .to_bytes()
+            .iter()
+            .map(|b| b.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        let data_str = match self.args.is_empty() {
+            true => format!("    let data = vec![{}];\n", discriminant),
+            false => format!(
+                "    let mut data = vec![{}];\n{}",
+                discriminant,
+                self.args
+                    .iter()
+                    .map(|arg| format!(
+                        "    BorshSerialize::serialize(&{}, &mut data).unwrap();\n",
+                        rust_ident(&arg.name)
+                    ))
+                    .collect::<String>()
+            ),
+        };
+        let account_metas = self
+            .accounts
+            .iter()
+            .map(rust_account_meta)
+            .collect::<String>();
+        // Remaining accounts are appended after the instruction's accounts.
+        let (remaining_param, remaining_metas) = match &self.remaining_accounts {
+            Some(a) => (
+                ",\n    remaining_accounts: &[Pubkey]".to_string(),
+                format!(
+                    "\n            .into_iter()\n            \
+                        .chain(remaining_accounts.iter().map(|key| AccountMeta::{}(*key, {})))\n            \
+                        .collect()",
+                    match a.is_mut {
+                        true => "new",
+                        false => "new_readonly",
+                    },
+                    a.is_signer
+                ),
+            ),
+            None => (String::new(), String::new()),
+        };
+        format!(
+            "/// Accounts required by the `{name}` instruction.\n\
+            #[derive(Clone, Debug)]\n\
+            pub struct {accounts_struct_name} {{\n{accounts_fields}}}\n\
+            \n\
+            /// Builds the `{name}` instruction.\n\
+            pub fn {fn_name}(\n    \
+                program_id: &Pubkey,\n    \
+                accounts: &{accounts_struct_name}{args_params}{remaining_param},\n\
+            ) -> Instruction {{\n\
+            {data_str}    \
+                Instruction {{\n        \
+                    program_id: *program_id,\n        \
+                    accounts: vec![\n{account_metas}        ]{remaining_metas},\n        \
+                    data,\n    \
+                }}\n\
+            }}\n",
+            name = self.name,
+            fn_name = rust_ident(&self.name),
+        )
+    }
+}
+
+impl RustConverter for IdlTypeDef {
+    fn to_rust_string(&self) -> String {
+        let body = match &self.idl_type {
+            IdlTypeDefType::Struct { fields } => format!(
+                "pub struct {} {{\n{}}}",
+                self.name,
+                fields
+                    .iter()
+                    .map(|field| format!(
+                        "    pub {}: {},\n",
+                        rust_ident(&field.name),
+                        field.field_data_type.to_rust_string()
+                    ))
+                    .collect::<String>()
+            ),
+            IdlTypeDefType::Enum { variants } => format!(
+                "pub enum {} {{\n{}}}",
+                self.name,
+                variants
+                    .iter()
+                    .map(|variant| match &variant.fields {
+                        Some(IdlTypeEnumFields::Named(fields)) => format!(
+                            "    {} {{ {} }},\n",
+                            variant.name,
+                            fields
+                                .iter()
+                                .map(|field| format!(
+                                    "{}: {}",
+                                    rust_ident(&field.name),
+                                    field.field_data_type.to_rust_string()
+                                ))
+                                .collect::<Vec<String>>()
+                                .join(", ")
+                        ),
+                        None => format!("    {},\n", variant.name),
+                    })
+                    .collect::<String>()
+            ),
+        };
+        format!(
+            "#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]\n{}\n",
+            body
+        )
+    }
+}
+
+impl RustConverter for IdlType {
+    fn to_rust_string(&self) -> String {
+        match self {
+            IdlType::Array(idl_type, size) => format!("[{}; {}]", idl_type.to_rust_string(), size),
+            IdlType::Bool => "bool".to_string(),
+            IdlType::Bytes => "Vec<u8>".to_string(),
+            IdlType::Defined(name) => name.clone(),
+            IdlType::I128 => "i128".to_string(),
+            IdlType::I16 => "i16".to_string(),
+            IdlType::I32 => "i32".to_string(),
+            IdlType::I64 => "i64".to_string(),
+            IdlType::I8 => "i8".to_string(),
+            IdlType::Option(idl_type) => format!("Option<{}>", idl_type.to_rust_string()),
+            IdlType::Tuple(idl_types) => format!(
+                "({},)",
+                idl_types
+                    .iter()
+                    .map(|idl_type| idl_type.to_rust_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            IdlType::PublicKey => "Pubkey".to_string(),
+            IdlType::String => "String".to_string(),
+            IdlType::U128 => "u128".to_string(),
+            IdlType::U16 => "u16".to_string(),
+            IdlType::U32 => "u32".to_string(),
+            IdlType::U64 => "u64".to_string(),
+            IdlType::U8 => "u8".to_string(),
+            IdlType::Vec(idl_type) => format!("Vec<{}>", idl_type.to_rust_string()),
+            IdlType::HashMap(key_type, value_type) => format!(
+                "std::collections::HashMap<{}, {}>",
+                key_type.to_rust_string(),
+                value_type.to_rust_string()
+            ),
+            IdlType::BTreeMap(key_type, value_type) => format!(
+                "std::collections::BTreeMap<{}, {}>",
+                key_type.to_rust_string(),
+                value_type.to_rust_string()
+            ),
+            IdlType::HashSet(idl_type) => {
+                format!("std::collections::HashSet<{}>", idl_type.to_rust_string())
+            }
+            IdlType::BTreeSet(idl_type) => {
+                format!("std::collections::BTreeSet<{}>", idl_type.to_rust_string())
+            }
+        }
+    }
+}
+
+/// Builds the discriminator-checked decoder and the PDA helper for an account
+/// type - either a `Table` record or a `State` account.
+fn rust_account_impl(type_def: &IdlTypeDef, config: &IdlTypeDefNautilusConfig) -> String {
+    let fields = match &type_def.idl_type {
+        IdlTypeDefType::Struct { fields } => fields.clone(),
+        IdlTypeDefType::Enum { .. } => vec![],
+    };
+    let discrim_str = account_discrim_str(type_def, config);
+    let (seeds_params, seeds_items) = match &config.table_name {
+        Some(table_name) => {
+            let mut seeds_params = vec![];
+            let mut seeds_items = vec![format!("b\"{}\".to_vec()", table_name)];
+            config.primary_key_fields().iter().for_each(|primary_key| {
+                let primary_key_param = rust_ident(primary_key);
+                let primary_key_ty = struct_field_type(&fields, primary_key);
+                seeds_items.push(rust_key_seed(&primary_key_ty, &primary_key_param));
+                seeds_params.push(format!(
+                    "{}: &{}",
+                    primary_key_param,
+                    primary_key_ty.to_rust_string()
+                ));
+            });
+            (seeds_params, seeds_items)
+        }
+        None => {
+            let mut seeds_params = vec![];
+            let mut seeds_items = vec![];
+            config.seeds.iter().for_each(|seed| match seed {
+                IdlSeed::Lit { value } => seeds_items.push(format!("b\"{}\".to_vec()", value)),
+                IdlSeed::Field { key } => {
+                    seeds_params.push(format!(
+                        "{}: &{}",
+                        rust_ident(key),
+                        struct_field_type(&fields, key).to_rust_string()
+                    ));
+                    seeds_items.push(format!("{}.try_to_vec().unwrap()", rust_ident(key)));
+                }
+                IdlSeed::Param { key, value } => {
+                    seeds_params.push(format!("{}: &{}", rust_ident(key), value.to_rust_string()));
+                    seeds_items.push(format!("{}.try_to_vec().unwrap()", rust_ident(key)));
+                }
+            });
+            (seeds_params, seeds_items)
+        }
+    };
+    let pda_fn = match seeds_items.is_empty() {
+        true => String::new(),
+        false => format!(
+            "\n    \
+            /// Derives the address of a `{name}` account from its seeds.\n    \
+            pub fn pda(program_id: &Pubkey{params}) -> (Pubkey, u8) {{\n        \
+                let seeds_vec: Vec<Vec<u8>> = vec![{items}];\n        \
+                let seeds: Vec<&[u8]> = seeds_vec.iter().map(AsRef::as_ref).collect();\n        \
+                Pubkey::find_program_address(&seeds, program_id)\n    \
+            }}\n",
+            name = type_def.name,
+            params = seeds_params
+                .iter()
+                .map(|p| format!(", {}", p))
+                .collect::<String>(),
+            items = seeds_items.join(", "),
+        ),
+    };
+    let counter_fn = match (&config.table_name, config.table_counter) {
+        (Some(table_name), true) => format!(
+            "\n    \
+            /// Derives the address of the record counter for the `{name}` table.\n    \
+            pub fn counter_pda(program_id: &Pubkey) -> (Pubkey, u8) {{\n        \
+                Pubkey::find_program_address(&[b\"nautilus_counter\", b\"{table_name}\"], program_id)\n    \
+            }}\n",
+            name = type_def.name,
+        ),
+        _ => String::new(),
+    };
+    let lookup_fns = config
+        .lookups
+        .iter()
+        .map(|lookup| {
+            let ident = rust_ident(&lookup.field);
+            let ty = struct_field_type(&fields, &lookup.field);
+            format!(
+                "\n    \
+                /// Derives the address of the lookup account for a `{name}`'s `{field}`.\n    \
+                pub fn {field_snake}_lookup_pda(program_id: &Pubkey, {ident}: &{ty}) -> (Pubkey, u8) {{\n        \
+                    let seeds_vec: Vec<Vec<u8>> = vec![b\"nautilus_lookup\".to_vec(), b\"{table}\".to_vec(), b\"{field}\".to_vec(), solana_program::hash::hash(&{seed}).to_bytes().to_vec()];\n        \
+                    let seeds: Vec<&[u8]> = seeds_vec.iter().map(AsRef::as_ref).collect();\n        \
+                    Pubkey::find_program_address(&seeds, program_id)\n    \
+                }}\n",
+                name = type_def.name,
+                field = lookup.field,
+                field_snake = lookup.field.to_case(Case::Snake),
+                ty = ty.to_rust_string(),
+                table = config.table_name.clone().unwrap_or_default(),
+                seed = rust_key_seed(&ty, &ident),
+            )
+        })
+        .collect::<String>();
+    // A versioned account's data is only decoded in its current layout, since
+    // older layouts are migrated by the program.
+    let (version_const, version_check, data_offset) = match config.version {
+        Some(version) => (
+            format!(
+                "    /// The schema version of `{}` account data this client decodes.\n    \
+                pub const VERSION: u8 = {};\n\
+                \n",
+                type_def.name, version
+            ),
+            format!(
+                "        if data[8] != Self::VERSION {{\n            \
+                    return Err(std::io::Error::new(\n                \
+                        std::io::ErrorKind::InvalidData,\n                \
+                        format!(\"Unsupported {name} version {{}}, the account must be migrated first\", data[8]),\n            \
+                    ));\n        \
+                }}\n",
+                name = type_def.name,
+            ),
+            9,
+        ),
+        None => (String::new(), String::new(), 8),
+    };
+    format!(
+        "impl {name} {{\n\
+            {version_const}    \
+            /// The discriminator prefixed to the data of every `{name}` account.\n    \
+            pub fn discriminator() -> [u8; 8] {{\n        \
+                discriminator(\"{discrim_str}\")\n    \
+            }}\n\
+            \n    \
+            /// Decodes a `{name}` from an account's data, checking its discriminator.\n    \
+            pub fn try_from_account_data(data: &[u8]) -> std::io::Result<Self> {{\n        \
+                if data.len() < {data_offset} || data[..8] != Self::discriminator() {{\n            \
+                    return Err(std::io::Error::new(\n                \
+                        std::io::ErrorKind::InvalidData,\n                \
+                        \"Discriminator mismatch for account data: {name}\",\n            \
+                    ));\n        \
+                }}\n\
+                {version_check}        \
+                Self::deserialize(&mut &data[{data_offset}..])\n    \
+            }}\n\
+            {pda_fn}{counter_fn}{lookup_fns}\
+        }}\n",
+        name = type_def.name,
+    )
+}
+
+/// Encodes a primary key or indexed field's value into the bytes used as its
+/// PDA seed, matching how the program converts it.
+fn rust_key_seed(ty: &IdlType, ident: &str) -> String {
+    match ty {
+        IdlType::String => format!("{}.as_bytes().to_vec()", ident),
+        IdlType::U8 => format!("vec![*{}]", ident),
+        IdlType::U16 | IdlType::U32 | IdlType::U64 => format!("{}.to_le_bytes().to_vec()", ident),
+        IdlType::PublicKey => format!("{}.to_bytes().to_vec()", ident),
+        _ => format!("{}.try_to_vec().unwrap()", ident),
+    }
+}
+
+/// Builds the `AccountMeta` for an account listed in an instruction.
+///
+/// An omitted optional account is passed as the program ID.
+fn rust_account_meta(account: &IdlInstructionAccount) -> String {
+    let constructor = match account.is_mut {
+        true => "new",
+        false => "new_readonly",
+    };
+    match account.is_optional {
+        true => format!(
+            "            accounts.{}.map_or(AccountMeta::new_readonly(*program_id, false), |key| AccountMeta::{}(key, {})),\n",
+            rust_ident(&account.name),
+            constructor,
+            account.is_signer
+        ),
+        false => format!(
+            "            AccountMeta::{}(accounts.{}, {}),\n",
+            constructor,
+            rust_ident(&account.name),
+            account.is_signer
+        ),
+    }
+}
+
+/// Converts an IDL name into a snake case Rust identifier, escaping any Rust
+/// keywords.
+pub fn rust_ident(name: &str) -> String {
+    let ident = name.to_case(Case::Snake);
+    match ident.as_str() {
+        "as" | "async" | "await" | "box" | "const" | "crate" | "dyn" | "enum" | "fn" | "impl"
+        | "in" | "let" | "loop" | "match" | "mod" | "move" | "mut" | "pub" | "ref" | "static"
+        | "struct" | "trait" | "type" | "unsafe" | "use" | "where" | "while" => {
+            format!("r#{}", ident)
+        }
+        _ => ident,
+    }
+}
@@ -5,13 +5,108 @@ use std::{
     path::Path,
 };
 
+use convert_case::{Case, Casing};
+
 use crate::{
-    idl_instruction::IdlInstruction,
+    idl_error::IdlErrorCode,
+    idl_instruction::{IdlInstruction, IdlInstructionDiscriminantValue},
+    idl_nautilus_config::{IdlSeed, IdlTypeDefNautilusConfig},
     idl_type::IdlType,
     idl_type_def::{IdlTypeDef, IdlTypeDefType, IdlTypeEnumFields},
+    util::{account_discrim_str, account_discriminator, struct_field_type},
     Idl,
 };
 
+/// The minimal Borsh reader & writer the generated bindings are built on, so
+/// they only depend on `@solana/web3.js`.
+const TS_BORSH_RUNTIME: &str = r#"export class BorshWriter {
+    private chunks: Buffer[] = []
+    private le(value: number | bigint, bytes: number, signed: boolean) {
+        let v = BigInt(value)
+        if (signed && v < BigInt(0)) v += BigInt(1) << BigInt(bytes * 8)
+        const b = Buffer.alloc(bytes)
+        for (let i = 0; i < bytes; i++) {
+            b[i] = Number(v & BigInt(0xff))
+            v >>= BigInt(8)
+        }
+        this.chunks.push(b)
+    }
+    u8(v: number) { this.le(v, 1, false) }
+    u16(v: number) { this.le(v, 2, false) }
+    u32(v: number) { this.le(v, 4, false) }
+    u64(v: bigint | number) { this.le(v, 8, false) }
+    u128(v: bigint | number) { this.le(v, 16, false) }
+    i8(v: number) { this.le(v, 1, true) }
+    i16(v: number) { this.le(v, 2, true) }
+    i32(v: number) { this.le(v, 4, true) }
+    i64(v: bigint | number) { this.le(v, 8, true) }
+    i128(v: bigint | number) { this.le(v, 16, true) }
+    bool(v: boolean) { this.u8(v ? 1 : 0) }
+    fixed(v: Uint8Array) { this.chunks.push(Buffer.from(v)) }
+    bytes(v: Uint8Array) { this.u32(v.length); this.fixed(v) }
+    string(v: string) { this.bytes(Buffer.from(v, "utf8")) }
+    publicKey(v: PublicKey) { this.fixed(v.toBuffer()) }
+    option<T>(v: T | null, f: (x: T) => void) {
+        if (v === null || v === undefined) { this.u8(0) } else { this.u8(1); f(v) }
+    }
+    array<T>(v: T[], f: (x: T) => void) { v.forEach(f) }
+    vec<T>(v: T[], f: (x: T) => void) { this.u32(v.length); v.forEach(f) }
+    set<T>(v: Set<T>, f: (x: T) => void) { this.vec(Array.from(v), f) }
+    map<K, V>(v: Map<K, V>, fk: (k: K) => void, fv: (v: V) => void) {
+        this.u32(v.size)
+        v.forEach((value, key) => { fk(key); fv(value) })
+    }
+    toBuffer(): Buffer { return Buffer.concat(this.chunks) }
+}
+
+export class BorshReader {
+    private offset = 0
+    constructor(private buf: Buffer) {}
+    private le(bytes: number, signed: boolean): bigint {
+        let v = BigInt(0)
+        for (let i = bytes - 1; i >= 0; i--) v = (v << BigInt(8)) | BigInt(this.buf[this.offset + i])
+        this.offset += bytes
+        if (signed && v >= BigInt(1) << BigInt(bytes * 8 - 1)) v -= BigInt(1) << BigInt(bytes * 8)
+        return v
+    }
+    u8(): number { return Number(this.le(1, false)) }
+    u16(): number { return Number(this.le(2, false)) }
+    u32(): number { return Number(this.le(4, false)) }
+    u64(): bigint { return this.le(8, false) }
+    u128(): bigint { return this.le(16, false) }
+    i8(): number { return Number(this.le(1, true)) }
+    i16(): number { return Number(this.le(2, true)) }
+    i32(): number { return Number(this.le(4, true)) }
+    i64(): bigint { return this.le(8, true) }
+    i128(): bigint { return this.le(16, true) }
+    bool(): boolean { return this.u8() !== 0 }
+    fixed(length: number): Buffer {
+        const b = this.buf.subarray(this.offset, this.offset + length)
+        this.offset += length
+        return Buffer.from(b)
+    }
+    bytes(): Buffer { return this.fixed(this.u32()) }
+    string(): string { return this.bytes().toString("utf8") }
+    publicKey(): PublicKey { return new PublicKey(this.fixed(32)) }
+    option<T>(f: () => T): T | null { return this.u8() === 0 ? null : f() }
+    array<T>(length: number, f: () => T): T[] { return Array.from({ length }, f) }
+    vec<T>(f: () => T): T[] { return this.array(this.u32(), f) }
+    set<T>(f: () => T): Set<T> { return new Set(this.vec(f)) }
+    map<K, V>(fk: () => K, fv: () => V): Map<K, V> {
+        const m = new Map<K, V>()
+        const length = this.u32()
+        for (let i = 0; i < length; i++) { const k = fk(); m.set(k, fv()) }
+        return m
+    }
+}
+
+function borshBytes(f: (w: BorshWriter) => void): Buffer {
+    const w = new BorshWriter()
+    f(w)
+    return w.toBuffer()
+}
+"#;
+
 pub trait TypeScriptIdlWrite {
     fn write_to_ts(&self, dir_path: &str) -> std::io::Result<()>;
 }
@@ -26,7 +121,7 @@ impl TypeScriptIdlWrite for Idl {
             fs::create_dir_all(dir_path)?;
         }
 
-        let ts_idl_path = Path::join(Path::new(dir_path), &format!("{}.ts", &self.name));
+        let ts_idl_path = Path::join(Path::new(dir_path), format!("{}.ts", &self.name));
 
         let mut file = File::create(ts_idl_path)?;
         let typescript_string = self.to_typescript_string();
@@ -38,19 +133,155 @@ impl TypeScriptIdlWrite for Idl {
 
 impl TypeScriptConverter for Idl {
     fn to_typescript_string(&self) -> String {
-        // TODO: Lay down schema and add instructions/configs:
-        let mut all_types = self.accounts.clone();
-        all_types.extend(self.types.clone());
-        let all_types_strings: Vec<String> =
-            all_types.iter().map(|t| t.to_typescript_string()).collect();
-        let res = all_types_strings.join("\n");
-        res
+        // Lookup addresses are seeded by a hash of the indexed value.
+        let has_lookups = self
+            .accounts
+            .iter()
+            .any(|a| a.config.as_ref().map_or(false, |c| !c.lookups.is_empty()));
+        let mut sections = vec![
+            format!(
+                "// TypeScript bindings for the `{}` Nautilus program.\n\
+                //\n\
+                // Generated from the program's IDL.\n\
+                {}import {{ Buffer }} from \"buffer\"\n\
+                import {{ PublicKey, TransactionInstruction }} from \"@solana/web3.js\"\n",
+                self.name,
+                match has_lookups {
+                    true => "import { sha256 } from \"@noble/hashes/sha256\"\n",
+                    false => "",
+                }
+            ),
+            TS_BORSH_RUNTIME.to_string(),
+        ];
+        let has_tables = self
+            .accounts
+            .iter()
+            .any(|a| a.config.as_ref().map_or(false, |c| c.table_name.is_some()));
+        if has_tables {
+            sections.push(
+                "export function deriveNautilusIndexAddress(programId: PublicKey): [PublicKey, number] {\n    \
+                    return PublicKey.findProgramAddressSync(\n        \
+                        [Buffer.from(\"nautilus_index\"), Buffer.from([0])],\n        \
+                        programId,\n    \
+                    )\n\
+                }\n"
+                .to_string(),
+            );
+        }
+        self.accounts
+            .iter()
+            .chain(self.types.iter())
+            .for_each(|t| sections.push(t.to_typescript_string()));
+        self.accounts.iter().for_each(|a| {
+            if let Some(config) = &a.config {
+                sections.push(ts_account_helpers(a, config));
+            }
+        });
+        self.instructions
+            .iter()
+            .for_each(|ix| sections.push(ix.to_typescript_string()));
+        if !self.errors.is_empty() {
+            sections.push(ts_errors(&self.errors));
+        }
+        sections.join("\n")
     }
 }
 
 impl TypeScriptConverter for IdlInstruction {
     fn to_typescript_string(&self) -> String {
-        todo!()
+        let pascal_name = self.name.to_case(Case::Pascal);
+        let accounts_fields = self
+            .accounts
+            .iter()
+            .map(|a| {
+                format!(
+                    "    {}{}: PublicKey\n",
+                    a.name.to_case(Case::Camel),
+                    if a.is_optional { "?" } else { "" }
+                )
+            })
+            .collect::<String>();
+        let (args_type, args_param, args_encode) = match self.args.is_empty() {
+            true => (String::new(), String::new(), String::new()),
+            false => (
+                format!(
+                    "\nexport type {}Args = {{\n{}}}\n",
+                    pascal_name,
+                    self.args
+                        .iter()
+                        .map(|arg| format!(
+                            "    {}: {}\n",
+                            arg.name,
+                            arg.arg_type.to_typescript_string()
+                        ))
+                        .collect::<String>()
+                ),
+                format!(",\n    args: {}Args", pascal_name),
+                self.args
+                    .iter()
+                    .map(|arg| {
+                        format!(
+                            "    {}\n",
+                            ts_encode(&arg.arg_type, &format!("args.{}", arg.name), 0)
+                        )
+                    })
+                    .collect::<String>(),
+            ),
+        };
+        let keys = self
+            .accounts
+            .iter()
+            .map(|a| match a.is_optional {
+                // An omitted optional account is passed as the program ID.
+                true => format!(
+                    "            accounts.{name}\n                \
+                        ? {{ pubkey: accounts.{name}, isSigner: {}, isWritable: {} }}\n                \
+                        : {{ pubkey: programId, isSigner: false, isWritable: false }},\n",
+                    a.is_signer,
+                    a.is_mut,
+                    name = a.name.to_case(Case::Camel),
+                ),
+                false => format!(
+                    "            {{ pubkey: accounts.{}, isSigner: {}, isWritable: {} }},\n",
+                    a.name.to_case(Case::Camel),
+                    a.is_signer,
+                    a.is_mut
+                ),
+            })
+            .collect::<String>();
+        // Remaining accounts are appended after the instruction's accounts.
+        let (remaining_param, remaining_keys) = match &self.remaining_accounts {
+            Some(a) => (
+                ",\n    remainingAccounts: PublicKey[] = []".to_string(),
+                format!(
+                    "            ...remainingAccounts.map((pubkey) => ({{ pubkey, isSigner: {}, isWritable: {} }})),\n",
+                    a.is_signer, a.is_mut
+                ),
+            ),
+            None => (String::new(), String::new()),
+        };
+        format!(
+            "export type {pascal_name}Accounts = {{\n{accounts_fields}}}\n\
+            {args_type}\n\
+            export function create{pascal_name}Instruction(\n    \
+                programId: PublicKey,\n    \
+                accounts: {pascal_name}Accounts{args_param}{remaining_param},\n\
+            ): TransactionInstruction {{\n    \
+                const w = new BorshWriter()\n    \
+                {discriminant}\n\
+            {args_encode}    \
+                return new TransactionInstruction({{\n        \
+                    keys: [\n{keys}{remaining_keys}        ],\n        \
+                    programId,\n        \
+                    data: w.toBuffer(),\n    \
+                }})\n\
+            }}\n",
+            discriminant = match &self.discriminant.value {
+                IdlInstructionDiscriminantValue::U8(value) => format!("w.u8({})", value),
+                IdlInstructionDiscriminantValue::Hashed(value) =>
+                    format!("w.fixed(Uint8Array.from({:?}))", value),
+            },
+        )
     }
 }
 
@@ -62,41 +293,127 @@ impl TypeScriptConverter for IdlTypeDef {
                     .iter()
                     .map(|field| {
                         format!(
-                            "   {}: {};",
+                            "    {}: {}\n",
                             field.name,
                             field.field_data_type.to_typescript_string()
                         )
                     })
-                    .collect::<Vec<String>>()
-                    .join("\n");
+                    .collect::<String>();
+                let encode_str = fields
+                    .iter()
+                    .map(|field| {
+                        format!(
+                            "    {}\n",
+                            ts_encode(&field.field_data_type, &format!("value.{}", field.name), 0)
+                        )
+                    })
+                    .collect::<String>();
+                let decode_str = fields
+                    .iter()
+                    .map(|field| {
+                        format!(
+                            "        {}: {},\n",
+                            field.name,
+                            ts_decode(&field.field_data_type)
+                        )
+                    })
+                    .collect::<String>();
 
-                format!("type {} = {{\n{}\n}};", self.name, fields_str)
+                format!(
+                    "export type {name} = {{\n{fields_str}}}\n\
+                    \n\
+                    export function encode{name}(w: BorshWriter, value: {name}) {{\n\
+                    {encode_str}\
+                    }}\n\
+                    \n\
+                    export function decode{name}(r: BorshReader): {name} {{\n    \
+                        return {{\n{decode_str}    }}\n\
+                    }}\n",
+                    name = self.name,
+                )
             }
             IdlTypeDefType::Enum { variants } => {
+                let empty_fields = vec![];
+                let variant_fields = |fields: &Option<IdlTypeEnumFields>| match fields {
+                    Some(IdlTypeEnumFields::Named(fields)) => fields.clone(),
+                    None => empty_fields.clone(),
+                };
                 let variants_str = variants
                     .iter()
                     .map(|variant| {
-                        let fields_str = match &variant.fields {
-                            Some(IdlTypeEnumFields::Named(fields)) => fields
+                        let fields_str = variant_fields(&variant.fields)
+                            .iter()
+                            .map(|field| {
+                                format!(
+                                    ", {}: {}",
+                                    field.name,
+                                    field.field_data_type.to_typescript_string()
+                                )
+                            })
+                            .collect::<String>();
+                        format!("    | {{ kind: \"{}\"{} }}\n", variant.name, fields_str)
+                    })
+                    .collect::<String>();
+                let encode_str = variants
+                    .iter()
+                    .enumerate()
+                    .map(|(i, variant)| {
+                        format!(
+                            "        case \"{}\":\n            w.u8({})\n{}            break\n",
+                            variant.name,
+                            i,
+                            variant_fields(&variant.fields)
                                 .iter()
-                                .map(|field| {
-                                    format!(
-                                        "   {}: {};",
-                                        field.name,
-                                        field.field_data_type.to_typescript_string()
+                                .map(|field| format!(
+                                    "            {}\n",
+                                    ts_encode(
+                                        &field.field_data_type,
+                                        &format!("value.{}", field.name),
+                                        0
                                     )
-                                })
-                                .collect::<Vec<String>>()
-                                .join(", "),
-                            None => String::new(),
-                        };
-
-                        format!("{} = {{ {} }}", variant.name, fields_str)
+                                ))
+                                .collect::<String>()
+                        )
                     })
-                    .collect::<Vec<String>>()
-                    .join(" | ");
+                    .collect::<String>();
+                let decode_str = variants
+                    .iter()
+                    .enumerate()
+                    .map(|(i, variant)| {
+                        format!(
+                            "        case {}:\n            return {{ kind: \"{}\"{} }}\n",
+                            i,
+                            variant.name,
+                            variant_fields(&variant.fields)
+                                .iter()
+                                .map(|field| format!(
+                                    ", {}: {}",
+                                    field.name,
+                                    ts_decode(&field.field_data_type)
+                                ))
+                                .collect::<String>()
+                        )
+                    })
+                    .collect::<String>();
 
-                format!("type {} = {};", self.name, variants_str)
+                format!(
+                    "export type {name} =\n{variants_str}\n\
+                    export function encode{name}(w: BorshWriter, value: {name}) {{\n    \
+                        switch (value.kind) {{\n\
+                    {encode_str}    \
+                        }}\n\
+                    }}\n\
+                    \n\
+                    export function decode{name}(r: BorshReader): {name} {{\n    \
+                        const variant = r.u8()\n    \
+                        switch (variant) {{\n\
+                    {decode_str}        \
+                            default:\n            \
+                                throw new Error(`Invalid variant for {name}: ${{variant}}`)\n    \
+                        }}\n\
+                    }}\n",
+                    name = self.name,
+                )
             }
         }
     }
@@ -105,15 +422,12 @@ impl TypeScriptConverter for IdlTypeDef {
 impl TypeScriptConverter for IdlType {
     fn to_typescript_string(&self) -> String {
         match self {
-            IdlType::Array(idl_type, size) => {
-                format!("[{}; {}]", idl_type.to_typescript_string(), size)
-            }
+            IdlType::Array(idl_type, _) => format!("Array<{}>", idl_type.to_typescript_string()),
             IdlType::Bool => "boolean".to_string(),
             IdlType::Bytes => "Uint8Array".to_string(),
             IdlType::Defined(name) => name.clone(),
-            IdlType::I128 | IdlType::I16 | IdlType::I32 | IdlType::I64 | IdlType::I8 => {
-                "number".to_string()
-            }
+            IdlType::I16 | IdlType::I32 | IdlType::I8 => "number".to_string(),
+            IdlType::I128 | IdlType::I64 => "bigint".to_string(),
             IdlType::Option(idl_type) => format!("{} | null", idl_type.to_typescript_string()),
             IdlType::Tuple(idl_types) => format!(
                 "[{}]",
@@ -125,10 +439,9 @@ impl TypeScriptConverter for IdlType {
             ),
             IdlType::PublicKey => "PublicKey".to_string(),
             IdlType::String => "string".to_string(),
-            IdlType::U128 | IdlType::U16 | IdlType::U32 | IdlType::U64 | IdlType::U8 => {
-                "number".to_string()
-            }
-            IdlType::Vec(idl_type) => format!("{}[]", idl_type.to_typescript_string()),
+            IdlType::U16 | IdlType::U32 | IdlType::U8 => "number".to_string(),
+            IdlType::U128 | IdlType::U64 => "bigint".to_string(),
+            IdlType::Vec(idl_type) => format!("Array<{}>", idl_type.to_typescript_string()),
             IdlType::HashMap(key_type, value_type) => format!(
                 "Map<{}, {}>",
                 key_type.to_typescript_string(),
@@ -144,3 +457,283 @@ impl TypeScriptConverter for IdlType {
         }
     }
 }
+
+/// Builds the TypeScript statement that Borsh-encodes `value` into the writer
+/// `w`.
+///
+/// The `depth` is used to keep the parameter names of nested closures unique.
+fn ts_encode(idl_type: &IdlType, value: &str, depth: usize) -> String {
+    let x = format!("x{}", depth);
+    let k = format!("k{}", depth);
+    match idl_type {
+        IdlType::Array(idl_type, _) => format!(
+            "w.array({}, ({}) => {{ {} }})",
+            value,
+            x,
+            ts_encode(idl_type, &x, depth + 1)
+        ),
+        IdlType::Bool => format!("w.bool({})", value),
+        IdlType::Bytes => format!("w.bytes({})", value),
+        IdlType::Defined(name) => format!("encode{}(w, {})", name, value),
+        IdlType::I128 => format!("w.i128({})", value),
+        IdlType::I16 => format!("w.i16({})", value),
+        IdlType::I32 => format!("w.i32({})", value),
+        IdlType::I64 => format!("w.i64({})", value),
+        IdlType::I8 => format!("w.i8({})", value),
+        IdlType::Option(idl_type) => format!(
+            "w.option({}, ({}) => {{ {} }})",
+            value,
+            x,
+            ts_encode(idl_type, &x, depth + 1)
+        ),
+        IdlType::Tuple(idl_types) => idl_types
+            .iter()
+            .enumerate()
+            .map(|(i, idl_type)| ts_encode(idl_type, &format!("{}[{}]", value, i), depth + 1))
+            .collect::<Vec<String>>()
+            .join("; "),
+        IdlType::PublicKey => format!("w.publicKey({})", value),
+        IdlType::String => format!("w.string({})", value),
+        IdlType::U128 => format!("w.u128({})", value),
+        IdlType::U16 => format!("w.u16({})", value),
+        IdlType::U32 => format!("w.u32({})", value),
+        IdlType::U64 => format!("w.u64({})", value),
+        IdlType::U8 => format!("w.u8({})", value),
+        IdlType::Vec(idl_type) => format!(
+            "w.vec({}, ({}) => {{ {} }})",
+            value,
+            x,
+            ts_encode(idl_type, &x, depth + 1)
+        ),
+        IdlType::HashMap(key_type, value_type) | IdlType::BTreeMap(key_type, value_type) => {
+            format!(
+                "w.map({}, ({}) => {{ {} }}, ({}) => {{ {} }})",
+                value,
+                k,
+                ts_encode(key_type, &k, depth + 1),
+                x,
+                ts_encode(value_type, &x, depth + 1)
+            )
+        }
+        IdlType::HashSet(idl_type) | IdlType::BTreeSet(idl_type) => format!(
+            "w.set({}, ({}) => {{ {} }})",
+            value,
+            x,
+            ts_encode(idl_type, &x, depth + 1)
+        ),
+    }
+}
+
+/// Builds the TypeScript expression that Borsh-decodes a value from the reader
+/// `r`.
+fn ts_decode(idl_type: &IdlType) -> String {
+    match idl_type {
+        IdlType::Array(idl_type, size) => {
+            format!("r.array({}, () => {})", size, ts_decode(idl_type))
+        }
+        IdlType::Bool => "r.bool()".to_string(),
+        IdlType::Bytes => "r.bytes()".to_string(),
+        IdlType::Defined(name) => format!("decode{}(r)", name),
+        IdlType::I128 => "r.i128()".to_string(),
+        IdlType::I16 => "r.i16()".to_string(),
+        IdlType::I32 => "r.i32()".to_string(),
+        IdlType::I64 => "r.i64()".to_string(),
+        IdlType::I8 => "r.i8()".to_string(),
+        IdlType::Option(idl_type) => format!("r.option(() => {})", ts_decode(idl_type)),
+        IdlType::Tuple(idl_types) => format!(
+            "[{}]",
+            idl_types
+                .iter()
+                .map(ts_decode)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        IdlType::PublicKey => "r.publicKey()".to_string(),
+        IdlType::String => "r.string()".to_string(),
+        IdlType::U128 => "r.u128()".to_string(),
+        IdlType::U16 => "r.u16()".to_string(),
+        IdlType::U32 => "r.u32()".to_string(),
+        IdlType::U64 => "r.u64()".to_string(),
+        IdlType::U8 => "r.u8()".to_string(),
+        IdlType::Vec(idl_type) => format!("r.vec(() => {})", ts_decode(idl_type)),
+        IdlType::HashMap(key_type, value_type) | IdlType::BTreeMap(key_type, value_type) => {
+            format!(
+                "r.map(() => {}, () => {})",
+                ts_decode(key_type),
+                ts_decode(value_type)
+            )
+        }
+        IdlType::HashSet(idl_type) | IdlType::BTreeSet(idl_type) => {
+            format!("r.set(() => {})", ts_decode(idl_type))
+        }
+    }
+}
+
+/// Builds the discriminator-checked deserializer and the address derivation
+/// helper for an account type - either a `Table` record or a `State` account.
+fn ts_account_helpers(type_def: &IdlTypeDef, config: &IdlTypeDefNautilusConfig) -> String {
+    let fields = match &type_def.idl_type {
+        IdlTypeDefType::Struct { fields } => fields.clone(),
+        IdlTypeDefType::Enum { .. } => vec![],
+    };
+    let discrim_str = account_discrim_str(type_def, config);
+    let discriminator = account_discriminator(&discrim_str)
+        .iter()
+        .map(|b| b.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    let (seeds_params, seeds_items) = match &config.table_name {
+        Some(table_name) => {
+            let mut seeds_params = vec![];
+            let mut seeds_items = vec![format!("Buffer.from(\"{}\")", table_name)];
+            config.primary_key_fields().iter().for_each(|primary_key| {
+                let primary_key_param = primary_key.to_case(Case::Camel);
+                let primary_key_ty = struct_field_type(&fields, primary_key);
+                seeds_items.push(ts_key_seed(&primary_key_ty, &primary_key_param));
+                seeds_params.push(format!(
+                    "{}: {}",
+                    primary_key_param,
+                    primary_key_ty.to_typescript_string()
+                ));
+            });
+            (seeds_params, seeds_items)
+        }
+        None => {
+            let mut seeds_params = vec![];
+            let mut seeds_items = vec![];
+            config.seeds.iter().for_each(|seed| {
+                let (key, ty) = match seed {
+                    IdlSeed::Lit { value } => {
+                        seeds_items.push(format!("Buffer.from(\"{}\")", value));
+                        return;
+                    }
+                    IdlSeed::Field { key } => (key, struct_field_type(&fields, key)),
+                    IdlSeed::Param { key, value } => (key, value.clone()),
+                };
+                let param = key.to_case(Case::Camel);
+                seeds_params.push(format!("{}: {}", param, ty.to_typescript_string()));
+                seeds_items.push(format!(
+                    "borshBytes((w) => {{ {} }})",
+                    ts_encode(&ty, &param, 0)
+                ));
+            });
+            (seeds_params, seeds_items)
+        }
+    };
+    let derive_fn = match seeds_items.is_empty() {
+        true => String::new(),
+        false => format!(
+            "\n\
+            export function derive{name}Address(programId: PublicKey{params}): [PublicKey, number] {{\n    \
+                return PublicKey.findProgramAddressSync(\n        \
+                    [{items}],\n        \
+                    programId,\n    \
+                )\n\
+            }}\n",
+            name = type_def.name,
+            params = seeds_params
+                .iter()
+                .map(|p| format!(", {}", p))
+                .collect::<String>(),
+            items = seeds_items.join(", "),
+        ),
+    };
+    let counter_fn = match (&config.table_name, config.table_counter) {
+        (Some(table_name), true) => format!(
+            "\n\
+            export function derive{name}CounterAddress(programId: PublicKey): [PublicKey, number] {{\n    \
+                return PublicKey.findProgramAddressSync(\n        \
+                    [Buffer.from(\"nautilus_counter\"), Buffer.from(\"{table_name}\")],\n        \
+                    programId,\n    \
+                )\n\
+            }}\n",
+            name = type_def.name,
+        ),
+        _ => String::new(),
+    };
+    let lookup_fns = config
+        .lookups
+        .iter()
+        .map(|lookup| {
+            let param = lookup.field.to_case(Case::Camel);
+            let ty = struct_field_type(&fields, &lookup.field);
+            format!(
+                "\n\
+                export function derive{name}{field}LookupAddress(programId: PublicKey, {param}: {ty}): [PublicKey, number] {{\n    \
+                    return PublicKey.findProgramAddressSync(\n        \
+                        [Buffer.from(\"nautilus_lookup\"), Buffer.from(\"{table}\"), Buffer.from(\"{field_name}\"), Buffer.from(sha256({seed}))],\n        \
+                        programId,\n    \
+                    )\n\
+                }}\n",
+                name = type_def.name,
+                field = lookup.field.to_case(Case::Pascal),
+                ty = ty.to_typescript_string(),
+                table = config.table_name.clone().unwrap_or_default(),
+                field_name = lookup.field,
+                seed = ts_key_seed(&ty, &param),
+            )
+        })
+        .collect::<String>();
+    // A versioned account's data is only decoded in its current layout, since
+    // older layouts are migrated by the program.
+    let (version_const, version_check, data_offset) = match config.version {
+        Some(version) => (
+            format!("export const {}_VERSION = {}\n", type_def.name.to_case(Case::UpperSnake), version),
+            format!(
+                "    if (data[8] !== {upper}_VERSION) {{\n        \
+                    throw new Error(`Unsupported {name} version ${{data[8]}}, the account must be migrated first`)\n    \
+                }}\n",
+                name = type_def.name,
+                upper = type_def.name.to_case(Case::UpperSnake),
+            ),
+            9,
+        ),
+        None => (String::new(), String::new(), 8),
+    };
+    format!(
+        "export const {upper}_DISCRIMINATOR = Buffer.from([{discriminator}])\n\
+        {version_const}\
+        \n\
+        export function deserialize{name}Account(data: Buffer): {name} {{\n    \
+            if (data.length < {data_offset} || !data.subarray(0, 8).equals({upper}_DISCRIMINATOR)) {{\n        \
+                throw new Error(\"Discriminator mismatch for account data: {name}\")\n    \
+            }}\n\
+            {version_check}    \
+            return decode{name}(new BorshReader(data.subarray({data_offset})))\n\
+        }}\n\
+        {derive_fn}{counter_fn}{lookup_fns}",
+        name = type_def.name,
+        upper = type_def.name.to_case(Case::UpperSnake),
+    )
+}
+
+/// Encodes a primary key or indexed field's value into the bytes used as its
+/// PDA seed, matching how the program converts it.
+fn ts_key_seed(ty: &IdlType, param: &str) -> String {
+    match ty {
+        IdlType::String => format!("Buffer.from({})", param),
+        IdlType::PublicKey => format!("{}.toBuffer()", param),
+        _ => format!("borshBytes((w) => {{ {} }})", ts_encode(ty, param, 0)),
+    }
+}
+
+/// The program's custom errors, and a lookup from the code of a failed
+/// transaction's `ProgramError::Custom(code)`.
+fn ts_errors(errors: &[IdlErrorCode]) -> String {
+    format!(
+        "export type ProgramErrorCode = {{ code: number, name: string, msg: string }}\n\n\
+        export const PROGRAM_ERRORS: ProgramErrorCode[] = [\n{}]\n\n\
+        export function errorFromCode(code: number): ProgramErrorCode | null {{\n    \
+            return PROGRAM_ERRORS.find((e) => e.code === code) ?? null\n\
+        }}\n",
+        errors
+            .iter()
+            .map(|e| format!(
+                "    {{ code: {}, name: {:?}, msg: {} }},\n",
+                e.code,
+                e.name,
+                serde_json::to_string(e.msg.as_ref().unwrap_or(&e.name)).unwrap()
+            ))
+            .collect::<String>(),
+    )
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// A program's custom error, declared with `#[derive(NautilusError)]`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlErrorCode {
+    pub code: u32,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub msg: Option<String>,
+}
+
+impl IdlErrorCode {
+    pub fn new(code: u32, name: String, msg: Option<String>) -> Self {
+        Self { code, name, msg }
+    }
+}
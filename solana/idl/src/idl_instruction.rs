@@ -8,6 +8,10 @@ use super::idl_type::IdlType;
 pub struct IdlInstruction {
     pub name: String,
     pub accounts: Vec<IdlInstructionAccount>,
+    /// The kind of account accepted any number of times after `accounts`,
+    /// declared with `Remaining<..>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining_accounts: Option<IdlInstructionAccount>,
     pub args: Vec<IdlInstructionArg>,
     pub discriminant: IdlInstructionDiscriminant,
 }
@@ -22,6 +26,7 @@ impl IdlInstruction {
         Self {
             name: name.to_string(),
             accounts,
+            remaining_accounts: None,
             args,
             discriminant,
         }
@@ -35,9 +40,16 @@ pub struct IdlInstructionAccount {
     pub name: String,
     pub is_mut: bool,
     pub is_signer: bool,
+    /// Whether the account can be omitted by passing the program ID in its
+    /// place.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_optional: bool,
     #[serde(rename = "type")]
     pub account_type: String,
     pub desc: String,
+    /// The account's fixed address, for accounts such as sysvars.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
 }
 
 impl IdlInstructionAccount {
@@ -52,8 +64,10 @@ impl IdlInstructionAccount {
             name,
             is_mut,
             is_signer,
+            is_optional: false,
             account_type,
             desc,
+            address: None,
         }
     }
 }
@@ -72,19 +86,43 @@ impl IdlInstructionArg {
     }
 }
 
+/// An instruction's discriminant: either a single byte or, for programs using
+/// hashed discriminators, the first 8 bytes of a hash of the instruction name.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct IdlInstructionDiscriminant {
     #[serde(rename = "type")]
     pub discriminant_type: IdlType,
-    pub value: u8,
+    pub value: IdlInstructionDiscriminantValue,
 }
 
 impl IdlInstructionDiscriminant {
     pub fn new(value: u8) -> Self {
         Self {
             discriminant_type: IdlType::U8,
-            value,
+            value: IdlInstructionDiscriminantValue::U8(value),
         }
     }
+
+    pub fn new_hashed(value: [u8; 8]) -> Self {
+        Self {
+            discriminant_type: IdlType::Array(Box::new(IdlType::U8), 8),
+            value: IdlInstructionDiscriminantValue::Hashed(value),
+        }
+    }
+
+    /// The bytes an instruction's data must be prefixed with.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self.value {
+            IdlInstructionDiscriminantValue::U8(value) => vec![value],
+            IdlInstructionDiscriminantValue::Hashed(value) => value.to_vec(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum IdlInstructionDiscriminantValue {
+    U8(u8),
+    Hashed([u8; 8]),
 }
@@ -24,13 +24,59 @@ pub struct IdlTypeDefNautilusConfig {
     pub table_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub primary_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub primary_keys: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub autoincrement: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u8>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub table_counter: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub zero_copy: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub space: Option<usize>,
     pub authorities: Vec<String>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub default_instructions: Vec<IdlTypeDefNautilusConfigDefaultInstruction>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub seeds: Vec<IdlSeed>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lookups: Vec<IdlTypeDefNautilusConfigLookup>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub foreign_keys: Vec<IdlTypeDefNautilusConfigForeignKey>,
+}
+
+impl IdlTypeDefNautilusConfig {
+    /// The fields making up a table's primary key, in the order they're used
+    /// as seeds for the record's program-derived address.
+    ///
+    /// Falls back to the single `primary_key` for IDLs that don't list
+    /// `primary_keys`.
+    pub fn primary_key_fields(&self) -> Vec<String> {
+        match self.primary_keys.is_empty() {
+            true => self.primary_key.iter().cloned().collect(),
+            false => self.primary_keys.clone(),
+        }
+    }
+}
+
+/// A secondary index declared on a table's field with `#[index]` or
+/// `#[unique]`, which clients can use to derive the field's lookup account.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlTypeDefNautilusConfigLookup {
+    pub field: String,
+    pub unique: bool,
+}
+
+/// A relation declared on a table's field with `#[foreign_key(..)]`, pointing
+/// to the primary key of a record in the referenced table.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlTypeDefNautilusConfigForeignKey {
+    pub field: String,
+    pub table: String,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -31,80 +31,95 @@ pub enum IdlType {
     BTreeSet(Box<IdlType>),
 }
 
-impl From<&syn::Type> for IdlType {
-    fn from(value: &syn::Type) -> Self {
+impl TryFrom<&syn::Type> for IdlType {
+    type Error = syn::Error;
+
+    fn try_from(value: &syn::Type) -> syn::Result<Self> {
         match value {
             syn::Type::Path(type_path) => {
-                let ident = &type_path.path.segments.last().unwrap().ident;
-                let arguments = &type_path.path.segments.last().unwrap().arguments;
+                let segment = match type_path.path.segments.last() {
+                    Some(segment) => segment,
+                    None => return Err(syn::Error::new_spanned(type_path, "Expected a type.")),
+                };
+                let ident = &segment.ident;
                 match ident.to_string().as_str() {
                     "Vec" => {
-                        if let syn::PathArguments::AngleBracketed(args) = arguments {
-                            if args.args.len() == 1 {
-                                let inner_type = args.args.first().unwrap();
-                                if let syn::GenericArgument::Type(inner_type) = inner_type {
-                                    return IdlType::Vec(Box::new(Self::from(inner_type)));
+                        if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                            if let Some(syn::GenericArgument::Type(inner_type)) = args.args.first()
+                            {
+                                if args.args.len() == 1 {
+                                    return Ok(IdlType::Vec(Box::new(Self::try_from(inner_type)?)));
+                                }
+                            }
+                        }
+                        Err(syn::Error::new_spanned(type_path, "Expected Vec<T>."))
+                    }
+                    "Option" => {
+                        if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                            if let Some(syn::GenericArgument::Type(inner_type)) = args.args.first()
+                            {
+                                if args.args.len() == 1 {
+                                    return Ok(IdlType::Option(Box::new(Self::try_from(
+                                        inner_type,
+                                    )?)));
                                 }
                             }
                         }
-                        panic!("Expected Vec<T>.");
+                        Err(syn::Error::new_spanned(type_path, "Expected Option<T>."))
                     }
-                    "bool" => IdlType::Bool,
-                    "u8" => IdlType::U8,
-                    "u16" => IdlType::U16,
-                    "u32" => IdlType::U32,
-                    "u64" => IdlType::U64,
-                    "u128" => IdlType::U128,
-                    "i8" => IdlType::I8,
-                    "i16" => IdlType::I16,
-                    "i32" => IdlType::I32,
-                    "i64" => IdlType::I64,
-                    "i128" => IdlType::I128,
-                    "String" => IdlType::String,
-                    "Pubkey" => IdlType::PublicKey,
-                    "Bytes" => IdlType::Bytes,
-                    _ => IdlType::Defined(ident.to_string()),
+                    "bool" => Ok(IdlType::Bool),
+                    "u8" => Ok(IdlType::U8),
+                    "u16" => Ok(IdlType::U16),
+                    "u32" => Ok(IdlType::U32),
+                    "u64" => Ok(IdlType::U64),
+                    "u128" => Ok(IdlType::U128),
+                    "i8" => Ok(IdlType::I8),
+                    "i16" => Ok(IdlType::I16),
+                    "i32" => Ok(IdlType::I32),
+                    "i64" => Ok(IdlType::I64),
+                    "i128" => Ok(IdlType::I128),
+                    "String" => Ok(IdlType::String),
+                    "Pubkey" => Ok(IdlType::PublicKey),
+                    "Bytes" => Ok(IdlType::Bytes),
+                    _ => Ok(IdlType::Defined(ident.to_string())),
                 }
             }
             syn::Type::Array(array_type) => {
                 let size = match &array_type.len {
-                    syn::Expr::Lit(lit) => {
-                        if let syn::Lit::Int(int_lit) = &lit.lit {
-                            int_lit.base10_parse().unwrap()
-                        } else {
-                            panic!("Expected array length as an integer literal.");
-                        }
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Int(int_lit),
+                        ..
+                    }) => int_lit.base10_parse()?,
+                    len => {
+                        return Err(syn::Error::new_spanned(
+                            len,
+                            "Expected array length as an integer literal.",
+                        ))
                     }
-                    _ => panic!("Expected array length as an integer literal."),
                 };
-                IdlType::Array(Box::new(Self::from(&*array_type.elem)), size)
+                Ok(IdlType::Array(
+                    Box::new(Self::try_from(&*array_type.elem)?),
+                    size,
+                ))
             }
-            syn::Type::Tuple(tuple_type) => {
-                let types = tuple_type
+            syn::Type::Tuple(tuple_type) => Ok(IdlType::Tuple(
+                tuple_type
                     .elems
                     .iter()
-                    .map(|elem| Self::from(elem))
-                    .collect();
-                IdlType::Tuple(types)
-            }
-            syn::Type::Reference(type_reference) => {
-                if let syn::Type::Slice(slice_type) = &*type_reference.elem {
-                    if let syn::Type::Path(ref_type_path) = &*slice_type.elem {
-                        let ident = &ref_type_path.path.segments.last().unwrap().ident;
-                        if ident == "u8" {
-                            IdlType::Bytes
-                        } else {
-                            panic!("Expected &[u8].");
-                        }
-                    } else {
-                        panic!("Expected &[u8].");
+                    .map(Self::try_from)
+                    .collect::<syn::Result<Vec<IdlType>>>()?,
+            )),
+            syn::Type::Reference(type_reference) => match &*type_reference.elem {
+                syn::Type::Slice(slice_type) => match &*slice_type.elem {
+                    syn::Type::Path(ref_type_path) if ref_type_path.path.is_ident("u8") => {
+                        Ok(IdlType::Bytes)
                     }
-                } else {
-                    Self::from(&*type_reference.elem)
-                }
-            }
-            syn::Type::Paren(paren_type) => Self::from(&*paren_type.elem),
-            _ => panic!("Unsupported type."),
+                    _ => Err(syn::Error::new_spanned(slice_type, "Expected &[u8].")),
+                },
+                elem => Self::try_from(elem),
+            },
+            syn::Type::Paren(paren_type) => Self::try_from(&*paren_type.elem),
+            _ => Err(syn::Error::new_spanned(value, "Unsupported type.")),
         }
     }
 }
@@ -27,27 +27,39 @@ impl IdlTypeDef {
     }
 }
 
-impl From<&syn::ItemStruct> for IdlTypeDef {
-    fn from(value: &syn::ItemStruct) -> Self {
-        Self {
+impl TryFrom<&syn::ItemStruct> for IdlTypeDef {
+    type Error = syn::Error;
+
+    fn try_from(value: &syn::ItemStruct) -> syn::Result<Self> {
+        Ok(Self {
             name: value.ident.to_string(),
             idl_type: IdlTypeDefType::Struct {
-                fields: value.fields.iter().map(|f| f.into()).collect(),
+                fields: value
+                    .fields
+                    .iter()
+                    .map(IdlTypeStructField::try_from)
+                    .collect::<syn::Result<Vec<IdlTypeStructField>>>()?,
             },
             config: None,
-        }
+        })
     }
 }
 
-impl From<&syn::ItemEnum> for IdlTypeDef {
-    fn from(value: &syn::ItemEnum) -> Self {
-        Self {
+impl TryFrom<&syn::ItemEnum> for IdlTypeDef {
+    type Error = syn::Error;
+
+    fn try_from(value: &syn::ItemEnum) -> syn::Result<Self> {
+        Ok(Self {
             name: value.ident.to_string(),
             idl_type: IdlTypeDefType::Enum {
-                variants: value.variants.iter().map(|v| v.into()).collect(),
+                variants: value
+                    .variants
+                    .iter()
+                    .map(IdlTypeEnumVariant::try_from)
+                    .collect::<syn::Result<Vec<IdlTypeEnumVariant>>>()?,
             },
             config: None,
-        }
+        })
     }
 }
 
@@ -75,17 +87,18 @@ impl IdlTypeStructField {
     }
 }
 
-impl From<&syn::Field> for IdlTypeStructField {
-    fn from(value: &syn::Field) -> Self {
+impl TryFrom<&syn::Field> for IdlTypeStructField {
+    type Error = syn::Error;
+
+    fn try_from(value: &syn::Field) -> syn::Result<Self> {
         let name = match &value.ident {
             Some(ident) => ident.to_string(),
-            None => panic!("Expected named field."),
+            None => return Err(syn::Error::new_spanned(value, "Expected named field.")),
         };
-        let ty = &value.ty;
-        Self {
+        Ok(Self {
             name,
-            field_data_type: ty.into(),
-        }
+            field_data_type: IdlType::try_from(&value.ty)?,
+        })
     }
 }
 
@@ -102,23 +115,30 @@ pub enum IdlTypeEnumFields {
     Named(Vec<IdlTypeStructField>),
 }
 
-impl From<&syn::Variant> for IdlTypeEnumVariant {
-    fn from(value: &syn::Variant) -> Self {
+impl TryFrom<&syn::Variant> for IdlTypeEnumVariant {
+    type Error = syn::Error;
+
+    fn try_from(value: &syn::Variant) -> syn::Result<Self> {
         let fields = match &value.fields {
             syn::Fields::Named(named_fields) => {
                 let fields = named_fields
                     .named
                     .iter()
-                    .map(|field| field.into())
-                    .collect();
+                    .map(IdlTypeStructField::try_from)
+                    .collect::<syn::Result<Vec<IdlTypeStructField>>>()?;
                 Some(IdlTypeEnumFields::Named(fields))
             }
             syn::Fields::Unit => None,
-            syn::Fields::Unnamed(_) => panic!("Expected named fields in enum variant."),
+            syn::Fields::Unnamed(unnamed_fields) => {
+                return Err(syn::Error::new_spanned(
+                    unnamed_fields,
+                    "Expected named fields in enum variant.",
+                ))
+            }
         };
-        Self {
+        Ok(Self {
             name: value.ident.to_string(),
             fields,
-        }
+        })
     }
 }
@@ -23,9 +23,13 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use self::{idl_instruction::IdlInstruction, idl_metadata::IdlMetadata, idl_type_def::IdlTypeDef};
+use self::{
+    idl_error::IdlErrorCode, idl_instruction::IdlInstruction, idl_metadata::IdlMetadata,
+    idl_type_def::IdlTypeDef,
+};
 
 pub mod converters;
+pub mod idl_error;
 pub mod idl_instruction;
 pub mod idl_metadata;
 pub mod idl_nautilus_config;
@@ -39,12 +43,14 @@ pub mod util;
 pub struct Idl {
     pub version: String,
     pub name: String,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub instructions: Vec<IdlInstruction>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub accounts: Vec<IdlTypeDef>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub types: Vec<IdlTypeDef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<IdlErrorCode>,
     pub metadata: IdlMetadata,
 }
 
@@ -55,6 +61,7 @@ impl Idl {
         instructions: Vec<IdlInstruction>,
         accounts: Vec<IdlTypeDef>,
         types: Vec<IdlTypeDef>,
+        errors: Vec<IdlErrorCode>,
         metadata: IdlMetadata,
     ) -> Self {
         Self {
@@ -63,15 +70,22 @@ impl Idl {
             instructions,
             accounts,
             types,
+            errors,
             metadata,
         }
     }
 
+    /// Reads an IDL from a JSON file, such as one written by `write_to_json`.
+    pub fn load_from_json(path: &str) -> std::io::Result<Self> {
+        let json_string = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json_string)?)
+    }
+
     pub fn write_to_json(&self, dir_path: &str) -> std::io::Result<()> {
         if dir_path != "." {
             fs::create_dir_all(dir_path)?;
         }
-        let idl_path = Path::join(Path::new(dir_path), &format!("{}.json", &self.name));
+        let idl_path = Path::join(Path::new(dir_path), format!("{}.json", &self.name));
         let mut file = File::create(idl_path)?;
         let json_string = serde_json::to_string(&self)?;
         file.write_all(json_string.as_bytes())?;
@@ -1,4 +1,13 @@
-use super::{idl_metadata::IdlMetadata, Idl};
+use convert_case::{Case, Casing};
+use sha2::{Digest, Sha256};
+
+use super::{
+    idl_metadata::IdlMetadata,
+    idl_nautilus_config::IdlTypeDefNautilusConfig,
+    idl_type::IdlType,
+    idl_type_def::{IdlTypeDef, IdlTypeStructField},
+    Idl,
+};
 
 pub fn load_idl_from_json(idl_path: &str) -> std::io::Result<Idl> {
     let file = std::fs::File::open(idl_path)?;
@@ -12,3 +21,49 @@ pub fn update_program_id(idl_path: &str, program_id: &str) -> std::io::Result<()
     idl.write_to_json(idl_path)?;
     Ok(())
 }
+
+/// Computes the 8-byte discriminator Nautilus prefixes to an account's data,
+/// so client bindings can embed it directly.
+pub fn account_discriminator(discrim_str: &str) -> [u8; 8] {
+    sighash(discrim_str)
+}
+
+/// Computes the 8-byte discriminator of an instruction for programs using
+/// hashed instruction discriminators, from the name of its function.
+pub fn instruction_discriminator(function_name: &str) -> [u8; 8] {
+    sighash(function_name)
+}
+
+/// The string an account type's discriminator is derived from: its table's
+/// name for a `Table` record, its declared discriminator string for a `State`
+/// account, and its snake case name otherwise.
+///
+/// A versioned account type's discriminator is derived from that string
+/// suffixed with `:versioned`, matching the program.
+pub fn account_discrim_str(type_def: &IdlTypeDef, config: &IdlTypeDefNautilusConfig) -> String {
+    let discrim_str = match (&config.table_name, &config.discrminator_str) {
+        (Some(table_name), _) => table_name.clone(),
+        (None, Some(discrim_str)) => discrim_str.clone(),
+        (None, None) => type_def.name.to_case(Case::Snake),
+    };
+    match config.version {
+        Some(_) => format!("{}:versioned", discrim_str),
+        None => discrim_str,
+    }
+}
+
+/// Looks up the type of a struct field by name, defaulting to raw bytes if the
+/// field can't be found.
+pub fn struct_field_type(fields: &[IdlTypeStructField], name: &str) -> IdlType {
+    fields
+        .iter()
+        .find(|f| f.name == name)
+        .map_or(IdlType::Bytes, |f| f.field_data_type.clone())
+}
+
+fn sighash(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    let preimage = format!("{}:{}", "global", name);
+    discriminator.copy_from_slice(&Sha256::digest(preimage.as_bytes())[..8]);
+    discriminator
+}
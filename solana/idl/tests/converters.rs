@@ -0,0 +1,440 @@
+//! Snapshot tests for the IDL converters, run against the fixture IDL in
+//! `tests/fixtures`.
+//!
+//! Set `NAUTILUS_BLESS=1` to rewrite the golden files after an intended change
+//! to a converter's output.
+use std::{fs, path::PathBuf};
+
+use nautilus_idl::{
+    converters::{
+        anchor::{AnchorConverter, AnchorIdl},
+        py::PythonConverter,
+        rust::RustConverter,
+        ts::TypeScriptConverter,
+    },
+    Idl,
+};
+use sha2::{Digest, Sha256};
+
+fn fixture_path(file_name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(file_name)
+}
+
+fn fixture_idl() -> Idl {
+    Idl::load_from_json(fixture_path("registry.json").to_str().unwrap()).unwrap()
+}
+
+/// Compares a converter's output with its golden file.
+fn assert_golden(file_name: &str, actual: &str) {
+    let path = fixture_path(file_name);
+    if std::env::var_os("NAUTILUS_BLESS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap();
+    assert!(
+        expected == actual,
+        "Generated output doesn't match `tests/fixtures/{}`. Rerun with `NAUTILUS_BLESS=1` if the change is intended.\n\n{}",
+        file_name,
+        actual
+    );
+}
+
+/// Asserts each snippet appears in the output, in the order given.
+fn assert_in_order(output: &str, snippets: &[&str]) {
+    let mut rest = output;
+    for snippet in snippets {
+        match rest.find(snippet) {
+            Some(i) => rest = &rest[i + snippet.len()..],
+            None => panic!("Expected `{}` in order in:\n\n{}", snippet, output),
+        }
+    }
+}
+
+/// The discriminator the program prefixes to an account's data, as a
+/// comma-separated byte list.
+fn discriminator_bytes(discrim_str: &str) -> String {
+    let hash = Sha256::digest(format!("global:{}", discrim_str).as_bytes());
+    hash[..8]
+        .iter()
+        .map(|b| b.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[test]
+fn rust_client_matches_golden() {
+    assert_golden("registry.rs", &fixture_idl().to_rust_string());
+}
+
+#[test]
+fn rust_instruction_data_is_discriminant_then_args() {
+    let rust = fixture_idl().to_rust_string();
+    assert_in_order(
+        &rust,
+        &[
+            "let mut data = vec![1];",
+            "BorshSerialize::serialize(&amount, &mut data)",
+            "BorshSerialize::serialize(&profile, &mut data)",
+            "BorshSerialize::serialize(&note, &mut data)",
+        ],
+    );
+    assert!(rust.contains("let data = vec![111, 92, 198, 47, 110, 51, 33, 13];"));
+}
+
+#[test]
+fn rust_account_metas_follow_idl_order() {
+    let rust = fixture_idl().to_rust_string();
+    assert_in_order(
+        &rust,
+        &[
+            "AccountMeta::new(accounts.index, false)",
+            "AccountMeta::new(accounts.member_counter, false)",
+            "AccountMeta::new(accounts.new_member, false)",
+            "AccountMeta::new_readonly(accounts.wallet, true)",
+            "AccountMeta::new(accounts.fee_payer, true)",
+            "AccountMeta::new_readonly(accounts.system_program, false)",
+            "AccountMeta::new_readonly(accounts.rent, false)",
+        ],
+    );
+    // Omitted optional accounts are passed as the program ID, and remaining
+    // accounts follow the instruction's own.
+    assert_in_order(
+        &rust,
+        &[
+            "AccountMeta::new_readonly(accounts.owner, true)",
+            "accounts.referrer.map_or(AccountMeta::new_readonly(*program_id, false)",
+            ".chain(remaining_accounts.iter().map(|key| AccountMeta::new(*key, false)))",
+        ],
+    );
+}
+
+#[test]
+fn rust_decoders_check_discriminator_and_version() {
+    let rust = fixture_idl().to_rust_string();
+    assert_in_order(
+        &rust,
+        &[
+            "impl Member {",
+            "discriminator(\"member\")",
+            "data[..8] != Self::discriminator()",
+            "Self::deserialize(&mut &data[8..])",
+        ],
+    );
+    assert_in_order(
+        &rust,
+        &[
+            "impl Ledger {",
+            "pub const VERSION: u8 = 2;",
+            "discriminator(\"ledger:versioned\")",
+            "data[8] != Self::VERSION",
+            "Self::deserialize(&mut &data[9..])",
+        ],
+    );
+}
+
+#[test]
+fn rust_pda_helpers_match_program_seeds() {
+    let rust = fixture_idl().to_rust_string();
+    assert!(rust.contains(
+        "pub fn pda(program_id: &Pubkey, id: &u32) -> (Pubkey, u8) {\n        \
+            let seeds_vec: Vec<Vec<u8>> = vec![b\"member\".to_vec(), id.to_le_bytes().to_vec()];"
+    ));
+    assert!(rust.contains(
+        "Pubkey::find_program_address(&[b\"nautilus_counter\", b\"member\"], program_id)"
+    ));
+    assert!(rust.contains(
+        "pub fn handle_lookup_pda(program_id: &Pubkey, handle: &String) -> (Pubkey, u8) {\n        \
+            let seeds_vec: Vec<Vec<u8>> = vec![b\"nautilus_lookup\".to_vec(), b\"member\".to_vec(), b\"handle\".to_vec(), \
+            solana_program::hash::hash(&handle.as_bytes().to_vec()).to_bytes().to_vec()];"
+    ));
+    assert!(rust.contains(
+        "pub fn pda(program_id: &Pubkey, owner: &Pubkey, epoch: &u64) -> (Pubkey, u8) {\n        \
+            let seeds_vec: Vec<Vec<u8>> = vec![b\"ledger\".to_vec(), owner.try_to_vec().unwrap(), epoch.try_to_vec().unwrap()];"
+    ));
+    assert!(rust.contains("Pubkey::find_program_address(&[b\"nautilus_index\", &[0]], program_id)"));
+}
+
+#[test]
+fn typescript_client_matches_golden() {
+    assert_golden("registry.ts", &fixture_idl().to_typescript_string());
+}
+
+#[test]
+fn typescript_instruction_data_is_discriminant_then_args() {
+    let ts = fixture_idl().to_typescript_string();
+    assert_in_order(
+        &ts,
+        &[
+            "w.u8(1)",
+            "w.u64(args.amount)",
+            "encodeProfile(w, args.profile)",
+            "w.option(args.note, (x0) => { w.string(x0) })",
+        ],
+    );
+    assert!(ts.contains("w.fixed(Uint8Array.from([111, 92, 198, 47, 110, 51, 33, 13]))"));
+}
+
+#[test]
+fn typescript_account_metas_follow_idl_order() {
+    let ts = fixture_idl().to_typescript_string();
+    assert_in_order(
+        &ts,
+        &[
+            "{ pubkey: accounts.index, isSigner: false, isWritable: true }",
+            "{ pubkey: accounts.memberCounter, isSigner: false, isWritable: true }",
+            "{ pubkey: accounts.newMember, isSigner: false, isWritable: true }",
+            "{ pubkey: accounts.wallet, isSigner: true, isWritable: false }",
+            "{ pubkey: accounts.feePayer, isSigner: true, isWritable: true }",
+            "{ pubkey: accounts.systemProgram, isSigner: false, isWritable: false }",
+            "{ pubkey: accounts.rent, isSigner: false, isWritable: false }",
+        ],
+    );
+    assert_in_order(
+        &ts,
+        &[
+            "{ pubkey: accounts.owner, isSigner: true, isWritable: false }",
+            ": { pubkey: programId, isSigner: false, isWritable: false }",
+            "...remainingAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))",
+        ],
+    );
+}
+
+#[test]
+fn typescript_deserializers_check_discriminator_and_version() {
+    let ts = fixture_idl().to_typescript_string();
+    assert!(ts.contains(&format!(
+        "export const MEMBER_DISCRIMINATOR = Buffer.from([{}])",
+        discriminator_bytes("member")
+    )));
+    assert!(ts.contains(&format!(
+        "export const LEDGER_DISCRIMINATOR = Buffer.from([{}])",
+        discriminator_bytes("ledger:versioned")
+    )));
+    assert_in_order(
+        &ts,
+        &[
+            "export function deserializeLedgerAccount(data: Buffer): Ledger {",
+            "!data.subarray(0, 8).equals(LEDGER_DISCRIMINATOR)",
+            "data[8] !== LEDGER_VERSION",
+            "decodeLedger(new BorshReader(data.subarray(9)))",
+        ],
+    );
+}
+
+#[test]
+fn typescript_pda_helpers_match_program_seeds() {
+    let ts = fixture_idl().to_typescript_string();
+    assert!(ts.contains("[Buffer.from(\"member\"), borshBytes((w) => { w.u32(id) })]"));
+    assert!(ts.contains("[Buffer.from(\"nautilus_counter\"), Buffer.from(\"member\")]"));
+    assert!(ts.contains(
+        "[Buffer.from(\"nautilus_lookup\"), Buffer.from(\"member\"), Buffer.from(\"handle\"), \
+        Buffer.from(sha256(Buffer.from(handle)))]"
+    ));
+    assert!(ts.contains(
+        "[Buffer.from(\"ledger\"), borshBytes((w) => { w.publicKey(owner) }), \
+        borshBytes((w) => { w.u64(epoch) })]"
+    ));
+}
+
+#[test]
+fn python_client_matches_golden() {
+    assert_golden("registry.py", &fixture_idl().to_python_string());
+}
+
+#[test]
+fn python_instruction_data_is_discriminant_then_args() {
+    let py = fixture_idl().to_python_string();
+    assert!(py.contains(
+        "SettleArgsLayout = borsh.CStruct(\"discriminant\" / borsh.U8, \"amount\" / borsh.U64, \
+        \"profile\" / ProfileLayout, \"note\" / borsh.Option(borsh.String))"
+    ));
+    assert!(py.contains(
+        "data = SettleArgsLayout.build({\"discriminant\": 1, \"amount\": amount, \
+        \"profile\": profile, \"note\": note})"
+    ));
+    assert!(py.contains("CloseLedgerArgsLayout = borsh.CStruct(\"discriminant\" / Bytes(8))"));
+    assert!(py.contains("{\"discriminant\": bytes([111, 92, 198, 47, 110, 51, 33, 13])}"));
+}
+
+#[test]
+fn python_account_metas_follow_idl_order() {
+    let py = fixture_idl().to_python_string();
+    assert_in_order(
+        &py,
+        &[
+            "AccountMeta(pubkey=accounts.index, is_signer=False, is_writable=True)",
+            "AccountMeta(pubkey=accounts.member_counter, is_signer=False, is_writable=True)",
+            "AccountMeta(pubkey=accounts.new_member, is_signer=False, is_writable=True)",
+            "AccountMeta(pubkey=accounts.wallet, is_signer=True, is_writable=False)",
+            "AccountMeta(pubkey=accounts.fee_payer, is_signer=True, is_writable=True)",
+            "AccountMeta(pubkey=accounts.system_program, is_signer=False, is_writable=False)",
+            "AccountMeta(pubkey=accounts.rent, is_signer=False, is_writable=False)",
+        ],
+    );
+    assert_in_order(
+        &py,
+        &[
+            "AccountMeta(pubkey=accounts.owner, is_signer=True, is_writable=False)",
+            "else AccountMeta(pubkey=program_id, is_signer=False, is_writable=False)",
+            "keys += [AccountMeta(pubkey=pubkey, is_signer=False, is_writable=True) \
+            for pubkey in remaining_accounts or []]",
+        ],
+    );
+}
+
+#[test]
+fn python_decoders_check_discriminator_and_version() {
+    let py = fixture_idl().to_python_string();
+    assert!(py.contains(&format!(
+        "MEMBER_DISCRIMINATOR = bytes([{}])",
+        discriminator_bytes("member")
+    )));
+    assert!(py.contains(&format!(
+        "LEDGER_DISCRIMINATOR = bytes([{}])",
+        discriminator_bytes("ledger:versioned")
+    )));
+    assert_in_order(
+        &py,
+        &[
+            "def decode_ledger_account(data: bytes) -> Ledger:",
+            "if data[:8] != LEDGER_DISCRIMINATOR:",
+            "if data[8] != LEDGER_VERSION:",
+            "return LedgerLayout.parse(data[9:])",
+        ],
+    );
+}
+
+#[test]
+fn python_pda_helpers_match_program_seeds() {
+    let py = fixture_idl().to_python_string();
+    assert!(py.contains("[b\"member\", borsh.U32.build(id)]"));
+    assert!(py.contains("[b\"nautilus_counter\", b\"member\"]"));
+    assert!(py.contains(
+        "[b\"nautilus_lookup\", b\"member\", b\"handle\", hashlib.sha256(handle.encode()).digest()]"
+    ));
+    assert!(py.contains("[b\"ledger\", BorshPubkey.build(owner), borsh.U64.build(epoch)]"));
+}
+
+#[test]
+fn python_types_are_declared_before_use() {
+    let py = fixture_idl().to_python_string();
+    assert_in_order(&py, &["StatusLayout = ", "LedgerLayout = "]);
+    assert_in_order(&py, &["ProfileLayout = ", "SettleArgsLayout = "]);
+}
+
+#[test]
+fn anchor_idl_matches_golden() {
+    let anchor = serde_json::to_string_pretty(&fixture_idl().to_anchor_idl()).unwrap();
+    assert_golden("registry.anchor.json", &format!("{}\n", anchor));
+}
+
+#[test]
+fn anchor_idl_round_trips_through_json() {
+    let anchor = fixture_idl().to_anchor_idl();
+    let json = serde_json::to_string(&anchor).unwrap();
+    let loaded: AnchorIdl = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+}
+
+#[test]
+fn anchor_instructions_keep_discriminators_and_account_order() {
+    let anchor = fixture_idl().to_anchor_idl();
+    let discriminators: Vec<Vec<u8>> = anchor
+        .instructions
+        .iter()
+        .map(|ix| ix.discriminator.clone())
+        .collect();
+    assert_eq!(
+        discriminators,
+        vec![vec![0], vec![1], vec![111, 92, 198, 47, 110, 51, 33, 13]]
+    );
+    let create_member = &anchor.instructions[0];
+    let accounts: Vec<(&str, bool, bool)> = create_member
+        .accounts
+        .iter()
+        .map(|a| (a.name.as_str(), a.writable, a.signer))
+        .collect();
+    assert_eq!(
+        accounts,
+        vec![
+            ("index", true, false),
+            ("member_counter", true, false),
+            ("new_member", true, false),
+            ("wallet", false, true),
+            ("fee_payer", true, true),
+            ("system_program", false, false),
+            ("rent", false, false),
+        ]
+    );
+    assert!(anchor.instructions[1].accounts[2].optional);
+}
+
+#[test]
+fn anchor_accounts_resolve_known_addresses_and_pdas() {
+    let anchor = fixture_idl().to_anchor_idl();
+    let accounts = &anchor.instructions[0].accounts;
+    assert_eq!(
+        accounts[5].address.as_deref(),
+        Some("11111111111111111111111111111111")
+    );
+    assert_eq!(
+        accounts[6].address.as_deref(),
+        Some("SysvarRent111111111111111111111111111111111")
+    );
+    assert_eq!(
+        accounts[0].pda.as_ref().unwrap()["seeds"][0]["value"],
+        serde_json::json!(b"nautilus_index".to_vec())
+    );
+    assert_eq!(
+        accounts[1].pda.as_ref().unwrap()["seeds"][1]["value"],
+        serde_json::json!(b"member".to_vec())
+    );
+}
+
+#[test]
+fn anchor_accounts_carry_program_discriminators() {
+    let anchor = fixture_idl().to_anchor_idl();
+    let discriminator = |name: &str| {
+        anchor
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| {
+                a.discriminator
+                    .iter()
+                    .map(|b| b.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            })
+            .unwrap()
+    };
+    assert_eq!(discriminator("Member"), discriminator_bytes("member"));
+    assert_eq!(
+        discriminator("Ledger"),
+        discriminator_bytes("ledger:versioned")
+    );
+    // A versioned account's version byte follows its discriminator.
+    let ledger = anchor.types.iter().find(|t| t.name == "Ledger").unwrap();
+    assert_eq!(ledger.ty["fields"][0]["name"], "schema_version");
+}
+
+#[test]
+fn anchor_extension_keeps_nautilus_only_data() {
+    let anchor = fixture_idl().to_anchor_idl();
+    let settle = &anchor.nautilus.instructions[1];
+    let remaining = settle.remaining_accounts.as_ref().unwrap();
+    assert_eq!(remaining.name, "payees");
+    assert!(remaining.is_mut);
+    assert_eq!(settle.accounts[0].account_type, "Ledger");
+    let configs: Vec<&str> = anchor
+        .nautilus
+        .accounts
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect();
+    assert_eq!(configs, vec!["Member", "Ledger"]);
+    assert_eq!(anchor.nautilus.accounts[1].config.seeds.len(), 3);
+}
@@ -0,0 +1,198 @@
+# Python bindings for the `registry` Nautilus program.
+#
+# Generated from the program's IDL.
+
+from __future__ import annotations
+
+import hashlib
+import typing
+from dataclasses import dataclass, fields
+
+import borsh_construct as borsh
+from construct import Adapter, Bytes
+from solders.instruction import AccountMeta, Instruction
+from solders.pubkey import Pubkey
+
+
+class _PubkeyAdapter(Adapter):
+    def _decode(self, obj, context, path) -> Pubkey:
+        return Pubkey.from_bytes(obj)
+
+    def _encode(self, obj, context, path) -> bytes:
+        return bytes(obj)
+
+
+class _DataclassAdapter(Adapter):
+    def __init__(self, cls, subcon):
+        super().__init__(subcon)
+        self.cls = cls
+
+    def _decode(self, obj, context, path):
+        return self.cls(**{f.name: obj[f.name] for f in fields(self.cls)})
+
+    def _encode(self, obj, context, path):
+        return {f.name: getattr(obj, f.name) for f in fields(self.cls)}
+
+
+BorshPubkey = _PubkeyAdapter(Bytes(32))
+
+
+def derive_nautilus_index_address(program_id: Pubkey) -> typing.Tuple[Pubkey, int]:
+    return Pubkey.find_program_address([b"nautilus_index", bytes([0])], program_id)
+
+
+StatusLayout = borsh.Enum(
+    "Open",
+    "Closed" / borsh.CStruct("reason" / borsh.String),
+    enum_name="Status",
+)
+Status = StatusLayout.enum
+
+
+@dataclass
+class Profile:
+    bio: str
+    tags: typing.List[str]
+
+
+ProfileLayout = _DataclassAdapter(Profile, borsh.CStruct("bio" / borsh.String, "tags" / borsh.Vec(borsh.String)))
+
+
+@dataclass
+class Member:
+    id: int
+    handle: str
+    wallet: Pubkey
+
+
+MemberLayout = _DataclassAdapter(Member, borsh.CStruct("id" / borsh.U32, "handle" / borsh.String, "wallet" / BorshPubkey))
+
+
+@dataclass
+class Ledger:
+    owner: Pubkey
+    balance: int
+    status: Status
+
+
+LedgerLayout = _DataclassAdapter(Ledger, borsh.CStruct("owner" / BorshPubkey, "balance" / borsh.U64, "status" / StatusLayout))
+
+
+MEMBER_DISCRIMINATOR = bytes([174, 2, 13, 241, 146, 22, 20, 59])
+
+
+def decode_member_account(data: bytes) -> Member:
+    if data[:8] != MEMBER_DISCRIMINATOR:
+        raise ValueError("Discriminator mismatch for account data: Member")
+    return MemberLayout.parse(data[8:])
+
+
+def derive_member_address(program_id: Pubkey, id: int) -> typing.Tuple[Pubkey, int]:
+    return Pubkey.find_program_address([b"member", borsh.U32.build(id)], program_id)
+
+
+def derive_member_counter_address(program_id: Pubkey) -> typing.Tuple[Pubkey, int]:
+    return Pubkey.find_program_address([b"nautilus_counter", b"member"], program_id)
+
+
+def derive_member_handle_lookup_address(program_id: Pubkey, handle: str) -> typing.Tuple[Pubkey, int]:
+    return Pubkey.find_program_address([b"nautilus_lookup", b"member", b"handle", hashlib.sha256(handle.encode()).digest()], program_id)
+
+
+LEDGER_DISCRIMINATOR = bytes([96, 243, 29, 140, 82, 7, 210, 191])
+LEDGER_VERSION = 2
+
+
+def decode_ledger_account(data: bytes) -> Ledger:
+    if data[:8] != LEDGER_DISCRIMINATOR:
+        raise ValueError("Discriminator mismatch for account data: Ledger")
+    if data[8] != LEDGER_VERSION:
+        raise ValueError(f"Unsupported Ledger version {data[8]}, the account must be migrated first")
+    return LedgerLayout.parse(data[9:])
+
+
+def derive_ledger_address(program_id: Pubkey, owner: Pubkey, epoch: int) -> typing.Tuple[Pubkey, int]:
+    return Pubkey.find_program_address([b"ledger", BorshPubkey.build(owner), borsh.U64.build(epoch)], program_id)
+
+
+@dataclass
+class CreateMemberAccounts:
+    index: Pubkey
+    member_counter: Pubkey
+    new_member: Pubkey
+    wallet: Pubkey
+    fee_payer: Pubkey
+    system_program: Pubkey
+    rent: Pubkey
+
+
+CreateMemberArgsLayout = borsh.CStruct("discriminant" / borsh.U8, "member" / MemberLayout)
+
+
+def create_member_instruction(program_id: Pubkey, accounts: CreateMemberAccounts, member: Member) -> Instruction:
+    data = CreateMemberArgsLayout.build({"discriminant": 0, "member": member})
+    keys = [
+        AccountMeta(pubkey=accounts.index, is_signer=False, is_writable=True),
+        AccountMeta(pubkey=accounts.member_counter, is_signer=False, is_writable=True),
+        AccountMeta(pubkey=accounts.new_member, is_signer=False, is_writable=True),
+        AccountMeta(pubkey=accounts.wallet, is_signer=True, is_writable=False),
+        AccountMeta(pubkey=accounts.fee_payer, is_signer=True, is_writable=True),
+        AccountMeta(pubkey=accounts.system_program, is_signer=False, is_writable=False),
+        AccountMeta(pubkey=accounts.rent, is_signer=False, is_writable=False),
+    ]
+    return Instruction(program_id, data, keys)
+
+
+@dataclass
+class SettleAccounts:
+    ledger: Pubkey
+    owner: Pubkey
+    referrer: typing.Optional[Pubkey]
+
+
+SettleArgsLayout = borsh.CStruct("discriminant" / borsh.U8, "amount" / borsh.U64, "profile" / ProfileLayout, "note" / borsh.Option(borsh.String))
+
+
+def settle_instruction(program_id: Pubkey, accounts: SettleAccounts, amount: int, profile: Profile, note: typing.Optional[str], remaining_accounts: typing.Optional[typing.List[Pubkey]] = None) -> Instruction:
+    data = SettleArgsLayout.build({"discriminant": 1, "amount": amount, "profile": profile, "note": note})
+    keys = [
+        AccountMeta(pubkey=accounts.ledger, is_signer=False, is_writable=True),
+        AccountMeta(pubkey=accounts.owner, is_signer=True, is_writable=False),
+        AccountMeta(pubkey=accounts.referrer, is_signer=False, is_writable=False) if accounts.referrer is not None else AccountMeta(pubkey=program_id, is_signer=False, is_writable=False),
+    ]
+    keys += [AccountMeta(pubkey=pubkey, is_signer=False, is_writable=True) for pubkey in remaining_accounts or []]
+    return Instruction(program_id, data, keys)
+
+
+@dataclass
+class CloseLedgerAccounts:
+    ledger: Pubkey
+    owner: Pubkey
+
+
+CloseLedgerArgsLayout = borsh.CStruct("discriminant" / Bytes(8))
+
+
+def close_ledger_instruction(program_id: Pubkey, accounts: CloseLedgerAccounts) -> Instruction:
+    data = CloseLedgerArgsLayout.build({"discriminant": bytes([111, 92, 198, 47, 110, 51, 33, 13])})
+    keys = [
+        AccountMeta(pubkey=accounts.ledger, is_signer=False, is_writable=True),
+        AccountMeta(pubkey=accounts.owner, is_signer=True, is_writable=True),
+    ]
+    return Instruction(program_id, data, keys)
+
+
+@dataclass
+class ProgramErrorCode:
+    code: int
+    name: str
+    msg: str
+
+
+PROGRAM_ERRORS: typing.List[ProgramErrorCode] = [
+    ProgramErrorCode(6000, "LedgerClosed", "The ledger is closed."),
+]
+
+
+def error_from_code(code: int) -> typing.Optional[ProgramErrorCode]:
+    return next((e for e in PROGRAM_ERRORS if e.code == code), None)
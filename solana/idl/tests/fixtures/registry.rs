@@ -0,0 +1,224 @@
+//! Rust client for the `registry` Nautilus program.
+//!
+//! Generated from the program's IDL.
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+/// Computes the 8-byte discriminator prefixed to the data of a Nautilus account.
+pub fn discriminator(discrim_str: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    let preimage = format!("global:{}", discrim_str);
+    discriminator
+        .copy_from_slice(&solana_program::hash::hash(preimage.as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+/// Derives the address of the program's Nautilus Index.
+pub fn nautilus_index_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"nautilus_index", &[0]], program_id)
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct Member {
+    pub id: u32,
+    pub handle: String,
+    pub wallet: Pubkey,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct Ledger {
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub status: Status,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub enum Status {
+    Open,
+    Closed { reason: String },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct Profile {
+    pub bio: String,
+    pub tags: Vec<String>,
+}
+
+impl Member {
+    /// The discriminator prefixed to the data of every `Member` account.
+    pub fn discriminator() -> [u8; 8] {
+        discriminator("member")
+    }
+
+    /// Decodes a `Member` from an account's data, checking its discriminator.
+    pub fn try_from_account_data(data: &[u8]) -> std::io::Result<Self> {
+        if data.len() < 8 || data[..8] != Self::discriminator() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Discriminator mismatch for account data: Member",
+            ));
+        }
+        Self::deserialize(&mut &data[8..])
+    }
+
+    /// Derives the address of a `Member` account from its seeds.
+    pub fn pda(program_id: &Pubkey, id: &u32) -> (Pubkey, u8) {
+        let seeds_vec: Vec<Vec<u8>> = vec![b"member".to_vec(), id.to_le_bytes().to_vec()];
+        let seeds: Vec<&[u8]> = seeds_vec.iter().map(AsRef::as_ref).collect();
+        Pubkey::find_program_address(&seeds, program_id)
+    }
+
+    /// Derives the address of the record counter for the `Member` table.
+    pub fn counter_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"nautilus_counter", b"member"], program_id)
+    }
+
+    /// Derives the address of the lookup account for a `Member`'s `handle`.
+    pub fn handle_lookup_pda(program_id: &Pubkey, handle: &String) -> (Pubkey, u8) {
+        let seeds_vec: Vec<Vec<u8>> = vec![b"nautilus_lookup".to_vec(), b"member".to_vec(), b"handle".to_vec(), solana_program::hash::hash(&handle.as_bytes().to_vec()).to_bytes().to_vec()];
+        let seeds: Vec<&[u8]> = seeds_vec.iter().map(AsRef::as_ref).collect();
+        Pubkey::find_program_address(&seeds, program_id)
+    }
+}
+
+impl Ledger {
+    /// The schema version of `Ledger` account data this client decodes.
+    pub const VERSION: u8 = 2;
+
+    /// The discriminator prefixed to the data of every `Ledger` account.
+    pub fn discriminator() -> [u8; 8] {
+        discriminator("ledger:versioned")
+    }
+
+    /// Decodes a `Ledger` from an account's data, checking its discriminator.
+    pub fn try_from_account_data(data: &[u8]) -> std::io::Result<Self> {
+        if data.len() < 9 || data[..8] != Self::discriminator() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Discriminator mismatch for account data: Ledger",
+            ));
+        }
+        if data[8] != Self::VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Unsupported Ledger version {}, the account must be migrated first", data[8]),
+            ));
+        }
+        Self::deserialize(&mut &data[9..])
+    }
+
+    /// Derives the address of a `Ledger` account from its seeds.
+    pub fn pda(program_id: &Pubkey, owner: &Pubkey, epoch: &u64) -> (Pubkey, u8) {
+        let seeds_vec: Vec<Vec<u8>> = vec![b"ledger".to_vec(), owner.try_to_vec().unwrap(), epoch.try_to_vec().unwrap()];
+        let seeds: Vec<&[u8]> = seeds_vec.iter().map(AsRef::as_ref).collect();
+        Pubkey::find_program_address(&seeds, program_id)
+    }
+}
+
+/// Accounts required by the `createMember` instruction.
+#[derive(Clone, Debug)]
+pub struct CreateMemberAccounts {
+    /// The Nautilus Index for this program
+    pub index: Pubkey,
+    /// The record counter for the member table
+    pub member_counter: Pubkey,
+    /// The member to create
+    pub new_member: Pubkey,
+    /// The member's wallet
+    pub wallet: Pubkey,
+    /// The fee payer
+    pub fee_payer: Pubkey,
+    /// The System Program
+    pub system_program: Pubkey,
+    /// The Sysvar: Rent
+    pub rent: Pubkey,
+}
+
+/// Builds the `createMember` instruction.
+pub fn create_member(
+    program_id: &Pubkey,
+    accounts: &CreateMemberAccounts,
+    member: Member,
+) -> Instruction {
+    let mut data = vec![0];
+    BorshSerialize::serialize(&member, &mut data).unwrap();
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(accounts.index, false),
+            AccountMeta::new(accounts.member_counter, false),
+            AccountMeta::new(accounts.new_member, false),
+            AccountMeta::new_readonly(accounts.wallet, true),
+            AccountMeta::new(accounts.fee_payer, true),
+            AccountMeta::new_readonly(accounts.system_program, false),
+            AccountMeta::new_readonly(accounts.rent, false),
+        ],
+        data,
+    }
+}
+
+/// Accounts required by the `settle` instruction.
+#[derive(Clone, Debug)]
+pub struct SettleAccounts {
+    /// The ledger to settle
+    pub ledger: Pubkey,
+    /// The ledger's owner
+    pub owner: Pubkey,
+    /// The referrer, if any
+    pub referrer: Option<Pubkey>,
+}
+
+/// Builds the `settle` instruction.
+pub fn settle(
+    program_id: &Pubkey,
+    accounts: &SettleAccounts,
+    amount: u64,
+    profile: Profile,
+    note: Option<String>,
+    remaining_accounts: &[Pubkey],
+) -> Instruction {
+    let mut data = vec![1];
+    BorshSerialize::serialize(&amount, &mut data).unwrap();
+    BorshSerialize::serialize(&profile, &mut data).unwrap();
+    BorshSerialize::serialize(&note, &mut data).unwrap();
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(accounts.ledger, false),
+            AccountMeta::new_readonly(accounts.owner, true),
+            accounts.referrer.map_or(AccountMeta::new_readonly(*program_id, false), |key| AccountMeta::new_readonly(key, false)),
+        ]
+            .into_iter()
+            .chain(remaining_accounts.iter().map(|key| AccountMeta::new(*key, false)))
+            .collect(),
+        data,
+    }
+}
+
+/// Accounts required by the `closeLedger` instruction.
+#[derive(Clone, Debug)]
+pub struct CloseLedgerAccounts {
+    /// The ledger to close
+    pub ledger: Pubkey,
+    /// The ledger's owner
+    pub owner: Pubkey,
+}
+
+/// Builds the `closeLedger` instruction.
+pub fn close_ledger(
+    program_id: &Pubkey,
+    accounts: &CloseLedgerAccounts,
+) -> Instruction {
+    let data = vec![111, 92, 198, 47, 110, 51, 33, 13];
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(accounts.ledger, false),
+            AccountMeta::new(accounts.owner, true),
+        ],
+        data,
+    }
+}
@@ -0,0 +1,340 @@
+// TypeScript bindings for the `registry` Nautilus program.
+//
+// Generated from the program's IDL.
+import { sha256 } from "@noble/hashes/sha256"
+import { Buffer } from "buffer"
+import { PublicKey, TransactionInstruction } from "@solana/web3.js"
+
+export class BorshWriter {
+    private chunks: Buffer[] = []
+    private le(value: number | bigint, bytes: number, signed: boolean) {
+        let v = BigInt(value)
+        if (signed && v < BigInt(0)) v += BigInt(1) << BigInt(bytes * 8)
+        const b = Buffer.alloc(bytes)
+        for (let i = 0; i < bytes; i++) {
+            b[i] = Number(v & BigInt(0xff))
+            v >>= BigInt(8)
+        }
+        this.chunks.push(b)
+    }
+    u8(v: number) { this.le(v, 1, false) }
+    u16(v: number) { this.le(v, 2, false) }
+    u32(v: number) { this.le(v, 4, false) }
+    u64(v: bigint | number) { this.le(v, 8, false) }
+    u128(v: bigint | number) { this.le(v, 16, false) }
+    i8(v: number) { this.le(v, 1, true) }
+    i16(v: number) { this.le(v, 2, true) }
+    i32(v: number) { this.le(v, 4, true) }
+    i64(v: bigint | number) { this.le(v, 8, true) }
+    i128(v: bigint | number) { this.le(v, 16, true) }
+    bool(v: boolean) { this.u8(v ? 1 : 0) }
+    fixed(v: Uint8Array) { this.chunks.push(Buffer.from(v)) }
+    bytes(v: Uint8Array) { this.u32(v.length); this.fixed(v) }
+    string(v: string) { this.bytes(Buffer.from(v, "utf8")) }
+    publicKey(v: PublicKey) { this.fixed(v.toBuffer()) }
+    option<T>(v: T | null, f: (x: T) => void) {
+        if (v === null || v === undefined) { this.u8(0) } else { this.u8(1); f(v) }
+    }
+    array<T>(v: T[], f: (x: T) => void) { v.forEach(f) }
+    vec<T>(v: T[], f: (x: T) => void) { this.u32(v.length); v.forEach(f) }
+    set<T>(v: Set<T>, f: (x: T) => void) { this.vec(Array.from(v), f) }
+    map<K, V>(v: Map<K, V>, fk: (k: K) => void, fv: (v: V) => void) {
+        this.u32(v.size)
+        v.forEach((value, key) => { fk(key); fv(value) })
+    }
+    toBuffer(): Buffer { return Buffer.concat(this.chunks) }
+}
+
+export class BorshReader {
+    private offset = 0
+    constructor(private buf: Buffer) {}
+    private le(bytes: number, signed: boolean): bigint {
+        let v = BigInt(0)
+        for (let i = bytes - 1; i >= 0; i--) v = (v << BigInt(8)) | BigInt(this.buf[this.offset + i])
+        this.offset += bytes
+        if (signed && v >= BigInt(1) << BigInt(bytes * 8 - 1)) v -= BigInt(1) << BigInt(bytes * 8)
+        return v
+    }
+    u8(): number { return Number(this.le(1, false)) }
+    u16(): number { return Number(this.le(2, false)) }
+    u32(): number { return Number(this.le(4, false)) }
+    u64(): bigint { return this.le(8, false) }
+    u128(): bigint { return this.le(16, false) }
+    i8(): number { return Number(this.le(1, true)) }
+    i16(): number { return Number(this.le(2, true)) }
+    i32(): number { return Number(this.le(4, true)) }
+    i64(): bigint { return this.le(8, true) }
+    i128(): bigint { return this.le(16, true) }
+    bool(): boolean { return this.u8() !== 0 }
+    fixed(length: number): Buffer {
+        const b = this.buf.subarray(this.offset, this.offset + length)
+        this.offset += length
+        return Buffer.from(b)
+    }
+    bytes(): Buffer { return this.fixed(this.u32()) }
+    string(): string { return this.bytes().toString("utf8") }
+    publicKey(): PublicKey { return new PublicKey(this.fixed(32)) }
+    option<T>(f: () => T): T | null { return this.u8() === 0 ? null : f() }
+    array<T>(length: number, f: () => T): T[] { return Array.from({ length }, f) }
+    vec<T>(f: () => T): T[] { return this.array(this.u32(), f) }
+    set<T>(f: () => T): Set<T> { return new Set(this.vec(f)) }
+    map<K, V>(fk: () => K, fv: () => V): Map<K, V> {
+        const m = new Map<K, V>()
+        const length = this.u32()
+        for (let i = 0; i < length; i++) { const k = fk(); m.set(k, fv()) }
+        return m
+    }
+}
+
+function borshBytes(f: (w: BorshWriter) => void): Buffer {
+    const w = new BorshWriter()
+    f(w)
+    return w.toBuffer()
+}
+
+export function deriveNautilusIndexAddress(programId: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [Buffer.from("nautilus_index"), Buffer.from([0])],
+        programId,
+    )
+}
+
+export type Member = {
+    id: number
+    handle: string
+    wallet: PublicKey
+}
+
+export function encodeMember(w: BorshWriter, value: Member) {
+    w.u32(value.id)
+    w.string(value.handle)
+    w.publicKey(value.wallet)
+}
+
+export function decodeMember(r: BorshReader): Member {
+    return {
+        id: r.u32(),
+        handle: r.string(),
+        wallet: r.publicKey(),
+    }
+}
+
+export type Ledger = {
+    owner: PublicKey
+    balance: bigint
+    status: Status
+}
+
+export function encodeLedger(w: BorshWriter, value: Ledger) {
+    w.publicKey(value.owner)
+    w.u64(value.balance)
+    encodeStatus(w, value.status)
+}
+
+export function decodeLedger(r: BorshReader): Ledger {
+    return {
+        owner: r.publicKey(),
+        balance: r.u64(),
+        status: decodeStatus(r),
+    }
+}
+
+export type Status =
+    | { kind: "Open" }
+    | { kind: "Closed", reason: string }
+
+export function encodeStatus(w: BorshWriter, value: Status) {
+    switch (value.kind) {
+        case "Open":
+            w.u8(0)
+            break
+        case "Closed":
+            w.u8(1)
+            w.string(value.reason)
+            break
+    }
+}
+
+export function decodeStatus(r: BorshReader): Status {
+    const variant = r.u8()
+    switch (variant) {
+        case 0:
+            return { kind: "Open" }
+        case 1:
+            return { kind: "Closed", reason: r.string() }
+        default:
+            throw new Error(`Invalid variant for Status: ${variant}`)
+    }
+}
+
+export type Profile = {
+    bio: string
+    tags: Array<string>
+}
+
+export function encodeProfile(w: BorshWriter, value: Profile) {
+    w.string(value.bio)
+    w.vec(value.tags, (x0) => { w.string(x0) })
+}
+
+export function decodeProfile(r: BorshReader): Profile {
+    return {
+        bio: r.string(),
+        tags: r.vec(() => r.string()),
+    }
+}
+
+export const MEMBER_DISCRIMINATOR = Buffer.from([174, 2, 13, 241, 146, 22, 20, 59])
+
+export function deserializeMemberAccount(data: Buffer): Member {
+    if (data.length < 8 || !data.subarray(0, 8).equals(MEMBER_DISCRIMINATOR)) {
+        throw new Error("Discriminator mismatch for account data: Member")
+    }
+    return decodeMember(new BorshReader(data.subarray(8)))
+}
+
+export function deriveMemberAddress(programId: PublicKey, id: number): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [Buffer.from("member"), borshBytes((w) => { w.u32(id) })],
+        programId,
+    )
+}
+
+export function deriveMemberCounterAddress(programId: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [Buffer.from("nautilus_counter"), Buffer.from("member")],
+        programId,
+    )
+}
+
+export function deriveMemberHandleLookupAddress(programId: PublicKey, handle: string): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [Buffer.from("nautilus_lookup"), Buffer.from("member"), Buffer.from("handle"), Buffer.from(sha256(Buffer.from(handle)))],
+        programId,
+    )
+}
+
+export const LEDGER_DISCRIMINATOR = Buffer.from([96, 243, 29, 140, 82, 7, 210, 191])
+export const LEDGER_VERSION = 2
+
+export function deserializeLedgerAccount(data: Buffer): Ledger {
+    if (data.length < 9 || !data.subarray(0, 8).equals(LEDGER_DISCRIMINATOR)) {
+        throw new Error("Discriminator mismatch for account data: Ledger")
+    }
+    if (data[8] !== LEDGER_VERSION) {
+        throw new Error(`Unsupported Ledger version ${data[8]}, the account must be migrated first`)
+    }
+    return decodeLedger(new BorshReader(data.subarray(9)))
+}
+
+export function deriveLedgerAddress(programId: PublicKey, owner: PublicKey, epoch: bigint): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [Buffer.from("ledger"), borshBytes((w) => { w.publicKey(owner) }), borshBytes((w) => { w.u64(epoch) })],
+        programId,
+    )
+}
+
+export type CreateMemberAccounts = {
+    index: PublicKey
+    memberCounter: PublicKey
+    newMember: PublicKey
+    wallet: PublicKey
+    feePayer: PublicKey
+    systemProgram: PublicKey
+    rent: PublicKey
+}
+
+export type CreateMemberArgs = {
+    member: Member
+}
+
+export function createCreateMemberInstruction(
+    programId: PublicKey,
+    accounts: CreateMemberAccounts,
+    args: CreateMemberArgs,
+): TransactionInstruction {
+    const w = new BorshWriter()
+    w.u8(0)
+    encodeMember(w, args.member)
+    return new TransactionInstruction({
+        keys: [
+            { pubkey: accounts.index, isSigner: false, isWritable: true },
+            { pubkey: accounts.memberCounter, isSigner: false, isWritable: true },
+            { pubkey: accounts.newMember, isSigner: false, isWritable: true },
+            { pubkey: accounts.wallet, isSigner: true, isWritable: false },
+            { pubkey: accounts.feePayer, isSigner: true, isWritable: true },
+            { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+            { pubkey: accounts.rent, isSigner: false, isWritable: false },
+        ],
+        programId,
+        data: w.toBuffer(),
+    })
+}
+
+export type SettleAccounts = {
+    ledger: PublicKey
+    owner: PublicKey
+    referrer?: PublicKey
+}
+
+export type SettleArgs = {
+    amount: bigint
+    profile: Profile
+    note: string | null
+}
+
+export function createSettleInstruction(
+    programId: PublicKey,
+    accounts: SettleAccounts,
+    args: SettleArgs,
+    remainingAccounts: PublicKey[] = [],
+): TransactionInstruction {
+    const w = new BorshWriter()
+    w.u8(1)
+    w.u64(args.amount)
+    encodeProfile(w, args.profile)
+    w.option(args.note, (x0) => { w.string(x0) })
+    return new TransactionInstruction({
+        keys: [
+            { pubkey: accounts.ledger, isSigner: false, isWritable: true },
+            { pubkey: accounts.owner, isSigner: true, isWritable: false },
+            accounts.referrer
+                ? { pubkey: accounts.referrer, isSigner: false, isWritable: false }
+                : { pubkey: programId, isSigner: false, isWritable: false },
+            ...remainingAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })),
+        ],
+        programId,
+        data: w.toBuffer(),
+    })
+}
+
+export type CloseLedgerAccounts = {
+    ledger: PublicKey
+    owner: PublicKey
+}
+
+export function createCloseLedgerInstruction(
+    programId: PublicKey,
+    accounts: CloseLedgerAccounts,
+): TransactionInstruction {
+    const w = new BorshWriter()
+    w.fixed(Uint8Array.from([111, 92, 198, 47, 110, 51, 33, 13]))
+    return new TransactionInstruction({
+        keys: [
+            { pubkey: accounts.ledger, isSigner: false, isWritable: true },
+            { pubkey: accounts.owner, isSigner: true, isWritable: true },
+        ],
+        programId,
+        data: w.toBuffer(),
+    })
+}
+
+export type ProgramErrorCode = { code: number, name: string, msg: string }
+
+export const PROGRAM_ERRORS: ProgramErrorCode[] = [
+    { code: 6000, name: "LedgerClosed", msg: "The ledger is closed." },
+]
+
+export function errorFromCode(code: number): ProgramErrorCode | null {
+    return PROGRAM_ERRORS.find((e) => e.code === code) ?? null
+}
@@ -1,212 +1,196 @@
-use nautilus_idl::*;
+use nautilus_idl::{
+    idl_error::IdlErrorCode,
+    idl_instruction::{
+        IdlInstruction, IdlInstructionAccount, IdlInstructionArg, IdlInstructionDiscriminant,
+    },
+    idl_metadata::IdlMetadata,
+    idl_nautilus_config::{IdlTypeDefNautilusConfig, IdlTypeDefNautilusConfigDefaultInstruction},
+    idl_type::IdlType,
+    idl_type_def::{IdlTypeDef, IdlTypeDefType, IdlTypeStructField},
+    Idl,
+};
+
+fn table_config(table_name: &str) -> IdlTypeDefNautilusConfig {
+    IdlTypeDefNautilusConfig {
+        discrminator_str: None,
+        table_name: Some(table_name.to_string()),
+        primary_key: Some("id".to_string()),
+        primary_keys: vec!["id".to_string()],
+        autoincrement: Some(true),
+        version: None,
+        table_counter: false,
+        zero_copy: false,
+        space: None,
+        authorities: vec!["authority".to_string()],
+        default_instructions: vec![
+            IdlTypeDefNautilusConfigDefaultInstruction::Create(table_name.to_string()),
+            IdlTypeDefNautilusConfigDefaultInstruction::Delete(table_name.to_string()),
+            IdlTypeDefNautilusConfigDefaultInstruction::Update(table_name.to_string()),
+        ],
+        seeds: vec![],
+        lookups: vec![],
+        foreign_keys: vec![],
+    }
+}
+
+fn table(name: &str, table_name: &str) -> IdlTypeDef {
+    IdlTypeDef::new(
+        name.to_string(),
+        IdlTypeDefType::Struct {
+            fields: vec![
+                IdlTypeStructField::new("id".to_string(), IdlType::U8),
+                IdlTypeStructField::new("name".to_string(), IdlType::String),
+                IdlTypeStructField::new("authority".to_string(), IdlType::PublicKey),
+            ],
+        },
+        Some(table_config(table_name)),
+    )
+}
+
+fn account(
+    name: &str,
+    is_mut: bool,
+    is_signer: bool,
+    account_type: &str,
+    desc: &str,
+) -> IdlInstructionAccount {
+    IdlInstructionAccount::new(
+        name.to_string(),
+        is_mut,
+        is_signer,
+        account_type.to_string(),
+        desc.to_string(),
+    )
+}
 
 #[test]
 fn idl() {
-    let (name, version) = parse_cargo_toml("Cargo.toml").unwrap();
     let metadata = IdlMetadata::new("some-program-id");
 
-    let types = vec![IdlType::new(
-        "CustomArgs",
-        IdlTypeType::new(
-            "struct",
-            vec![
-                IdlTypeTypeField::new("string1", "string"),
-                IdlTypeTypeField::new("string2", "string"),
+    let types = vec![IdlTypeDef::new(
+        "CustomArgs".to_string(),
+        IdlTypeDefType::Struct {
+            fields: vec![
+                IdlTypeStructField::new("string1".to_string(), IdlType::String),
+                IdlTypeStructField::new("string2".to_string(), IdlType::String),
             ],
-        ),
+        },
+        None,
     )];
 
-    let accounts = vec![
-        IdlAccount::new(
-            "Hero",
-            IdlTypeType::new(
-                "struct",
-                vec![
-                    IdlTypeTypeField::new("id", "u8"),
-                    IdlTypeTypeField::new("name", "string"),
-                    IdlTypeTypeField::new("authority", "publicKey"),
-                ],
-            ),
-        ),
-        IdlAccount::new(
-            "Villain",
-            IdlTypeType::new(
-                "struct",
-                vec![
-                    IdlTypeTypeField::new("id", "u8"),
-                    IdlTypeTypeField::new("name", "string"),
-                    IdlTypeTypeField::new("authority", "publicKey"),
-                ],
-            ),
-        ),
-    ];
+    let accounts = vec![table("Hero", "hero"), table("Villain", "villain")];
 
     let instructions = vec![
         IdlInstruction::new(
             "CreateHero",
             vec![
-                IdlInstructionAccount::new(
-                    "autoincAccount",
+                account("index", true, false, "NautilusIndex", "The Nautilus Index."),
+                account(
+                    "newAccount",
                     true,
                     false,
-                    "The autoincrement account.",
+                    "Hero",
+                    "The account to be created.",
                 ),
-                IdlInstructionAccount::new("newAccount", true, false, "The account to be created."),
-                IdlInstructionAccount::new(
+                account(
                     "authority",
                     true,
                     true,
+                    "Wallet",
                     "One of the authorities specified for this account.",
                 ),
-                IdlInstructionAccount::new("feePayer", true, true, "Fee payer"),
-                IdlInstructionAccount::new("systemProgram", false, false, "The System Program"),
+                account("feePayer", true, true, "Wallet", "Fee payer"),
+                account(
+                    "systemProgram",
+                    false,
+                    false,
+                    "SystemProgram",
+                    "The System Program",
+                ),
             ],
             vec![IdlInstructionArg::new(
-                "hero",
-                IdlInstructionArgType::new("Hero"),
+                "hero".to_string(),
+                IdlType::Defined("Hero".to_string()),
             )],
             IdlInstructionDiscriminant::new(0),
         ),
         IdlInstruction::new(
             "DeleteHero",
             vec![
-                IdlInstructionAccount::new(
+                account(
                     "targetAccount",
                     true,
                     false,
+                    "Hero",
                     "The account to be deleted.",
                 ),
-                IdlInstructionAccount::new(
+                account(
                     "authority",
                     true,
                     true,
+                    "Wallet",
                     "One of the authorities specified for this account.",
                 ),
-                IdlInstructionAccount::new("feePayer", true, true, "Fee payer"),
+                account("feePayer", true, true, "Wallet", "Fee payer"),
             ],
             vec![],
             IdlInstructionDiscriminant::new(1),
         ),
         IdlInstruction::new(
-            "UpdateHero",
+            "CustomInstruction",
             vec![
-                IdlInstructionAccount::new(
+                account(
                     "targetAccount",
                     true,
                     false,
-                    "The account to be updated.",
-                ),
-                IdlInstructionAccount::new(
-                    "authority",
-                    true,
-                    true,
-                    "One of the authorities specified for this account.",
-                ),
-                IdlInstructionAccount::new("feePayer", true, true, "Fee payer"),
-                IdlInstructionAccount::new("systemProgram", false, false, "The System Program"),
-            ],
-            vec![IdlInstructionArg::new(
-                "hero",
-                IdlInstructionArgType::new("Hero"),
-            )],
-            IdlInstructionDiscriminant::new(2),
-        ),
-        IdlInstruction::new(
-            "CreateVillain",
-            vec![
-                IdlInstructionAccount::new(
-                    "autoincAccount",
-                    true,
-                    false,
-                    "The autoincrement account.",
-                ),
-                IdlInstructionAccount::new("newAccount", true, false, "The account to be created."),
-                IdlInstructionAccount::new(
-                    "authority",
-                    true,
-                    true,
-                    "One of the authorities specified for this account.",
+                    "Villain",
+                    "The account to be used as a test.",
                 ),
-                IdlInstructionAccount::new("feePayer", true, true, "Fee payer"),
-                IdlInstructionAccount::new("systemProgram", false, false, "The System Program"),
-            ],
-            vec![IdlInstructionArg::new(
-                "villain",
-                IdlInstructionArgType::new("Villain"),
-            )],
-            IdlInstructionDiscriminant::new(3),
-        ),
-        IdlInstruction::new(
-            "DeleteVillain",
-            vec![
-                IdlInstructionAccount::new(
-                    "targetAccount",
-                    true,
+                account("feePayer", true, true, "Wallet", "Fee payer"),
+                account(
+                    "systemProgram",
                     false,
-                    "The account to be deleted.",
-                ),
-                IdlInstructionAccount::new(
-                    "authority",
-                    true,
-                    true,
-                    "One of the authorities specified for this account.",
-                ),
-                IdlInstructionAccount::new("feePayer", true, true, "Fee payer"),
-            ],
-            vec![],
-            IdlInstructionDiscriminant::new(4),
-        ),
-        IdlInstruction::new(
-            "UpdateVillain",
-            vec![
-                IdlInstructionAccount::new(
-                    "targetAccount",
-                    true,
                     false,
-                    "The account to be updated.",
-                ),
-                IdlInstructionAccount::new(
-                    "authority",
-                    true,
-                    true,
-                    "One of the authorities specified for this account.",
+                    "SystemProgram",
+                    "The System Program",
                 ),
-                IdlInstructionAccount::new("feePayer", true, true, "Fee payer"),
-                IdlInstructionAccount::new("systemProgram", false, false, "The System Program"),
             ],
             vec![IdlInstructionArg::new(
-                "villain",
-                IdlInstructionArgType::new("Villain"),
+                "customArgs".to_string(),
+                IdlType::Defined("CustomArgs".to_string()),
             )],
-            IdlInstructionDiscriminant::new(5),
+            IdlInstructionDiscriminant::new(2),
         ),
-        IdlInstruction::new(
-            "CustomInstruction",
-            vec![
-                IdlInstructionAccount::new(
-                    "targetAccount",
-                    true,
-                    false,
-                    "The account to be used as a test.",
-                ),
-                IdlInstructionAccount::new(
-                    "authority",
-                    true,
-                    true,
-                    "One of the authorities specified for this account.",
-                ),
-                IdlInstructionAccount::new("feePayer", true, true, "Fee payer"),
-                IdlInstructionAccount::new("systemProgram", false, false, "The System Program"),
-            ],
-            vec![IdlInstructionArg::new(
-                "customArgs",
-                IdlInstructionArgType::new("CustomArgs"),
-            )],
-            IdlInstructionDiscriminant::new(6),
+    ];
+
+    let errors = vec![
+        IdlErrorCode::new(
+            6000,
+            "HeroFainted".to_string(),
+            Some("The hero fainted.".to_string()),
         ),
+        IdlErrorCode::new(6001, "VillainEscaped".to_string(), None),
     ];
 
-    let idl = Idl::new(&version, &name, instructions, accounts, types, metadata);
+    let idl = Idl::new(
+        env!("CARGO_PKG_VERSION").to_string(),
+        env!("CARGO_PKG_NAME").to_string(),
+        instructions,
+        accounts,
+        types,
+        errors,
+        metadata,
+    );
+
+    idl.write_to_json("./target/idl").unwrap();
 
-    idl.write_to_json("./target/idl");
+    let loaded =
+        Idl::load_from_json(&format!("./target/idl/{}.json", env!("CARGO_PKG_NAME"))).unwrap();
+    assert_eq!(loaded.instructions.len(), 3);
+    assert_eq!(loaded.accounts.len(), 2);
+    assert_eq!(loaded.types.len(), 1);
+    assert_eq!(loaded.errors.len(), 2);
+    assert_eq!(loaded.errors[0].code, 6000);
+    assert_eq!(loaded.errors[0].msg.as_deref(), Some("The hero fainted."));
+    assert_eq!(loaded.errors[1].msg, None);
 }
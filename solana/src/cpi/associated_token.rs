@@ -1,12 +1,12 @@
 //! Cross-Program invocations to the Associated Token Program
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, program::invoke};
 
-use crate::{AssociatedTokenAccount, NautilusAccountInfo, NautilusSigner};
+use crate::{NautilusAccountInfo, NautilusSigner};
 
 /// Creates an associated token account.
 #[allow(clippy::boxed_local)]
 pub fn create_associated_token_account<'a>(
-    new_account: AssociatedTokenAccount<'a>,
+    new_account: impl NautilusAccountInfo<'a>,
     owner: impl NautilusAccountInfo<'a>,
     mint: impl NautilusAccountInfo<'a>,
     payer: impl NautilusSigner<'a>,
@@ -2,4 +2,5 @@
 pub mod associated_token;
 pub mod system;
 pub mod token;
+pub mod token_2022;
 pub mod token_metadata;
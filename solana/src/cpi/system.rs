@@ -45,6 +45,10 @@ pub fn create_account<'a>(
 
 /// Cross-Program Invocation (CPI) to create a program-derived address account
 /// (PDA).
+///
+/// A PDA's address is known ahead of time, so anyone can send it lamports
+/// before it's created - which would make `create_account` fail. A pre-funded
+/// PDA is instead topped up to its required rent, then allocated and assigned.
 #[allow(clippy::boxed_local)]
 pub fn create_pda<'a, T: BorshSerialize>(
     new_account: impl NautilusAccountInfo<'a>,
@@ -53,17 +57,42 @@ pub fn create_pda<'a, T: BorshSerialize>(
     data: Box<T>,
     signer_seeds: Vec<&[u8]>,
 ) -> ProgramResult {
-    invoke_signed(
-        &system_instruction::create_account(
-            payer.key(),
-            new_account.key(),
-            new_account.required_rent()?,
-            new_account.size()?,
-            owner,
-        ),
-        &[*payer.account_info(), *new_account.account_info()],
-        &[&signer_seeds],
-    )?;
+    let current_lamports = new_account.lamports();
+    if current_lamports == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key(),
+                new_account.key(),
+                new_account.required_rent()?,
+                new_account.size()?,
+                owner,
+            ),
+            &[*payer.account_info(), *new_account.account_info()],
+            &[&signer_seeds],
+        )?;
+    } else {
+        let required_rent = new_account.required_rent()?;
+        if required_rent > current_lamports {
+            invoke(
+                &system_instruction::transfer(
+                    payer.key(),
+                    new_account.key(),
+                    required_rent - current_lamports,
+                ),
+                &[*payer.account_info(), *new_account.account_info()],
+            )?;
+        }
+        invoke_signed(
+            &system_instruction::allocate(new_account.key(), new_account.size()?),
+            &[*new_account.account_info()],
+            &[&signer_seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(new_account.key(), owner),
+            &[*new_account.account_info()],
+            &[&signer_seeds],
+        )?;
+    }
     data.serialize(&mut &mut new_account.account_info().data.borrow_mut()[..])?;
     Ok(())
 }
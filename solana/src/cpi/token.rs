@@ -1,4 +1,8 @@
 //! Cross-Program invocations to the Token Program (legacy).
+//!
+//! Each instruction is sent to the program that owns the token account or mint
+//! it operates on, so these also work with accounts owned by the Token-2022
+//! Program.
 use solana_program::{
     account_info::AccountInfo, entrypoint::ProgramResult, program::invoke, pubkey::Pubkey,
 };
@@ -9,7 +13,6 @@ use crate::{NautilusAccountInfo, NautilusMut, NautilusSigner};
 /// Approves a delegate.  A delegate is given the authority over tokens on
 /// behalf of the source account's owner.
 pub fn approve<'a>(
-    token_program_id: &Pubkey,
     source_account: impl NautilusMut<'a>,
     delegate: impl NautilusAccountInfo<'a>,
     source_owner: impl NautilusSigner<'a>,
@@ -26,8 +29,8 @@ pub fn approve<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::approve(
-            token_program_id,
+        &spl_token_2022::instruction::approve(
+            source_account.owner(),
             source_account.key(),
             delegate.key(),
             source_owner.key(),
@@ -46,7 +49,6 @@ pub fn approve<'a>(
 /// creating transactions offline or within a hardware wallet.
 #[allow(clippy::too_many_arguments)]
 pub fn approve_checked<'a>(
-    token_program_id: &Pubkey,
     source_account: impl NautilusMut<'a>,
     mint: impl NautilusAccountInfo<'a>,
     delegate: impl NautilusAccountInfo<'a>,
@@ -65,8 +67,8 @@ pub fn approve_checked<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::approve_checked(
-            token_program_id,
+        &spl_token_2022::instruction::approve_checked(
+            source_account.owner(),
             source_account.key(),
             mint.key(),
             delegate.key(),
@@ -81,7 +83,6 @@ pub fn approve_checked<'a>(
 
 /// Burns tokens by removing them from an account.
 pub fn burn<'a>(
-    token_program_id: &Pubkey,
     token_account: impl NautilusMut<'a>,
     mint: impl NautilusAccountInfo<'a>,
     authority: impl NautilusSigner<'a>,
@@ -98,8 +99,8 @@ pub fn burn<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::burn(
-            token_program_id,
+        &spl_token_2022::instruction::burn(
+            token_account.owner(),
             token_account.key(),
             mint.key(),
             authority.key(),
@@ -118,7 +119,6 @@ pub fn burn<'a>(
 /// by the caller. This may be useful when creating transactions offline or
 /// within a hardware wallet.
 pub fn burn_checked<'a>(
-    token_program_id: &Pubkey,
     token_account: impl NautilusMut<'a>,
     mint: impl NautilusAccountInfo<'a>,
     authority: impl NautilusSigner<'a>,
@@ -136,8 +136,8 @@ pub fn burn_checked<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::burn_checked(
-            token_program_id,
+        &spl_token_2022::instruction::burn_checked(
+            token_account.owner(),
             token_account.key(),
             mint.key(),
             authority.key(),
@@ -152,7 +152,6 @@ pub fn burn_checked<'a>(
 /// Close an account by transferring all its SOL to the destination account.
 /// Non-native accounts may only be closed if its token amount is zero.
 pub fn close_account<'a>(
-    token_program_id: &Pubkey,
     token_account: impl NautilusMut<'a>,
     destination: impl NautilusMut<'a>,
     authority: impl NautilusSigner<'a>,
@@ -168,8 +167,8 @@ pub fn close_account<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::close_account(
-            token_program_id,
+        &spl_token_2022::instruction::close_account(
+            token_account.owner(),
             token_account.key(),
             destination.key(),
             authority.key(),
@@ -182,7 +181,6 @@ pub fn close_account<'a>(
 /// Freeze an Initialized account using the Mint's freeze_authority (if
 /// set).
 pub fn freeze_account<'a>(
-    token_program_id: &Pubkey,
     token_account: impl NautilusMut<'a>,
     mint: impl NautilusAccountInfo<'a>,
     freeze_authority: impl NautilusSigner<'a>,
@@ -198,8 +196,8 @@ pub fn freeze_account<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::freeze_account(
-            token_program_id,
+        &spl_token_2022::instruction::freeze_account(
+            token_account.owner(),
             token_account.key(),
             mint.key(),
             freeze_authority.key(),
@@ -216,15 +214,14 @@ pub fn freeze_account<'a>(
 /// command can succeed.
 #[allow(clippy::boxed_local)]
 pub fn initialize_account<'a>(
-    token_program_id: &Pubkey,
     new_token_account: impl NautilusMut<'a>,
     mint: impl NautilusAccountInfo<'a>,
     authority: impl NautilusAccountInfo<'a>,
     rent: Box<AccountInfo<'a>>,
 ) -> ProgramResult {
     invoke(
-        &spl_token::instruction::initialize_account(
-            token_program_id,
+        &spl_token_2022::instruction::initialize_account(
+            new_token_account.owner(),
             new_token_account.key(),
             mint.key(),
             authority.key(),
@@ -244,15 +241,14 @@ pub fn initialize_account<'a>(
 /// `AccountInfo` otherwise.
 #[allow(clippy::boxed_local)]
 pub fn initialize_account2<'a>(
-    token_program_id: &Pubkey,
     new_token_account: impl NautilusMut<'a>,
     mint: impl NautilusAccountInfo<'a>,
     authority: &Pubkey,
     rent: Box<AccountInfo<'a>>,
 ) -> ProgramResult {
     invoke(
-        &spl_token::instruction::initialize_account2(
-            token_program_id,
+        &spl_token_2022::instruction::initialize_account2(
+            new_token_account.owner(),
             new_token_account.key(),
             mint.key(),
             authority,
@@ -267,14 +263,13 @@ pub fn initialize_account2<'a>(
 
 /// Like InitializeAccount2, but does not require the Rent sysvar to be provided
 pub fn initialize_account3<'a>(
-    token_program_id: &Pubkey,
     new_token_account: impl NautilusMut<'a>,
     mint: impl NautilusAccountInfo<'a>,
     authority: &Pubkey,
 ) -> ProgramResult {
     invoke(
-        &spl_token::instruction::initialize_account3(
-            token_program_id,
+        &spl_token_2022::instruction::initialize_account3(
+            new_token_account.owner(),
             new_token_account.key(),
             mint.key(),
             authority,
@@ -287,13 +282,10 @@ pub fn initialize_account3<'a>(
 ///
 /// Fails if the account has already been initialized, so must be called before
 /// `InitializeAccount`.
-pub fn initialize_immutable_owner<'a>(
-    token_program_id: &Pubkey,
-    new_token_account: impl NautilusMut<'a>,
-) -> ProgramResult {
+pub fn initialize_immutable_owner<'a>(new_token_account: impl NautilusMut<'a>) -> ProgramResult {
     invoke(
-        &spl_token::instruction::initialize_immutable_owner(
-            token_program_id,
+        &spl_token_2022::instruction::initialize_immutable_owner(
+            new_token_account.owner(),
             new_token_account.key(),
         )?,
         &[*new_token_account.account_info()],
@@ -304,7 +296,6 @@ pub fn initialize_immutable_owner<'a>(
 /// tokens in an account.
 #[allow(clippy::boxed_local)]
 pub fn initialize_mint<'a>(
-    token_program_id: &Pubkey,
     mint: impl NautilusMut<'a>,
     mint_authority: &Pubkey,
     freeze_authority: Option<&Pubkey>,
@@ -312,8 +303,8 @@ pub fn initialize_mint<'a>(
     rent: Box<AccountInfo<'a>>,
 ) -> ProgramResult {
     invoke(
-        &spl_token::instruction::initialize_mint(
-            token_program_id,
+        &spl_token_2022::instruction::initialize_mint(
+            mint.owner(),
             mint.key(),
             mint_authority,
             freeze_authority,
@@ -325,15 +316,14 @@ pub fn initialize_mint<'a>(
 
 /// Like InitializeMint, but does not require the Rent sysvar to be provided
 pub fn initialize_mint2<'a>(
-    token_program_id: &Pubkey,
     mint: impl NautilusMut<'a>,
     mint_authority: &Pubkey,
     freeze_authority: Option<&Pubkey>,
     decimals: u8,
 ) -> ProgramResult {
     invoke(
-        &spl_token::instruction::initialize_mint2(
-            token_program_id,
+        &spl_token_2022::instruction::initialize_mint2(
+            mint.owner(),
             mint.key(),
             mint_authority,
             freeze_authority,
@@ -351,7 +341,6 @@ pub fn initialize_mint2<'a>(
 /// required to validate this multisignature account.
 #[allow(clippy::boxed_local)]
 pub fn initialize_multisig<'a>(
-    token_program_id: &Pubkey,
     multisig_account: impl NautilusMut<'a>,
     multisigs: Vec<impl NautilusAccountInfo<'a>>,
     m: u8,
@@ -360,8 +349,8 @@ pub fn initialize_multisig<'a>(
     let mut accounts = vec![*multisig_account.account_info(), *rent];
     let signer_pubkeys = append_multisig_accounts_and_return_keys(&mut accounts, multisigs);
     invoke(
-        &spl_token::instruction::initialize_multisig(
-            token_program_id,
+        &spl_token_2022::instruction::initialize_multisig(
+            multisig_account.owner(),
             multisig_account.key(),
             signer_pubkeys.as_slice(),
             m,
@@ -377,7 +366,6 @@ pub fn initialize_multisig<'a>(
 /// present.  The variant field represents the number of signers (M)
 /// required to validate this multisignature account.
 pub fn initialize_multisig2<'a>(
-    token_program_id: &Pubkey,
     multisig_account: impl NautilusMut<'a>,
     multisigs: Vec<impl NautilusAccountInfo<'a>>,
     m: u8,
@@ -385,8 +373,8 @@ pub fn initialize_multisig2<'a>(
     let mut accounts = vec![*multisig_account.account_info()];
     let signer_pubkeys = append_multisig_accounts_and_return_keys(&mut accounts, multisigs);
     invoke(
-        &spl_token::instruction::initialize_multisig2(
-            token_program_id,
+        &spl_token_2022::instruction::initialize_multisig2(
+            multisig_account.owner(),
             multisig_account.key(),
             signer_pubkeys.as_slice(),
             m,
@@ -398,7 +386,6 @@ pub fn initialize_multisig2<'a>(
 /// Mints new tokens to an account.  The native mint does not support
 /// minting.
 pub fn mint_to<'a>(
-    token_program_id: &Pubkey,
     mint: impl NautilusMut<'a>,
     recipient: impl NautilusMut<'a>,
     mint_authority: impl NautilusSigner<'a>,
@@ -415,8 +402,8 @@ pub fn mint_to<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::mint_to(
-            token_program_id,
+        &spl_token_2022::instruction::mint_to(
+            mint.owner(),
             mint.key(),
             recipient.key(),
             mint_authority.key(),
@@ -434,7 +421,6 @@ pub fn mint_to<'a>(
 /// checked by the caller.  This may be useful when creating transactions
 /// offline or within a hardware wallet.
 pub fn mint_to_checked<'a>(
-    token_program_id: &Pubkey,
     mint: impl NautilusMut<'a>,
     recipient: impl NautilusMut<'a>,
     mint_authority: impl NautilusSigner<'a>,
@@ -452,8 +438,8 @@ pub fn mint_to_checked<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::mint_to_checked(
-            token_program_id,
+        &spl_token_2022::instruction::mint_to_checked(
+            mint.owner(),
             mint.key(),
             recipient.key(),
             mint_authority.key(),
@@ -467,7 +453,6 @@ pub fn mint_to_checked<'a>(
 
 /// Revokes the delegate's authority.
 pub fn revoke<'a>(
-    token_program_id: &Pubkey,
     source_account: impl NautilusMut<'a>,
     source_owner: impl NautilusSigner<'a>,
     multisigs: Option<Vec<impl NautilusSigner<'a>>>,
@@ -478,8 +463,8 @@ pub fn revoke<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::revoke(
-            token_program_id,
+        &spl_token_2022::instruction::revoke(
+            source_account.owner(),
             source_account.key(),
             source_owner.key(),
             signer_pubkeys.as_slice(),
@@ -490,7 +475,6 @@ pub fn revoke<'a>(
 
 /// Sets a new authority of a mint or account.
 pub fn set_authority<'a>(
-    token_program_id: &Pubkey,
     mint_or_account: impl NautilusMut<'a>,
     new_authority: Option<&Pubkey>,
     authority_type: AuthorityType,
@@ -506,11 +490,11 @@ pub fn set_authority<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::set_authority(
-            token_program_id,
+        &spl_token_2022::instruction::set_authority(
+            mint_or_account.owner(),
             mint_or_account.key(),
             new_authority,
-            authority_type,
+            token_2022_authority_type(authority_type),
             current_authority.key(),
             signer_pubkeys.as_slice(),
         )?,
@@ -523,19 +507,15 @@ pub fn set_authority<'a>(
 /// This is useful if a non-wrapped SOL account uses
 /// `system_instruction::transfer` to move lamports to a wrapped token account,
 /// and needs to have its token `amount` field updated.
-pub fn sync_native<'a>(
-    token_program_id: &Pubkey,
-    token_account: impl NautilusMut<'a>,
-) -> ProgramResult {
+pub fn sync_native<'a>(token_account: impl NautilusMut<'a>) -> ProgramResult {
     invoke(
-        &spl_token::instruction::sync_native(token_program_id, token_account.key())?,
+        &spl_token_2022::instruction::sync_native(token_account.owner(), token_account.key())?,
         &[*token_account.account_info()],
     )
 }
 
 /// Thaw a Frozen account using the Mint's freeze_authority (if set).
 pub fn thaw_account<'a>(
-    token_program_id: &Pubkey,
     token_account: impl NautilusMut<'a>,
     mint: impl NautilusAccountInfo<'a>,
     freeze_authority: impl NautilusSigner<'a>,
@@ -551,8 +531,8 @@ pub fn thaw_account<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::thaw_account(
-            token_program_id,
+        &spl_token_2022::instruction::thaw_account(
+            token_account.owner(),
             token_account.key(),
             mint.key(),
             freeze_authority.key(),
@@ -562,10 +542,13 @@ pub fn thaw_account<'a>(
     )
 }
 
-/// Mints new tokens to an account.  The native mint does not support
-/// minting.
+/// Transfers tokens from one account to another either directly or via a
+/// delegate.
+///
+/// The Token-2022 Program rejects this instruction for mints with a transfer
+/// fee, so prefer `transfer_checked` for those.
+#[allow(deprecated)]
 pub fn transfer<'a>(
-    token_program_id: &Pubkey,
     from: impl NautilusMut<'a>,
     to: impl NautilusMut<'a>,
     authority: impl NautilusSigner<'a>,
@@ -582,8 +565,8 @@ pub fn transfer<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::transfer(
-            token_program_id,
+        &spl_token_2022::instruction::transfer(
+            from.owner(),
             from.key(),
             to.key(),
             authority.key(),
@@ -604,7 +587,6 @@ pub fn transfer<'a>(
 /// creating transactions offline or within a hardware wallet.
 #[allow(clippy::too_many_arguments)]
 pub fn transfer_checked<'a>(
-    token_program_id: &Pubkey,
     mint: impl NautilusAccountInfo<'a>,
     from: impl NautilusMut<'a>,
     to: impl NautilusMut<'a>,
@@ -624,8 +606,8 @@ pub fn transfer_checked<'a>(
         None => vec![],
     };
     invoke(
-        &spl_token::instruction::transfer_checked(
-            token_program_id,
+        &spl_token_2022::instruction::transfer_checked(
+            mint.owner(),
             from.key(),
             mint.key(),
             to.key(),
@@ -638,8 +620,21 @@ pub fn transfer_checked<'a>(
     )
 }
 
+/// Maps a Token Program authority type to its Token-2022 Program equivalent,
+/// since the instructions are built with the Token-2022 Program's builders.
+fn token_2022_authority_type(
+    authority_type: AuthorityType,
+) -> spl_token_2022::instruction::AuthorityType {
+    match authority_type {
+        AuthorityType::MintTokens => spl_token_2022::instruction::AuthorityType::MintTokens,
+        AuthorityType::FreezeAccount => spl_token_2022::instruction::AuthorityType::FreezeAccount,
+        AuthorityType::AccountOwner => spl_token_2022::instruction::AuthorityType::AccountOwner,
+        AuthorityType::CloseAccount => spl_token_2022::instruction::AuthorityType::CloseAccount,
+    }
+}
+
 /// Helper function to build lists of pubkeys and accounts from multisig option.
-fn append_multisig_accounts_and_return_keys<'a>(
+pub(crate) fn append_multisig_accounts_and_return_keys<'a>(
     accounts: &mut Vec<AccountInfo<'a>>,
     multisigs: Vec<impl NautilusAccountInfo<'a>>,
 ) -> Vec<&'a Pubkey> {
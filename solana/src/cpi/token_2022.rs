@@ -0,0 +1,331 @@
+//! Cross-Program invocations to the Token-2022 Program.
+//!
+//! Every instruction here is built with `spl_token_2022`, which accepts either
+//! the legacy Token Program or the Token-2022 Program as `token_program_id` for
+//! the base token instructions. The extension initializers only work against
+//! the Token-2022 Program, and must be invoked after the mint account is
+//! created but before `initialize_mint`.
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke, pubkey::Pubkey,
+};
+use spl_token_2022::{extension, instruction::AuthorityType};
+
+use super::token::append_multisig_accounts_and_return_keys;
+use crate::{NautilusAccountInfo, NautilusMut, NautilusSigner};
+
+/// Burns tokens by removing them from an account.
+pub fn burn<'a>(
+    token_program_id: &Pubkey,
+    token_account: impl NautilusMut<'a>,
+    mint: impl NautilusAccountInfo<'a>,
+    authority: impl NautilusSigner<'a>,
+    multisigs: Option<Vec<impl NautilusSigner<'a>>>,
+    amount: u64,
+) -> ProgramResult {
+    let mut accounts = vec![
+        *token_account.account_info(),
+        *mint.account_info(),
+        *authority.account_info(),
+    ];
+    let signer_pubkeys = match multisigs {
+        Some(sigs) => append_multisig_accounts_and_return_keys(&mut accounts, sigs),
+        None => vec![],
+    };
+    invoke(
+        &spl_token_2022::instruction::burn(
+            token_program_id,
+            token_account.key(),
+            mint.key(),
+            authority.key(),
+            signer_pubkeys.as_slice(),
+            amount,
+        )?,
+        &accounts,
+    )
+}
+
+/// Freeze an Initialized account using the Mint's freeze_authority (if
+/// set).
+pub fn freeze_account<'a>(
+    token_program_id: &Pubkey,
+    account: impl NautilusMut<'a>,
+    mint: impl NautilusAccountInfo<'a>,
+    freeze_authority: impl NautilusSigner<'a>,
+    multisigs: Option<Vec<impl NautilusSigner<'a>>>,
+) -> ProgramResult {
+    let mut accounts = vec![
+        *account.account_info(),
+        *mint.account_info(),
+        *freeze_authority.account_info(),
+    ];
+    let signer_pubkeys = match multisigs {
+        Some(sigs) => append_multisig_accounts_and_return_keys(&mut accounts, sigs),
+        None => vec![],
+    };
+    invoke(
+        &spl_token_2022::instruction::freeze_account(
+            token_program_id,
+            account.key(),
+            mint.key(),
+            freeze_authority.key(),
+            signer_pubkeys.as_slice(),
+        )?,
+        &accounts,
+    )
+}
+
+/// Initializes a new mint. Any extensions must be initialized beforehand.
+#[allow(clippy::boxed_local)]
+pub fn initialize_mint<'a>(
+    token_program_id: &Pubkey,
+    mint: impl NautilusMut<'a>,
+    mint_authority: &Pubkey,
+    freeze_authority: Option<&Pubkey>,
+    decimals: u8,
+    rent: Box<AccountInfo<'a>>,
+) -> ProgramResult {
+    invoke(
+        &spl_token_2022::instruction::initialize_mint(
+            token_program_id,
+            mint.key(),
+            mint_authority,
+            freeze_authority,
+            decimals,
+        )?,
+        &[*mint.account_info(), *rent],
+    )
+}
+
+/// Initialize the interest-bearing extension for a new mint.
+pub fn initialize_interest_bearing_mint<'a>(
+    token_program_id: &Pubkey,
+    mint: impl NautilusMut<'a>,
+    rate_authority: Option<Pubkey>,
+    rate: i16,
+) -> ProgramResult {
+    invoke(
+        &extension::interest_bearing_mint::instruction::initialize(
+            token_program_id,
+            mint.key(),
+            rate_authority,
+            rate,
+        )?,
+        &[*mint.account_info()],
+    )
+}
+
+/// Initialize the metadata pointer extension for a new mint.
+pub fn initialize_metadata_pointer<'a>(
+    token_program_id: &Pubkey,
+    mint: impl NautilusMut<'a>,
+    authority: Option<Pubkey>,
+    metadata_address: Option<Pubkey>,
+) -> ProgramResult {
+    invoke(
+        &extension::metadata_pointer::instruction::initialize(
+            token_program_id,
+            mint.key(),
+            authority,
+            metadata_address,
+        )?,
+        &[*mint.account_info()],
+    )
+}
+
+/// Initialize the close account authority on a new mint.
+pub fn initialize_mint_close_authority<'a>(
+    token_program_id: &Pubkey,
+    mint: impl NautilusMut<'a>,
+    close_authority: Option<&Pubkey>,
+) -> ProgramResult {
+    invoke(
+        &spl_token_2022::instruction::initialize_mint_close_authority(
+            token_program_id,
+            mint.key(),
+            close_authority,
+        )?,
+        &[*mint.account_info()],
+    )
+}
+
+/// Initialize the non-transferable extension for a new mint.
+pub fn initialize_non_transferable_mint<'a>(
+    token_program_id: &Pubkey,
+    mint: impl NautilusMut<'a>,
+) -> ProgramResult {
+    invoke(
+        &spl_token_2022::instruction::initialize_non_transferable_mint(
+            token_program_id,
+            mint.key(),
+        )?,
+        &[*mint.account_info()],
+    )
+}
+
+/// Initialize the permanent delegate on a new mint.
+pub fn initialize_permanent_delegate<'a>(
+    token_program_id: &Pubkey,
+    mint: impl NautilusMut<'a>,
+    delegate: &Pubkey,
+) -> ProgramResult {
+    invoke(
+        &spl_token_2022::instruction::initialize_permanent_delegate(
+            token_program_id,
+            mint.key(),
+            delegate,
+        )?,
+        &[*mint.account_info()],
+    )
+}
+
+/// Initialize the transfer fee on a new mint.
+pub fn initialize_transfer_fee_config<'a>(
+    token_program_id: &Pubkey,
+    mint: impl NautilusMut<'a>,
+    transfer_fee_config_authority: Option<&Pubkey>,
+    withdraw_withheld_authority: Option<&Pubkey>,
+    transfer_fee_basis_points: u16,
+    maximum_fee: u64,
+) -> ProgramResult {
+    invoke(
+        &extension::transfer_fee::instruction::initialize_transfer_fee_config(
+            token_program_id,
+            mint.key(),
+            transfer_fee_config_authority,
+            withdraw_withheld_authority,
+            transfer_fee_basis_points,
+            maximum_fee,
+        )?,
+        &[*mint.account_info()],
+    )
+}
+
+/// Mints new tokens to an account.  The native mint does not support
+/// minting.
+pub fn mint_to<'a>(
+    token_program_id: &Pubkey,
+    mint: impl NautilusMut<'a>,
+    recipient: impl NautilusMut<'a>,
+    mint_authority: impl NautilusSigner<'a>,
+    multisigs: Option<Vec<impl NautilusSigner<'a>>>,
+    amount: u64,
+) -> ProgramResult {
+    let mut accounts = vec![
+        *mint.account_info(),
+        *recipient.account_info(),
+        *mint_authority.account_info(),
+    ];
+    let signer_pubkeys = match multisigs {
+        Some(sigs) => append_multisig_accounts_and_return_keys(&mut accounts, sigs),
+        None => vec![],
+    };
+    invoke(
+        &spl_token_2022::instruction::mint_to(
+            token_program_id,
+            mint.key(),
+            recipient.key(),
+            mint_authority.key(),
+            signer_pubkeys.as_slice(),
+            amount,
+        )?,
+        &accounts,
+    )
+}
+
+/// Sets a new authority of a mint or account.
+pub fn set_authority<'a>(
+    token_program_id: &Pubkey,
+    mint_or_account: impl NautilusMut<'a>,
+    new_authority: Option<&Pubkey>,
+    authority_type: AuthorityType,
+    current_authority: impl NautilusSigner<'a>,
+    multisigs: Option<Vec<impl NautilusSigner<'a>>>,
+) -> ProgramResult {
+    let mut accounts = vec![
+        *mint_or_account.account_info(),
+        *current_authority.account_info(),
+    ];
+    let signer_pubkeys = match multisigs {
+        Some(sigs) => append_multisig_accounts_and_return_keys(&mut accounts, sigs),
+        None => vec![],
+    };
+    invoke(
+        &spl_token_2022::instruction::set_authority(
+            token_program_id,
+            mint_or_account.key(),
+            new_authority,
+            authority_type,
+            current_authority.key(),
+            signer_pubkeys.as_slice(),
+        )?,
+        &accounts,
+    )
+}
+
+/// Thaw a Frozen account using the Mint's freeze_authority (if set).
+pub fn thaw_account<'a>(
+    token_program_id: &Pubkey,
+    account: impl NautilusMut<'a>,
+    mint: impl NautilusAccountInfo<'a>,
+    freeze_authority: impl NautilusSigner<'a>,
+    multisigs: Option<Vec<impl NautilusSigner<'a>>>,
+) -> ProgramResult {
+    let mut accounts = vec![
+        *account.account_info(),
+        *mint.account_info(),
+        *freeze_authority.account_info(),
+    ];
+    let signer_pubkeys = match multisigs {
+        Some(sigs) => append_multisig_accounts_and_return_keys(&mut accounts, sigs),
+        None => vec![],
+    };
+    invoke(
+        &spl_token_2022::instruction::thaw_account(
+            token_program_id,
+            account.key(),
+            mint.key(),
+            freeze_authority.key(),
+            signer_pubkeys.as_slice(),
+        )?,
+        &accounts,
+    )
+}
+
+/// Transfers tokens from one account to another either directly or via a
+/// delegate, checking the token mint and decimals. Mints with a transfer fee
+/// withhold the fee in the destination account.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked<'a>(
+    token_program_id: &Pubkey,
+    from: impl NautilusMut<'a>,
+    mint: impl NautilusAccountInfo<'a>,
+    to: impl NautilusMut<'a>,
+    authority: impl NautilusSigner<'a>,
+    multisigs: Option<Vec<impl NautilusSigner<'a>>>,
+    amount: u64,
+    decimals: u8,
+) -> ProgramResult {
+    let mut accounts = vec![
+        *from.account_info(),
+        *mint.account_info(),
+        *to.account_info(),
+        *authority.account_info(),
+    ];
+    let signer_pubkeys = match multisigs {
+        Some(sigs) => append_multisig_accounts_and_return_keys(&mut accounts, sigs),
+        None => vec![],
+    };
+    invoke(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program_id,
+            from.key(),
+            mint.key(),
+            to.key(),
+            authority.key(),
+            signer_pubkeys.as_slice(),
+            amount,
+            decimals,
+        )?,
+        &accounts,
+    )
+}
@@ -34,6 +34,41 @@ pub enum NautilusError {
     /// The underlying account for a `Create<T>` declared object already exists.
     #[error("The underlying account for a `Create<T>` declared object already exists.")]
     AccountExists(String),
+    /// A record's value for a `#[unique]` field is already held by another
+    /// record in the table.
+    #[error(
+        "A record's value for a `#[unique]` field is already held by another record in the table."
+    )]
+    UniqueConstraintViolated(String, String),
+    /// The record provided for a `#[foreign_key(..)]` field doesn't exist or
+    /// isn't the record the field references.
+    #[error("The record provided for a `#[foreign_key(..)]` field doesn't exist or isn't the record the field references.")]
+    ForeignKeyViolated(String, String),
+    /// The data of an account exceeds the space declared for it with
+    /// `#[max_len(..)]`.
+    #[error("The data of an account exceeds the space declared for it with `#[max_len(..)]`.")]
+    MaxLengthExceeded(String, String),
+    /// The account isn't owned by the program expected to own an account of
+    /// its type.
+    #[error("The account isn't owned by the program expected to own an account of its type.")]
+    InvalidOwner(String, String),
+    /// The account's data doesn't begin with the discriminator of its expected
+    /// type.
+    #[error("The account's data doesn't begin with the discriminator of its expected type.")]
+    DiscriminatorMismatch(String, String),
+    /// The account's address doesn't match the address derived from its seeds.
+    #[error("The account's address doesn't match the address derived from its seeds.")]
+    PdaMismatch(String, String),
+    /// A constraint declared on an instruction's parameter with
+    /// `#[has_one(..)]`, `#[constraint(..)]` or `#[address(..)]` doesn't hold.
+    #[error("A constraint declared on an instruction's parameter doesn't hold.")]
+    ConstraintViolated(String),
+    /// No ed25519 or secp256k1 program instruction in the transaction verifies
+    /// the expected signature.
+    #[error(
+        "No signature verification instruction in the transaction verifies the expected signature."
+    )]
+    SignatureNotVerified(String, String),
 }
 
 impl<T> DecodeError<T> for NautilusError {
@@ -54,6 +89,29 @@ impl FromPrimitive for NautilusError {
             203 => Some(Self::AccountNotMutable(String::default())),
             204 => Some(Self::AccountNotSigner(String::default())),
             205 => Some(Self::AccountExists(String::default())),
+            206 => Some(Self::UniqueConstraintViolated(
+                String::default(),
+                String::default(),
+            )),
+            207 => Some(Self::ForeignKeyViolated(
+                String::default(),
+                String::default(),
+            )),
+            208 => Some(Self::MaxLengthExceeded(
+                String::default(),
+                String::default(),
+            )),
+            209 => Some(Self::InvalidOwner(String::default(), String::default())),
+            210 => Some(Self::DiscriminatorMismatch(
+                String::default(),
+                String::default(),
+            )),
+            211 => Some(Self::PdaMismatch(String::default(), String::default())),
+            212 => Some(Self::ConstraintViolated(String::default())),
+            213 => Some(Self::SignatureNotVerified(
+                String::default(),
+                String::default(),
+            )),
             _ => None,
         }
     }
@@ -72,6 +130,14 @@ impl ToPrimitive for NautilusError {
             Self::AccountNotMutable(..) => Some(203),
             Self::AccountNotSigner(..) => Some(204),
             Self::AccountExists(..) => Some(205),
+            Self::UniqueConstraintViolated(..) => Some(206),
+            Self::ForeignKeyViolated(..) => Some(207),
+            Self::MaxLengthExceeded(..) => Some(208),
+            Self::InvalidOwner(..) => Some(209),
+            Self::DiscriminatorMismatch(..) => Some(210),
+            Self::PdaMismatch(..) => Some(211),
+            Self::ConstraintViolated(..) => Some(212),
+            Self::SignatureNotVerified(..) => Some(213),
         }
     }
 
@@ -123,6 +189,38 @@ impl PrintProgramError for NautilusError {
                 "This account was marked with `Create<T>` but it exists already: {}",
                 pubkey
             ),
+            Self::UniqueConstraintViolated(table_name, field_name) => error!(
+                "Another record in table `{}` already holds this value for unique field: {}",
+                table_name, field_name
+            ),
+            Self::ForeignKeyViolated(table_name, field_name) => error!(
+                "The record provided for foreign key `{}` of table `{}` doesn't exist or doesn't match",
+                field_name, table_name
+            ),
+            Self::MaxLengthExceeded(state_type, pubkey) => error!(
+                "The {} data exceeds the space declared with `#[max_len(..)]` for account: {}",
+                state_type, pubkey
+            ),
+            Self::InvalidOwner(state_type, pubkey) => error!(
+                "This account isn't owned by the program that owns {} accounts: {}",
+                state_type, pubkey
+            ),
+            Self::DiscriminatorMismatch(state_type, pubkey) => error!(
+                "This account's data doesn't begin with the {} discriminator: {}",
+                state_type, pubkey
+            ),
+            Self::PdaMismatch(state_type, pubkey) => error!(
+                "This account's address doesn't match the derived {} address: {}",
+                state_type, pubkey
+            ),
+            Self::ConstraintViolated(param_name) => error!(
+                "A constraint declared on this instruction's parameter doesn't hold: {}",
+                param_name
+            ),
+            Self::SignatureNotVerified(program, signer) => error!(
+                "No {} program instruction in this transaction verifies the expected signature of: {}",
+                program, signer
+            ),
         }
     }
 }
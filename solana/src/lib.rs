@@ -12,7 +12,9 @@ pub mod error;
 pub mod objects;
 pub mod properties;
 
+pub use bytemuck;
 pub use mpl_token_metadata;
+pub use num_traits;
 pub use solana_program;
 pub use spl_associated_token_account;
 pub use spl_token;
@@ -20,7 +22,7 @@ pub use spl_token_2022;
 pub use splogger;
 
 pub use borsh::{self, BorshDeserialize, BorshSerialize};
-pub use nautilus_derive::{nautilus, State, Table};
+pub use nautilus_derive::{declare_program, nautilus, NautilusError, State, Table};
 pub use solana_program::{
     account_info::{next_account_info, AccountInfo, IntoAccountInfo},
     declare_id, entrypoint,
@@ -33,9 +35,15 @@ pub use solana_program::{
 };
 
 pub use objects::{
-    accounts::*,
-    records::{index::*, *},
-    tokens::{associated_token::*, metadata::*, mint::*, nft::*, token::*, *},
+    accounts::{zero_copy::*, *},
+    records::{counter::*, index::*, lookup::*, *},
+    sysvars::{clock::*, instructions::*, rent::*, slot_hashes::*, *},
+    tokens::{
+        associated_token::*, associated_token_2022::*, metadata::*, mint::*, mint_2022::*, nft::*,
+        token::*, token_2022::*, *,
+    },
     wallets::*,
 };
-pub use properties::{create::*, data::*, mutable::*, signer::*, *};
+pub use properties::{
+    create::*, data::*, delete::*, mutable::*, remaining::*, signer::*, update::*, *,
+};
@@ -1,11 +1,19 @@
 //! The `Account<T>` Nautilus object and all associated trait implementations.
-use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
 
 use crate::{
-    error::NautilusError, Mut, NautilusAccount, NautilusAccountData, NautilusAccountInfo,
-    NautilusMut, NautilusTransferLamports,
+    cpi,
+    error::NautilusError,
+    properties::data::{check_data_discriminator, check_owner, check_pda, deserialize_data},
+    Mut, NautilusAccount, NautilusAccountData, NautilusAccountInfo, NautilusDelete, NautilusMut,
+    NautilusSigner, NautilusTransferLamports, NautilusUpdate,
 };
 
+pub mod zero_copy;
+
 /// The struct that allows you to create a plain-old program-derived address
 /// (PDA) account.
 ///
@@ -38,20 +46,34 @@ where
     }
 
     /// Instantiate a new PDA and load the account inner data from on-chain.
+    ///
+    /// The account must be owned by the program and its data must begin with
+    /// the discriminator of `T`. If the seeds of `T` don't take any
+    /// parameters, its address must also be the one derived from them.
     pub fn load(
         program_id: &'a Pubkey,
         account_info: Box<AccountInfo<'a>>,
     ) -> Result<Self, ProgramError> {
-        let data = match T::try_from_slice(match &account_info.try_borrow_data() {
-            Ok(acct_data) => acct_data,
-            Err(_) => {
-                return Err(NautilusError::LoadDataFailed(
-                    T::DISCRIMINATOR_STR.to_string(),
-                    account_info.key.to_string(),
-                )
-                .into())
-            }
-        }) {
+        check_owner(&account_info, program_id, T::DISCRIMINATOR_STR)?;
+        check_data_discriminator(
+            &account_info,
+            T::DISCRIMINATOR_STR,
+            T::VERSIONED,
+            T::MIGRATES_UNVERSIONED,
+        )?;
+        let data = match deserialize_data::<T>(
+            match &account_info.try_borrow_data() {
+                Ok(acct_data) => acct_data,
+                Err(_) => {
+                    return Err(NautilusError::LoadDataFailed(
+                        T::DISCRIMINATOR_STR.to_string(),
+                        account_info.key.to_string(),
+                    )
+                    .into())
+                }
+            },
+            T::SPACE,
+        ) {
             Ok(state_data) => Box::new(state_data),
             Err(_) => {
                 return Err(NautilusError::DeserializeDataFailed(
@@ -61,6 +83,9 @@ where
                 .into())
             }
         };
+        if let Some((pda, _)) = data.derived_pda(program_id) {
+            check_pda(&account_info, &pda, T::DISCRIMINATOR_STR)?;
+        }
         Ok(Self {
             program_id,
             account_info,
@@ -102,7 +127,16 @@ where
     }
 
     fn span(&self) -> Result<usize, ProgramError> {
-        Ok(self.data.try_to_vec()?.len())
+        let len = self.data.try_to_vec()?.len();
+        match T::SPACE {
+            Some(space) if len > space => Err(NautilusError::MaxLengthExceeded(
+                T::DISCRIMINATOR_STR.to_string(),
+                self.account_info.key.to_string(),
+            )
+            .into()),
+            Some(space) => Ok(space),
+            None => Ok(len),
+        }
     }
 }
 
@@ -138,3 +172,50 @@ where
         Ok(())
     }
 }
+
+impl<'a, T> NautilusDelete<'a> for Mut<Account<'a, T>>
+where
+    T: NautilusAccountData,
+{
+    fn delete_with_authorities(
+        &self,
+        authorities: Vec<AccountInfo>,
+        receiver: impl NautilusMut<'a>,
+    ) -> ProgramResult {
+        self.self_account.check_authorities(authorities)?;
+        self.transfer_lamports(receiver, self.lamports())?;
+        let account_info = self.account_info();
+        account_info.data.borrow_mut().fill(0);
+        account_info.realloc(0, false)?;
+        account_info.assign(&solana_program::system_program::ID);
+        Ok(())
+    }
+}
+
+impl<'a, T> NautilusUpdate<'a> for Mut<Account<'a, T>>
+where
+    T: NautilusAccountData,
+{
+    fn update(&self) -> ProgramResult {
+        if self.required_rent()? > self.lamports() {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+        let account_info = self.account_info();
+        account_info.realloc(self.span()?, false)?;
+        self.self_account
+            .data
+            .serialize(&mut &mut account_info.data.borrow_mut()[..])?;
+        Ok(())
+    }
+
+    fn update_with_payer(&self, payer: impl NautilusSigner<'a>) -> ProgramResult {
+        let required_rent = self.required_rent()?;
+        let lamports = self.lamports();
+        if required_rent > lamports {
+            cpi::system::transfer(payer, self.clone(), required_rent - lamports)?;
+        } else if lamports > required_rent {
+            self.transfer_lamports(Mut::new(payer)?, lamports - required_rent)?;
+        }
+        self.update()
+    }
+}
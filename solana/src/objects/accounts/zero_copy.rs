@@ -0,0 +1,382 @@
+//! The `ZeroCopyAccount<T>` Nautilus object and all associated trait
+//! implementations.
+use std::cell::{Ref, RefMut};
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::NautilusError,
+    properties::data::{check_discriminator, check_owner, check_pda, discriminator},
+    Mut, NautilusAccount, NautilusAccountData, NautilusAccountInfo, NautilusDelete, NautilusMut,
+    NautilusTransferLamports,
+};
+
+/// The struct that allows you to create a program-derived address (PDA) account
+/// whose data is accessed in place, without being deserialized.
+///
+/// A user wraps their data type `T` with `ZeroCopyAccount<'_, T>` when `T` is
+/// declared with `#[zero_copy]`. Since `T` is plain-old-data (`bytemuck::Pod`),
+/// its bytes are mapped directly from the account's data after the
+/// discriminator, so loading the account costs the same no matter how large
+/// `T` is.
+///
+/// The data is accessed through the borrow guards returned by
+/// `try_borrow_data()` and `try_borrow_mut_data()`, and any changes made
+/// through the latter are written straight to the account. A new account is
+/// created with zeroed data, so its fields are set after it's created.
+///
+/// The `account_info` field represents the PDA itself.
+#[derive(Clone)]
+pub struct ZeroCopyAccount<'a, T>
+where
+    T: NautilusAccountData + bytemuck::Pod,
+{
+    pub program_id: &'a Pubkey,
+    pub account_info: Box<AccountInfo<'a>>,
+    _data: std::marker::PhantomData<T>,
+}
+
+impl<'a, T> ZeroCopyAccount<'a, T>
+where
+    T: NautilusAccountData + bytemuck::Pod,
+{
+    /// The fixed span of the account's data: the discriminator followed by the
+    /// bytes of `T`.
+    pub const SPACE: usize = 8 + std::mem::size_of::<T>();
+
+    /// Instantiate a new PDA without checking the account inner data.
+    pub fn new(program_id: &'a Pubkey, account_info: Box<AccountInfo<'a>>) -> Self {
+        Self {
+            program_id,
+            account_info,
+            _data: std::marker::PhantomData,
+        }
+    }
+
+    /// Instantiate a PDA from an existing on-chain account, checking that its
+    /// data is a `T`.
+    ///
+    /// Unlike `Account::load(..)`, the data is not deserialized, but only
+    /// checked for the right owner, discriminator and span - and address, if
+    /// the seeds of `T` don't take any parameters.
+    pub fn load(
+        program_id: &'a Pubkey,
+        account_info: Box<AccountInfo<'a>>,
+    ) -> Result<Self, ProgramError> {
+        check_owner(&account_info, program_id, T::DISCRIMINATOR_STR)?;
+        check_discriminator(&account_info, T::DISCRIMINATOR_STR)?;
+        let account = Self::new(program_id, account_info);
+        {
+            let data = match account.account_info.try_borrow_data() {
+                Ok(data) => data,
+                Err(_) => {
+                    return Err(NautilusError::LoadDataFailed(
+                        T::DISCRIMINATOR_STR.to_string(),
+                        account.account_info.key.to_string(),
+                    )
+                    .into())
+                }
+            };
+            if data.len() != Self::SPACE {
+                return Err(NautilusError::DeserializeDataFailed(
+                    T::DISCRIMINATOR_STR.to_string(),
+                    account.account_info.key.to_string(),
+                )
+                .into());
+            }
+        }
+        if let Some((pda, _)) = account.try_borrow_data()?.derived_pda(program_id) {
+            check_pda(&account.account_info, &pda, T::DISCRIMINATOR_STR)?;
+        }
+        Ok(account)
+    }
+
+    /// Borrows the account's data as a `T`.
+    pub fn try_borrow_data(&self) -> Result<Ref<'_, T>, ProgramError> {
+        let data = self.account_info.try_borrow_data()?;
+        Self::check_layout(&data)?;
+        Ok(Ref::map(data, |data| {
+            bytemuck::from_bytes(&data[8..Self::SPACE])
+        }))
+    }
+
+    /// Mutably borrows the account's data as a `T`. Any changes are written
+    /// directly to the account.
+    pub fn try_borrow_mut_data(&self) -> Result<RefMut<'_, T>, ProgramError> {
+        let data = self.account_info.try_borrow_mut_data()?;
+        Self::check_layout(&data)?;
+        Ok(RefMut::map(data, |data| {
+            bytemuck::from_bytes_mut(&mut data[8..Self::SPACE])
+        }))
+    }
+
+    /// Checks that the account's data is large enough and aligned to be
+    /// mapped to a `T`.
+    fn check_layout(data: &[u8]) -> ProgramResult {
+        if data.len() < Self::SPACE || bytemuck::try_from_bytes::<T>(&data[8..Self::SPACE]).is_err()
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T> NautilusAccountInfo<'a> for ZeroCopyAccount<'a, T>
+where
+    T: NautilusAccountData + bytemuck::Pod,
+{
+    fn account_info(&self) -> Box<AccountInfo<'a>> {
+        self.account_info.clone()
+    }
+
+    fn key(&self) -> &'a Pubkey {
+        self.account_info.key
+    }
+
+    fn is_signer(&self) -> bool {
+        self.account_info.is_signer
+    }
+
+    fn is_writable(&self) -> bool {
+        self.account_info.is_writable
+    }
+
+    fn lamports(&self) -> u64 {
+        self.account_info.lamports()
+    }
+
+    fn mut_lamports(&self) -> Result<std::cell::RefMut<'_, &'a mut u64>, ProgramError> {
+        self.account_info.try_borrow_mut_lamports()
+    }
+
+    fn owner(&self) -> &'a Pubkey {
+        self.account_info.owner
+    }
+
+    fn span(&self) -> Result<usize, ProgramError> {
+        Ok(Self::SPACE)
+    }
+}
+
+impl<'a, T> NautilusAccount<'a> for ZeroCopyAccount<'a, T>
+where
+    T: NautilusAccountData + bytemuck::Pod,
+{
+    fn discriminator(&self) -> [u8; 8] {
+        discriminator(T::DISCRIMINATOR_STR)
+    }
+
+    fn check_authorities(&self, accounts: Vec<AccountInfo>) -> Result<(), ProgramError> {
+        self.try_borrow_data()?.check_authorities(accounts)
+    }
+
+    fn count_authorities(&self) -> u8 {
+        self.try_borrow_data()
+            .map_or(0, |data| data.count_authorities())
+    }
+}
+
+impl<'a, T> NautilusTransferLamports<'a> for Mut<ZeroCopyAccount<'a, T>>
+where
+    T: NautilusAccountData + bytemuck::Pod,
+{
+    fn transfer_lamports(
+        &self,
+        to: impl NautilusMut<'a>,
+        amount: u64,
+    ) -> solana_program::entrypoint::ProgramResult {
+        let from = self.account_info();
+        **from.try_borrow_mut_lamports()? -= amount;
+        **to.mut_lamports()? += amount;
+        Ok(())
+    }
+}
+
+impl<'a, T> NautilusDelete<'a> for Mut<ZeroCopyAccount<'a, T>>
+where
+    T: NautilusAccountData + bytemuck::Pod,
+{
+    fn delete_with_authorities(
+        &self,
+        authorities: Vec<AccountInfo>,
+        receiver: impl NautilusMut<'a>,
+    ) -> ProgramResult {
+        self.self_account.check_authorities(authorities)?;
+        self.transfer_lamports(receiver, self.lamports())?;
+        let account_info = self.account_info();
+        account_info.data.borrow_mut().fill(0);
+        account_info.realloc(0, false)?;
+        account_info.assign(&solana_program::system_program::ID);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+    use super::ZeroCopyAccount;
+    use crate::{error::NautilusError, properties::data::discriminator, NautilusAccountData};
+
+    /// A `#[zero_copy]` account data type, which the user declares as
+    /// `bytemuck::Pod`.
+    #[repr(C)]
+    #[derive(Clone, Copy, Default, BorshDeserialize, BorshSerialize)]
+    struct Tally {
+        count: [u8; 8],
+    }
+
+    unsafe impl bytemuck::Zeroable for Tally {}
+
+    unsafe impl bytemuck::Pod for Tally {}
+
+    impl NautilusAccountData for Tally {
+        const DISCRIMINATOR_STR: &'static str = "tally";
+
+        fn check_authorities(&self, _accounts: Vec<AccountInfo>) -> Result<(), ProgramError> {
+            Ok(())
+        }
+
+        fn count_authorities(&self) -> u8 {
+            0
+        }
+    }
+
+    fn tally_data() -> Vec<u8> {
+        let mut data = discriminator("tally").to_vec();
+        data.resize(ZeroCopyAccount::<Tally>::SPACE, 0);
+        data
+    }
+
+    #[test]
+    fn load_maps_data_in_place() {
+        let (program_id, key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut lamports = 0;
+        let mut data = tally_data();
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        let account = ZeroCopyAccount::<Tally>::load(&program_id, Box::new(account_info)).unwrap();
+
+        account.try_borrow_mut_data().unwrap().count = [7; 8];
+        assert_eq!(account.try_borrow_data().unwrap().count, [7; 8]);
+        assert_eq!(account.account_info.try_borrow_data().unwrap()[8..], [7; 8]);
+    }
+
+    #[test]
+    fn shared_borrows_coexist() {
+        let (program_id, key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut lamports = 0;
+        let mut data = tally_data();
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        let account = ZeroCopyAccount::<Tally>::new(&program_id, Box::new(account_info));
+
+        let first = account.try_borrow_data().unwrap();
+        let second = account.try_borrow_data().unwrap();
+        assert_eq!(first.count, second.count);
+    }
+
+    #[test]
+    fn rejects_conflicting_borrows() {
+        let (program_id, key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut lamports = 0;
+        let mut data = tally_data();
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        let account = ZeroCopyAccount::<Tally>::new(&program_id, Box::new(account_info));
+
+        {
+            let _data = account.try_borrow_data().unwrap();
+            assert_eq!(
+                account.try_borrow_mut_data().err(),
+                Some(ProgramError::AccountBorrowFailed)
+            );
+        }
+        {
+            let _data = account.try_borrow_mut_data().unwrap();
+            assert_eq!(
+                account.try_borrow_data().err(),
+                Some(ProgramError::AccountBorrowFailed)
+            );
+            assert_eq!(
+                account.try_borrow_mut_data().err(),
+                Some(ProgramError::AccountBorrowFailed)
+            );
+        }
+        assert!(account.try_borrow_mut_data().is_ok());
+    }
+
+    #[test]
+    fn borrow_rejects_data_shorter_than_span() {
+        let (program_id, key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut lamports = 0;
+        let mut data = discriminator("tally").to_vec();
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        let account = ZeroCopyAccount::<Tally>::new(&program_id, Box::new(account_info));
+
+        assert_eq!(
+            account.try_borrow_data().err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn load_rejects_wrong_span() {
+        let (program_id, key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut lamports = 0;
+        let mut data = tally_data();
+        data.push(0);
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+
+        assert_eq!(
+            ZeroCopyAccount::<Tally>::load(&program_id, Box::new(account_info)).err(),
+            Some(NautilusError::DeserializeDataFailed(String::new(), String::new()).into())
+        );
+    }
+}
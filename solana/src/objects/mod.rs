@@ -2,5 +2,6 @@
 //! implementations.
 pub mod accounts;
 pub mod records;
+pub mod sysvars;
 pub mod tokens;
 pub mod wallets;
@@ -0,0 +1,391 @@
+//! The special `NautilusCounter` Nautilus object, which keeps the record count
+//! for a single table.
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    cpi,
+    error::NautilusError,
+    properties::data::{check_discriminator, check_owner, check_pda},
+    NautilusAccountInfo, NautilusSigner,
+};
+
+/// The seed prefix shared by every table counter.
+pub const COUNTER_SEED_PREFIX: &str = "nautilus_counter";
+
+/// The account inner data for a `NautilusCounter`.
+///
+/// The `count` is the last primary key handed out for the counter's table, so
+/// it never goes down, while `records` is the number of records currently in
+/// the table.
+#[derive(Clone, Default)]
+pub struct NautilusCounterData {
+    pub count: u64,
+    pub records: u64,
+}
+
+impl NautilusCounterData {
+    /// The 8-bit discriminator applied to every table counter.
+    pub fn discriminator(&self) -> [u8; 8] {
+        let mut discriminator = [0u8; 8];
+        let preimage = format!("{}:{}", "global", COUNTER_SEED_PREFIX);
+        discriminator
+            .copy_from_slice(&solana_program::hash::hash(preimage.as_bytes()).to_bytes()[..8]);
+        discriminator
+    }
+}
+
+impl borsh::de::BorshDeserialize for NautilusCounterData {
+    fn deserialize(buf: &mut &[u8]) -> ::core::result::Result<Self, borsh::maybestd::io::Error> {
+        let _discrim: [u8; 8] = borsh::BorshDeserialize::deserialize(buf)?; // Skip the first 8 bytes for discriminator
+        Ok(Self {
+            count: borsh::BorshDeserialize::deserialize(buf)?,
+            records: borsh::BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
+impl borsh::ser::BorshSerialize for NautilusCounterData {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> ::core::result::Result<(), borsh::maybestd::io::Error> {
+        borsh::BorshSerialize::serialize(&self.discriminator(), writer)?; // Serialize the discriminator first
+        borsh::BorshSerialize::serialize(&self.count, writer)?;
+        borsh::BorshSerialize::serialize(&self.records, writer)?;
+        Ok(())
+    }
+}
+
+/// The special Nautilus object representing the record counter for a single
+/// table, used by tables declared with
+/// `#[primary_key(autoincrement = true, counter = "table")]`.
+///
+/// Unlike the `NautilusIndex`, which holds every table's count in one account,
+/// each table's counter is its own PDA seeded by the table name. Records of
+/// different tables can then be created in parallel, and the counter is
+/// created with the table's first record so it never needs to be initialized
+/// ahead of time.
+#[derive(Clone)]
+pub struct NautilusCounter<'a> {
+    pub program_id: &'a Pubkey,
+    pub account_info: Box<AccountInfo<'a>>,
+    pub data: NautilusCounterData,
+}
+
+impl<'a> NautilusCounter<'a> {
+    /// Instantiate a `NautilusCounter` for a table, loading the account inner
+    /// data from on-chain if the counter exists.
+    ///
+    /// The account's address must be the one derived from the table's name, and
+    /// an existing counter must be owned by the program.
+    pub fn load(
+        program_id: &'a Pubkey,
+        table_name: &str,
+        account_info: Box<AccountInfo<'a>>,
+    ) -> Result<Self, ProgramError> {
+        check_pda(
+            &account_info,
+            &Self::pda(program_id, table_name).0,
+            COUNTER_SEED_PREFIX,
+        )?;
+        let data = match account_info.data_is_empty() {
+            true => NautilusCounterData::default(),
+            false => {
+                check_owner(&account_info, program_id, COUNTER_SEED_PREFIX)?;
+                check_discriminator(&account_info, COUNTER_SEED_PREFIX)?;
+                match NautilusCounterData::try_from_slice(match &account_info.try_borrow_data() {
+                    Ok(acct_data) => acct_data,
+                    Err(_) => {
+                        return Err(NautilusError::LoadDataFailed(
+                            COUNTER_SEED_PREFIX.to_string(),
+                            account_info.key.to_string(),
+                        )
+                        .into())
+                    }
+                }) {
+                    Ok(state_data) => state_data,
+                    Err(_) => {
+                        return Err(NautilusError::DeserializeDataFailed(
+                            COUNTER_SEED_PREFIX.to_string(),
+                            account_info.key.to_string(),
+                        )
+                        .into())
+                    }
+                }
+            }
+        };
+        Ok(Self {
+            program_id,
+            account_info,
+            data,
+        })
+    }
+
+    /// The seeds used to derive the program-derived address of a table's
+    /// counter.
+    pub fn seeds(table_name: &str) -> Vec<Vec<u8>> {
+        vec![
+            COUNTER_SEED_PREFIX.as_bytes().to_vec(),
+            table_name.as_bytes().to_vec(),
+        ]
+    }
+
+    /// Returns the program-derived address and bump of a table's counter.
+    pub fn pda(program_id: &Pubkey, table_name: &str) -> (Pubkey, u8) {
+        let seeds_vec = Self::seeds(table_name);
+        let seeds: Vec<&[u8]> = seeds_vec.iter().map(AsRef::as_ref).collect();
+        Pubkey::find_program_address(&seeds, program_id)
+    }
+
+    pub fn get_count(&self) -> u64 {
+        self.data.count
+    }
+
+    pub fn get_next_count(&self) -> u64 {
+        self.data.count + 1
+    }
+
+    pub fn get_record_count(&self) -> u64 {
+        self.data.records
+    }
+
+    /// Adds a new record to the table's count, creating the counter if this is
+    /// the table's first record.
+    pub fn add_record(
+        &mut self,
+        table_name: &str,
+        fee_payer: impl NautilusSigner<'a>,
+    ) -> Result<u64, ProgramError> {
+        let (pda, bump) = Self::pda(self.program_id, table_name);
+        check_pda(&self.account_info, &pda, COUNTER_SEED_PREFIX)?;
+        self.data.count += 1;
+        self.data.records += 1;
+        match self.account_info.data_is_empty() {
+            true => {
+                let mut signer_seeds_vec = Self::seeds(table_name);
+                signer_seeds_vec.push(vec![bump]);
+                let signer_seeds: Vec<&[u8]> = signer_seeds_vec.iter().map(AsRef::as_ref).collect();
+                cpi::system::create_pda(
+                    self.clone(),
+                    self.program_id,
+                    fee_payer,
+                    Box::new(self.data.clone()),
+                    signer_seeds,
+                )?;
+            }
+            false => self
+                .data
+                .serialize(&mut &mut self.account_info.data.borrow_mut()[..])?,
+        }
+        Ok(self.data.count)
+    }
+
+    /// Removes a deleted record from the table's number of records.
+    pub fn remove_record(&mut self, table_name: &str) -> ProgramResult {
+        check_pda(
+            &self.account_info,
+            &Self::pda(self.program_id, table_name).0,
+            COUNTER_SEED_PREFIX,
+        )?;
+        if self.account_info.data_is_empty() || self.data.records == 0 {
+            return Ok(());
+        }
+        self.data.records -= 1;
+        self.data
+            .serialize(&mut &mut self.account_info.data.borrow_mut()[..])?;
+        Ok(())
+    }
+}
+
+impl<'a> NautilusAccountInfo<'a> for NautilusCounter<'a> {
+    fn account_info(&self) -> Box<AccountInfo<'a>> {
+        self.account_info.clone()
+    }
+
+    fn key(&self) -> &'a Pubkey {
+        self.account_info.key
+    }
+
+    fn is_signer(&self) -> bool {
+        self.account_info.is_signer
+    }
+
+    fn is_writable(&self) -> bool {
+        self.account_info.is_writable
+    }
+
+    fn lamports(&self) -> u64 {
+        self.account_info.lamports()
+    }
+
+    fn mut_lamports(&self) -> Result<std::cell::RefMut<'_, &'a mut u64>, ProgramError> {
+        self.account_info.try_borrow_mut_lamports()
+    }
+
+    fn owner(&self) -> &'a Pubkey {
+        self.account_info.owner
+    }
+
+    fn span(&self) -> Result<usize, ProgramError> {
+        Ok(self.data.try_to_vec()?.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+    use super::{NautilusCounter, NautilusCounterData};
+    use crate::{error::NautilusError, Signer, Wallet};
+
+    fn counter_data(count: u64, records: u64) -> Vec<u8> {
+        NautilusCounterData { count, records }.try_to_vec().unwrap()
+    }
+
+    fn load_counter<'a>(
+        program_id: &'a Pubkey,
+        key: &'a Pubkey,
+        owner: &'a Pubkey,
+        lamports: &'a mut u64,
+        data: &'a mut [u8],
+    ) -> Result<NautilusCounter<'a>, ProgramError> {
+        let account_info = AccountInfo::new(key, false, true, lamports, data, owner, false, 0);
+        NautilusCounter::load(program_id, "person", Box::new(account_info))
+    }
+
+    #[test]
+    fn counter_address_depends_on_table() {
+        let program_id = Pubkey::new_unique();
+        assert_ne!(
+            NautilusCounter::pda(&program_id, "person").0,
+            NautilusCounter::pda(&program_id, "pet").0
+        );
+    }
+
+    #[test]
+    fn load_starts_empty_counter_without_data() {
+        let program_id = Pubkey::new_unique();
+        let key = NautilusCounter::pda(&program_id, "person").0;
+        let system_program = solana_program::system_program::ID;
+        let mut lamports = 0;
+        let mut data = vec![];
+
+        let counter =
+            load_counter(&program_id, &key, &system_program, &mut lamports, &mut data).unwrap();
+        assert_eq!(counter.get_count(), 0);
+        assert_eq!(counter.get_next_count(), 1);
+        assert_eq!(counter.get_record_count(), 0);
+    }
+
+    #[test]
+    fn load_rejects_wrong_address() {
+        let program_id = Pubkey::new_unique();
+        let key = NautilusCounter::pda(&program_id, "pet").0;
+        let mut lamports = 0;
+        let mut data = counter_data(3, 2);
+
+        assert_eq!(
+            load_counter(&program_id, &key, &program_id, &mut lamports, &mut data).err(),
+            Some(NautilusError::PdaMismatch(String::new(), String::new()).into())
+        );
+    }
+
+    #[test]
+    fn load_rejects_wrong_owner() {
+        let program_id = Pubkey::new_unique();
+        let key = NautilusCounter::pda(&program_id, "person").0;
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = counter_data(3, 2);
+
+        assert_eq!(
+            load_counter(&program_id, &key, &owner, &mut lamports, &mut data).err(),
+            Some(NautilusError::InvalidOwner(String::new(), String::new()).into())
+        );
+    }
+
+    #[test]
+    fn load_rejects_wrong_discriminator() {
+        let program_id = Pubkey::new_unique();
+        let key = NautilusCounter::pda(&program_id, "person").0;
+        let mut lamports = 0;
+        let mut data = counter_data(3, 2);
+        data[0] ^= 1;
+
+        assert_eq!(
+            load_counter(&program_id, &key, &program_id, &mut lamports, &mut data).err(),
+            Some(NautilusError::DiscriminatorMismatch(String::new(), String::new()).into())
+        );
+    }
+
+    #[test]
+    fn add_and_remove_records() {
+        let program_id = Pubkey::new_unique();
+        let key = NautilusCounter::pda(&program_id, "person").0;
+        let (payer_key, system_program) =
+            (Pubkey::new_unique(), solana_program::system_program::ID);
+        let (mut lamports, mut payer_lamports, mut system_program_lamports) = (0, 0, 0);
+        let mut data = counter_data(3, 2);
+        let (mut payer_data, mut system_program_data) = (vec![], vec![]);
+        let payer_info = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            &mut payer_lamports,
+            &mut payer_data,
+            &system_program,
+            false,
+            0,
+        );
+        let system_program_info = AccountInfo::new(
+            &system_program,
+            false,
+            false,
+            &mut system_program_lamports,
+            &mut system_program_data,
+            &system_program,
+            true,
+            0,
+        );
+        let payer = Signer::new(Wallet::new(
+            Box::new(payer_info),
+            Box::new(system_program_info),
+        ))
+        .unwrap();
+
+        let mut counter =
+            load_counter(&program_id, &key, &program_id, &mut lamports, &mut data).unwrap();
+        assert_eq!(counter.add_record("person", payer).unwrap(), 4);
+        assert_eq!(counter.get_record_count(), 3);
+        counter.remove_record("person").unwrap();
+        counter.remove_record("person").unwrap();
+        assert_eq!(counter.get_count(), 4);
+        assert_eq!(counter.get_record_count(), 1);
+
+        let stored =
+            NautilusCounterData::try_from_slice(&counter.account_info.try_borrow_data().unwrap())
+                .unwrap();
+        assert_eq!((stored.count, stored.records), (4, 1));
+    }
+
+    #[test]
+    fn remove_record_rejects_another_tables_counter() {
+        let program_id = Pubkey::new_unique();
+        let key = NautilusCounter::pda(&program_id, "person").0;
+        let mut lamports = 0;
+        let mut data = counter_data(3, 2);
+
+        let mut counter =
+            load_counter(&program_id, &key, &program_id, &mut lamports, &mut data).unwrap();
+        assert_eq!(
+            counter.remove_record("pet").err(),
+            Some(NautilusError::PdaMismatch(String::new(), String::new()).into())
+        );
+        assert_eq!(counter.get_record_count(), 2);
+    }
+}
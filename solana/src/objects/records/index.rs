@@ -7,7 +7,10 @@ use solana_program::{
 };
 
 use crate::{
-    cpi, error::NautilusError, Create, Mut, NautilusAccountInfo, NautilusMut, NautilusRecord,
+    cpi,
+    error::NautilusError,
+    properties::data::{check_discriminator, check_owner, check_pda},
+    Create, Mut, NautilusAccountInfo, NautilusCounter, NautilusMut, NautilusRecord,
     NautilusRecordData, NautilusSigner, NautilusTransferLamports, Signer, Wallet,
 };
 
@@ -19,9 +22,14 @@ use crate::{
 ///
 /// This data is kept in one single account and used as a reference to enable
 /// autoincrementing of records.
+///
+/// A table's count is the last primary key handed out, so it never goes down.
+/// The number of records currently in each table is kept separately in
+/// `records`, which is `None` for an index created before it was kept.
 #[derive(Clone, Default)]
 pub struct NautilusIndexData {
     pub index: std::collections::HashMap<String, u32>,
+    pub records: Option<std::collections::HashMap<String, u32>>,
 }
 
 impl NautilusIndexData {
@@ -38,8 +46,34 @@ impl NautilusIndexData {
         }
     }
 
+    /// Get the number of records currently in a table.
+    pub fn get_record_count(&self, table_name: &str) -> Option<u32> {
+        self.records
+            .as_ref()
+            .map(|records| records.get(table_name).copied().unwrap_or(0))
+    }
+
+    /// Remove a deleted record from the table's number of records, returning
+    /// whether it changed.
+    pub fn remove_record(&mut self, table_name: &str) -> bool {
+        match self
+            .records
+            .as_mut()
+            .and_then(|records| records.get_mut(table_name))
+        {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Add a new record to the index.
     pub fn add_record(&mut self, table_name: &str) -> u32 {
+        if let Some(records) = self.records.as_mut() {
+            *records.entry(table_name.to_string()).or_insert(0) += 1;
+        }
         match self.index.get_mut(&(table_name.to_string())) {
             Some(count) => {
                 *count += 1;
@@ -59,9 +93,13 @@ where
 {
     fn deserialize(buf: &mut &[u8]) -> ::core::result::Result<Self, borsh::maybestd::io::Error> {
         let _discrim: [u8; 8] = borsh::BorshDeserialize::deserialize(buf)?; // Skip the first 8 bytes for discriminator
-        Ok(Self {
-            index: borsh::BorshDeserialize::deserialize(buf)?,
-        })
+        let index = borsh::BorshDeserialize::deserialize(buf)?;
+        // An index created before the number of records was kept ends here.
+        let records = match buf.is_empty() {
+            true => None,
+            false => Some(borsh::BorshDeserialize::deserialize(buf)?),
+        };
+        Ok(Self { index, records })
     }
 }
 impl borsh::ser::BorshSerialize for NautilusIndexData
@@ -74,6 +112,9 @@ where
     ) -> ::core::result::Result<(), borsh::maybestd::io::Error> {
         borsh::BorshSerialize::serialize(&self.discriminator(), writer)?; // Serialize the discriminator first
         borsh::BorshSerialize::serialize(&self.index, writer)?;
+        if let Some(records) = &self.records {
+            borsh::BorshSerialize::serialize(records, writer)?;
+        }
         Ok(())
     }
 }
@@ -122,11 +163,13 @@ impl<'a> NautilusIndex<'a> {
     }
 
     /// Instantiate a new `NautilusIndex` and load the account inner data from
-    /// on-chain.
+    /// on-chain, checking that the account is the program's index.
     pub fn load(
         program_id: &'a Pubkey,
         account_info: Box<AccountInfo<'a>>,
     ) -> Result<Self, ProgramError> {
+        check_owner(&account_info, program_id, NautilusIndexData::TABLE_NAME)?;
+        check_discriminator(&account_info, NautilusIndexData::TABLE_NAME)?;
         let data = match NautilusIndexData::try_from_slice(match &account_info.try_borrow_data() {
             Ok(acct_data) => acct_data,
             Err(_) => {
@@ -146,6 +189,11 @@ impl<'a> NautilusIndex<'a> {
                 .into());
             }
         };
+        check_pda(
+            &account_info,
+            &data.pda(program_id).0,
+            NautilusIndexData::TABLE_NAME,
+        )?;
         Ok(Self {
             program_id,
             account_info,
@@ -161,6 +209,22 @@ impl<'a> NautilusIndex<'a> {
         self.data.get_next_count(table_name)
     }
 
+    pub fn get_record_count(&self, table_name: &str) -> Option<u32> {
+        self.data.get_record_count(table_name)
+    }
+
+    /// Removes a deleted record from the table's number of records.
+    ///
+    /// The account's size doesn't change, since the table's entry already
+    /// exists, so no payer is needed.
+    pub fn remove_record(&mut self, table_name: &str) -> ProgramResult {
+        if self.data.remove_record(table_name) {
+            self.data
+                .serialize(&mut &mut self.account_info.data.borrow_mut()[..])?;
+        }
+        Ok(())
+    }
+
     pub fn add_record(
         &mut self,
         table_name: &str,
@@ -263,6 +327,7 @@ impl<'a> Create<'a, NautilusIndex<'a>> {
         })?;
         let data = NautilusIndexData {
             index: std::collections::HashMap::new(),
+            records: Some(std::collections::HashMap::new()),
         };
         let data_pointer = Box::new(data);
         let (pda, bump) = self.pda();
@@ -291,6 +356,7 @@ impl<'a> Create<'a, NautilusIndex<'a>> {
     pub fn create_with_payer(&mut self, payer: impl NautilusSigner<'a>) -> ProgramResult {
         let data = NautilusIndexData {
             index: std::collections::HashMap::new(),
+            records: Some(std::collections::HashMap::new()),
         };
         let data_pointer = Box::new(data);
         let (pda, bump) = self.pda();
@@ -340,3 +406,115 @@ impl<'a> NautilusRecord<'a> for Create<'a, NautilusIndex<'a>> {
         self.self_account.count_authorities()
     }
 }
+
+/// The source of autoincremented primary keys for a table's records.
+///
+/// Tables use the program-wide `NautilusIndex` by default, or their own
+/// `NautilusCounter` when declared with
+/// `#[primary_key(autoincrement = true, counter = "table")]`.
+#[derive(Clone)]
+pub enum NautilusRecordIndex<'a> {
+    Global(NautilusIndex<'a>),
+    Table(NautilusCounter<'a>),
+}
+
+impl<'a> NautilusRecordIndex<'a> {
+    pub fn get_count(&self, table_name: &str) -> Option<u64> {
+        match self {
+            Self::Global(index) => index.get_count(table_name).map(u64::from),
+            Self::Table(counter) => Some(counter.get_count()),
+        }
+    }
+
+    pub fn get_next_count(&self, table_name: &str) -> u64 {
+        match self {
+            Self::Global(index) => index.get_next_count(table_name).into(),
+            Self::Table(counter) => counter.get_next_count(),
+        }
+    }
+
+    /// The number of records currently in the table. Unlike its count, this
+    /// goes down when a record is deleted.
+    ///
+    /// Returns `None` if the program's `NautilusIndex` was created before the
+    /// number of records was kept.
+    pub fn get_record_count(&self, table_name: &str) -> Option<u64> {
+        match self {
+            Self::Global(index) => index.get_record_count(table_name).map(u64::from),
+            Self::Table(counter) => Some(counter.get_record_count()),
+        }
+    }
+
+    /// Removes a deleted record from the table's number of records, leaving
+    /// its count - and so the primary keys handed out - as-is.
+    pub fn remove_record(&mut self, table_name: &str) -> ProgramResult {
+        match self {
+            Self::Global(index) => index.remove_record(table_name),
+            Self::Table(counter) => counter.remove_record(table_name),
+        }
+    }
+
+    /// Adds a new record to the table's count and returns the new count, which
+    /// is the record's primary key.
+    pub fn add_record(
+        &mut self,
+        table_name: &str,
+        fee_payer: impl NautilusSigner<'a>,
+    ) -> Result<u64, ProgramError> {
+        match self {
+            Self::Global(index) => index.add_record(table_name, fee_payer).map(u64::from),
+            Self::Table(counter) => counter.add_record(table_name, fee_payer),
+        }
+    }
+}
+
+impl<'a> From<NautilusIndex<'a>> for NautilusRecordIndex<'a> {
+    fn from(value: NautilusIndex<'a>) -> Self {
+        Self::Global(value)
+    }
+}
+
+impl<'a> From<NautilusCounter<'a>> for NautilusRecordIndex<'a> {
+    fn from(value: NautilusCounter<'a>) -> Self {
+        Self::Table(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    use super::NautilusIndexData;
+    use crate::NautilusRecordData;
+
+    #[test]
+    fn remove_record_keeps_count() {
+        let mut data = NautilusIndexData {
+            index: Default::default(),
+            records: Some(Default::default()),
+        };
+        data.add_record("person");
+        data.add_record("person");
+        assert!(data.remove_record("person"));
+
+        assert_eq!(data.get_count("person"), Some(2));
+        assert_eq!(data.get_next_count("person"), 3);
+        assert_eq!(data.get_record_count("person"), Some(1));
+        assert_eq!(data.get_record_count("car"), Some(0));
+        assert!(!data.remove_record("car"));
+    }
+
+    #[test]
+    fn deserialize_index_without_record_counts() {
+        let mut bytes = NautilusIndexData::default().discriminator().to_vec();
+        let index: std::collections::HashMap<String, u32> =
+            [("person".to_string(), 4)].into_iter().collect();
+        bytes.extend(index.try_to_vec().unwrap());
+
+        let mut data = NautilusIndexData::try_from_slice(&bytes).unwrap();
+        assert_eq!(data.get_count("person"), Some(4));
+        assert_eq!(data.get_record_count("person"), None);
+        assert!(!data.remove_record("person"));
+        assert_eq!(data.try_to_vec().unwrap(), bytes);
+    }
+}
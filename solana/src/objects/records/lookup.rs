@@ -0,0 +1,474 @@
+//! The `NautilusLookup` Nautilus object, which backs the secondary indexes
+//! declared on a table with `#[index]` or `#[unique]`.
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, hash::hash, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    cpi,
+    error::NautilusError,
+    properties::data::{check_discriminator, check_owner, check_pda, discriminator},
+    Mut, NautilusAccountInfo, NautilusMut, NautilusSigner, NautilusTransferLamports,
+};
+
+/// The seed prefix shared by every lookup account.
+pub const LOOKUP_SEED_PREFIX: &str = "nautilus_lookup";
+
+/// A secondary index declared on a table's field, along with the value of that
+/// field for a particular record.
+///
+/// Returned by `NautilusRecordData::lookups(..)`, which is implemented by the
+/// derive macro `#[derive(nautilus::Table)]` for each field annotated with
+/// `#[index]` or `#[unique]`.
+#[derive(Clone, Debug)]
+pub struct NautilusLookupKey {
+    pub table_name: &'static str,
+    pub field_name: &'static str,
+    pub value: Vec<u8>,
+    pub unique: bool,
+}
+
+impl NautilusLookupKey {
+    /// The seeds used to derive the program-derived address of the lookup
+    /// account for this field value.
+    ///
+    /// The value is hashed into a single 32-byte seed, since a field such as a
+    /// `String` can hold more bytes than a seed allows.
+    pub fn seeds(&self) -> Vec<Vec<u8>> {
+        vec![
+            LOOKUP_SEED_PREFIX.as_bytes().to_vec(),
+            self.table_name.as_bytes().to_vec(),
+            self.field_name.as_bytes().to_vec(),
+            hash(&self.value).to_bytes().to_vec(),
+        ]
+    }
+
+    /// Returns the program-derived address and bump of the lookup account for
+    /// this field value.
+    pub fn pda(&self, program_id: &Pubkey) -> (Pubkey, u8) {
+        let seeds_vec = self.seeds();
+        let seeds: Vec<&[u8]> = seeds_vec.iter().map(AsRef::as_ref).collect();
+        Pubkey::find_program_address(&seeds, program_id)
+    }
+}
+
+/// The account inner data for a `NautilusLookup`.
+///
+/// This is simply the primary keys of every record whose indexed field holds
+/// the lookup's value. For a `#[unique]` field there is at most one.
+#[derive(Clone, Default)]
+pub struct NautilusLookupData {
+    pub primary_keys: Vec<Vec<u8>>,
+}
+
+impl NautilusLookupData {
+    /// The 8-bit discriminator applied to every lookup account.
+    pub fn discriminator(&self) -> [u8; 8] {
+        discriminator(LOOKUP_SEED_PREFIX)
+    }
+}
+
+impl borsh::de::BorshDeserialize for NautilusLookupData {
+    fn deserialize(buf: &mut &[u8]) -> ::core::result::Result<Self, borsh::maybestd::io::Error> {
+        let _discrim: [u8; 8] = borsh::BorshDeserialize::deserialize(buf)?; // Skip the first 8 bytes for discriminator
+        Ok(Self {
+            primary_keys: borsh::BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
+impl borsh::ser::BorshSerialize for NautilusLookupData {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> ::core::result::Result<(), borsh::maybestd::io::Error> {
+        borsh::BorshSerialize::serialize(&self.discriminator(), writer)?; // Serialize the discriminator first
+        borsh::BorshSerialize::serialize(&self.primary_keys, writer)?;
+        Ok(())
+    }
+}
+
+/// The special Nautilus object representing a lookup account, which maps the
+/// value of an indexed field to the primary keys of the records holding it.
+///
+/// Lookup accounts are created, updated and closed alongside the records they
+/// index, so they never need to be managed directly.
+#[derive(Clone)]
+pub struct NautilusLookup<'a> {
+    pub program_id: &'a Pubkey,
+    pub account_info: Box<AccountInfo<'a>>,
+    pub key: NautilusLookupKey,
+    pub data: NautilusLookupData,
+}
+
+impl<'a> NautilusLookup<'a> {
+    /// Instantiate a `NautilusLookup` for a field value, loading the account
+    /// inner data from on-chain if the lookup account exists.
+    ///
+    /// The account's address must be the one derived from the field value, and
+    /// an existing lookup account must be owned by the program.
+    pub fn load(
+        program_id: &'a Pubkey,
+        account_info: Box<AccountInfo<'a>>,
+        key: NautilusLookupKey,
+    ) -> Result<Self, ProgramError> {
+        check_pda(&account_info, &key.pda(program_id).0, LOOKUP_SEED_PREFIX)?;
+        let data = match account_info.data_is_empty() {
+            true => NautilusLookupData::default(),
+            false => {
+                check_owner(&account_info, program_id, LOOKUP_SEED_PREFIX)?;
+                check_discriminator(&account_info, LOOKUP_SEED_PREFIX)?;
+                match NautilusLookupData::try_from_slice(match &account_info.try_borrow_data() {
+                    Ok(acct_data) => acct_data,
+                    Err(_) => {
+                        return Err(NautilusError::LoadDataFailed(
+                            LOOKUP_SEED_PREFIX.to_string(),
+                            account_info.key.to_string(),
+                        )
+                        .into())
+                    }
+                }) {
+                    Ok(state_data) => state_data,
+                    Err(_) => {
+                        return Err(NautilusError::DeserializeDataFailed(
+                            LOOKUP_SEED_PREFIX.to_string(),
+                            account_info.key.to_string(),
+                        )
+                        .into())
+                    }
+                }
+            }
+        };
+        Ok(Self {
+            program_id,
+            account_info,
+            key,
+            data,
+        })
+    }
+
+    /// Adds a record's primary key to this lookup, creating the lookup account
+    /// if it doesn't exist yet.
+    ///
+    /// Fails with `NautilusError::UniqueConstraintViolated` if the field is
+    /// `#[unique]` and another record already holds this value.
+    pub fn add_record(
+        &mut self,
+        primary_key: Vec<u8>,
+        payer: impl NautilusSigner<'a>,
+    ) -> ProgramResult {
+        if self.key.unique && !self.data.primary_keys.is_empty() {
+            return Err(NautilusError::UniqueConstraintViolated(
+                self.key.table_name.to_string(),
+                self.key.field_name.to_string(),
+            )
+            .into());
+        }
+        let is_new = self.account_info.data_is_empty();
+        self.data.primary_keys.push(primary_key);
+        if is_new {
+            let (_, bump) = self.key.pda(self.program_id);
+            let mut signer_seeds_vec = self.key.seeds();
+            signer_seeds_vec.push(vec![bump]);
+            let signer_seeds: Vec<&[u8]> = signer_seeds_vec.iter().map(AsRef::as_ref).collect();
+            cpi::system::create_pda(
+                self.clone(),
+                self.program_id,
+                payer,
+                Box::new(self.data.clone()),
+                signer_seeds,
+            )
+        } else {
+            let required_rent = self.required_rent()?;
+            if required_rent > self.lamports() {
+                cpi::system::transfer(
+                    payer,
+                    Mut::<Self>::new(self.clone())?,
+                    required_rent - self.lamports(),
+                )?;
+            }
+            self.account_info.realloc(self.span()?, false)?;
+            self.data
+                .serialize(&mut &mut self.account_info.data.borrow_mut()[..])?;
+            Ok(())
+        }
+    }
+
+    /// Removes a record's primary key from this lookup, refunding any rent no
+    /// longer needed to the receiver. The lookup account is closed once no
+    /// records hold its value.
+    pub fn remove_record(
+        &mut self,
+        primary_key: &Vec<u8>,
+        receiver: impl NautilusMut<'a>,
+    ) -> ProgramResult {
+        self.data.primary_keys.retain(|pk| pk != primary_key);
+        if self.data.primary_keys.is_empty() {
+            self.transfer_lamports(receiver, self.lamports())?;
+            self.account_info.data.borrow_mut().fill(0);
+            self.account_info.realloc(0, false)?;
+            self.account_info
+                .assign(&solana_program::system_program::ID);
+        } else {
+            self.account_info.realloc(self.span()?, false)?;
+            self.data
+                .serialize(&mut &mut self.account_info.data.borrow_mut()[..])?;
+            let required_rent = self.required_rent()?;
+            if self.lamports() > required_rent {
+                self.transfer_lamports(receiver, self.lamports() - required_rent)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> NautilusAccountInfo<'a> for NautilusLookup<'a> {
+    fn account_info(&self) -> Box<AccountInfo<'a>> {
+        self.account_info.clone()
+    }
+
+    fn key(&self) -> &'a Pubkey {
+        self.account_info.key
+    }
+
+    fn is_signer(&self) -> bool {
+        self.account_info.is_signer
+    }
+
+    fn is_writable(&self) -> bool {
+        self.account_info.is_writable
+    }
+
+    fn lamports(&self) -> u64 {
+        self.account_info.lamports()
+    }
+
+    fn mut_lamports(&self) -> Result<std::cell::RefMut<'_, &'a mut u64>, ProgramError> {
+        self.account_info.try_borrow_mut_lamports()
+    }
+
+    fn owner(&self) -> &'a Pubkey {
+        self.account_info.owner
+    }
+
+    fn span(&self) -> Result<usize, ProgramError> {
+        Ok(self.data.try_to_vec()?.len())
+    }
+}
+
+impl<'a> NautilusTransferLamports<'a> for NautilusLookup<'a> {
+    fn transfer_lamports(&self, to: impl NautilusMut<'a>, amount: u64) -> ProgramResult {
+        let from = self.account_info();
+        **from.try_borrow_mut_lamports()? -= amount;
+        **to.mut_lamports()? += amount;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::BorshSerialize;
+    use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+    use super::{NautilusLookup, NautilusLookupData, NautilusLookupKey};
+    use crate::{error::NautilusError, Signer, Wallet};
+
+    fn email_key(value: &str, unique: bool) -> NautilusLookupKey {
+        NautilusLookupKey {
+            table_name: "person",
+            field_name: "email",
+            value: value.as_bytes().to_vec(),
+            unique,
+        }
+    }
+
+    #[test]
+    fn lookup_address_depends_on_table_field_and_value() {
+        let program_id = Pubkey::new_unique();
+        let pda = email_key("joe@nautilus.sh", true).pda(&program_id).0;
+        let other_value = email_key("ann@nautilus.sh", true);
+        let other_field = NautilusLookupKey {
+            field_name: "name",
+            ..email_key("joe@nautilus.sh", true)
+        };
+        let other_table = NautilusLookupKey {
+            table_name: "pet",
+            ..email_key("joe@nautilus.sh", true)
+        };
+        for key in [other_value, other_field, other_table] {
+            assert_ne!(key.pda(&program_id).0, pda);
+        }
+    }
+
+    #[test]
+    fn lookup_address_supports_values_longer_than_a_seed() {
+        let program_id = Pubkey::new_unique();
+        let email = "a.very.long.email.address.for.a.person@nautilus.sh";
+        assert!(email.len() > 32);
+        let key = email_key(email, true);
+        assert!(key.seeds().iter().all(|seed| seed.len() <= 32));
+        let address = key.pda(&program_id).0;
+        assert_ne!(
+            address,
+            email_key(&email[..email.len() - 1], true)
+                .pda(&program_id)
+                .0
+        );
+
+        let system_program = solana_program::system_program::ID;
+        let mut lamports = 0;
+        let mut data = vec![];
+        let account_info = AccountInfo::new(
+            &address,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &system_program,
+            false,
+            0,
+        );
+        assert!(NautilusLookup::load(&program_id, Box::new(account_info), key).is_ok());
+    }
+
+    #[test]
+    fn load_starts_empty_lookup_without_data() {
+        let program_id = Pubkey::new_unique();
+        let key = email_key("joe@nautilus.sh", true);
+        let address = key.pda(&program_id).0;
+        let system_program = solana_program::system_program::ID;
+        let mut lamports = 0;
+        let mut data = vec![];
+        let account_info = AccountInfo::new(
+            &address,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &system_program,
+            false,
+            0,
+        );
+
+        let lookup = NautilusLookup::load(&program_id, Box::new(account_info), key).unwrap();
+        assert!(lookup.data.primary_keys.is_empty());
+    }
+
+    #[test]
+    fn load_rejects_wrong_address() {
+        let program_id = Pubkey::new_unique();
+        let address = email_key("ann@nautilus.sh", true).pda(&program_id).0;
+        let mut lamports = 0;
+        let mut data = vec![];
+        let account_info = AccountInfo::new(
+            &address,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+
+        assert_eq!(
+            NautilusLookup::load(
+                &program_id,
+                Box::new(account_info),
+                email_key("joe@nautilus.sh", true)
+            )
+            .err(),
+            Some(NautilusError::PdaMismatch(String::new(), String::new()).into())
+        );
+    }
+
+    #[test]
+    fn load_rejects_wrong_owner() {
+        let program_id = Pubkey::new_unique();
+        let key = email_key("joe@nautilus.sh", true);
+        let address = key.pda(&program_id).0;
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = NautilusLookupData {
+            primary_keys: vec![vec![1]],
+        }
+        .try_to_vec()
+        .unwrap();
+        let account_info = AccountInfo::new(
+            &address,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+
+        assert_eq!(
+            NautilusLookup::load(&program_id, Box::new(account_info), key).err(),
+            Some(NautilusError::InvalidOwner(String::new(), String::new()).into())
+        );
+    }
+
+    #[test]
+    fn add_record_rejects_taken_unique_value() {
+        let program_id = Pubkey::new_unique();
+        let key = email_key("joe@nautilus.sh", true);
+        let address = key.pda(&program_id).0;
+        let (payer_key, system_program) =
+            (Pubkey::new_unique(), solana_program::system_program::ID);
+        let (mut lamports, mut payer_lamports, mut system_program_lamports) = (0, 0, 0);
+        let mut data = NautilusLookupData {
+            primary_keys: vec![vec![1]],
+        }
+        .try_to_vec()
+        .unwrap();
+        let (mut payer_data, mut system_program_data) = (vec![], vec![]);
+        let account_info = AccountInfo::new(
+            &address,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        let payer_info = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            &mut payer_lamports,
+            &mut payer_data,
+            &system_program,
+            false,
+            0,
+        );
+        let system_program_info = AccountInfo::new(
+            &system_program,
+            false,
+            false,
+            &mut system_program_lamports,
+            &mut system_program_data,
+            &system_program,
+            true,
+            0,
+        );
+        let payer = Signer::new(Wallet::new(
+            Box::new(payer_info),
+            Box::new(system_program_info),
+        ))
+        .unwrap();
+
+        let mut lookup = NautilusLookup::load(&program_id, Box::new(account_info), key).unwrap();
+        assert_eq!(lookup.data.primary_keys, vec![vec![1]]);
+        assert_eq!(
+            lookup.add_record(vec![2], payer).err(),
+            Some(NautilusError::UniqueConstraintViolated(String::new(), String::new()).into())
+        );
+        assert_eq!(lookup.data.primary_keys, vec![vec![1]]);
+    }
+}
@@ -5,11 +5,17 @@ use solana_program::{
 };
 
 use crate::{
-    cpi, error::NautilusError, Create, Mut, NautilusAccountInfo, NautilusIndex, NautilusMut,
-    NautilusRecord, NautilusRecordData, NautilusSigner, NautilusTransferLamports, Signer, Wallet,
+    cpi,
+    error::NautilusError,
+    properties::data::{check_data_discriminator, check_owner, check_pda, deserialize_data},
+    Create, Mut, NautilusAccountInfo, NautilusDelete, NautilusLookup, NautilusMut, NautilusRecord,
+    NautilusRecordData, NautilusRecordIndex, NautilusSigner, NautilusTransferLamports,
+    NautilusUpdate, Signer, Wallet,
 };
 
+pub mod counter;
 pub mod index;
+pub mod lookup;
 
 /// The struct that allows you to treat a Program-Derived-Address (PDA) account
 /// as a table record.
@@ -19,11 +25,17 @@ pub mod index;
 /// it.
 ///
 /// The `account_info` field represents the PDA itself, while the `index` field
-/// is one single account that accompanies a Nautilus program and keeps an index
-/// of every table.
+/// keeps the table's record count: either the one `NautilusIndex` account that
+/// accompanies a Nautilus program and keeps an index of every table, or the
+/// table's own `NautilusCounter`.
 ///
-/// For more information on the `NautilusIndex<'_>` see the docs for that
-/// struct.
+/// For more information on the `NautilusIndex<'_>` and `NautilusCounter<'_>`
+/// see the docs for those structs.
+///
+/// The `lookups` field holds the lookup accounts for any fields declared with
+/// `#[index]` or `#[unique]`, in declaration order. They're only provided when
+/// the record is being created or is mutable, since that's when they need to be
+/// kept in sync.
 #[derive(Clone)]
 pub struct Record<'a, T>
 where
@@ -31,7 +43,8 @@ where
 {
     pub program_id: &'a Pubkey,
     pub account_info: Box<AccountInfo<'a>>,
-    pub index: NautilusIndex<'a>,
+    pub index: NautilusRecordIndex<'a>,
+    pub lookups: Vec<Box<AccountInfo<'a>>>,
     pub data: Box<T>,
 }
 
@@ -44,32 +57,47 @@ where
     pub fn new(
         program_id: &'a Pubkey,
         account_info: Box<AccountInfo<'a>>,
-        index: NautilusIndex<'a>,
+        index: NautilusRecordIndex<'a>,
     ) -> Self {
         Self {
             program_id,
             index,
             account_info,
+            lookups: vec![],
             data: Box::<T>::default(),
         }
     }
 
     /// Instantiate a new record and load the account inner data from on-chain.
+    ///
+    /// The account must be owned by the program, its data must begin with the
+    /// table's discriminator, and its address must be the one derived from the
+    /// record's primary key.
     pub fn load(
         program_id: &'a Pubkey,
         account_info: Box<AccountInfo<'a>>,
-        index: NautilusIndex<'a>,
+        index: NautilusRecordIndex<'a>,
     ) -> Result<Self, ProgramError> {
-        let data = match T::try_from_slice(match &account_info.try_borrow_data() {
-            Ok(acct_data) => acct_data,
-            Err(_) => {
-                return Err(NautilusError::LoadDataFailed(
-                    T::TABLE_NAME.to_string(),
-                    account_info.key.to_string(),
-                )
-                .into())
-            }
-        }) {
+        check_owner(&account_info, program_id, T::TABLE_NAME)?;
+        check_data_discriminator(
+            &account_info,
+            T::TABLE_NAME,
+            T::VERSIONED,
+            T::MIGRATES_UNVERSIONED,
+        )?;
+        let data = match deserialize_data::<T>(
+            match &account_info.try_borrow_data() {
+                Ok(acct_data) => acct_data,
+                Err(_) => {
+                    return Err(NautilusError::LoadDataFailed(
+                        T::TABLE_NAME.to_string(),
+                        account_info.key.to_string(),
+                    )
+                    .into())
+                }
+            },
+            T::SPACE,
+        ) {
             Ok(state_data) => Box::new(state_data),
             Err(_) => {
                 return Err(NautilusError::DeserializeDataFailed(
@@ -79,13 +107,60 @@ where
                 .into())
             }
         };
+        check_pda(&account_info, &data.pda(program_id).0, T::TABLE_NAME)?;
         Ok(Self {
             program_id,
             index,
             account_info,
+            lookups: vec![],
             data,
         })
     }
+
+    /// Provide the lookup accounts for the table's `#[index]` and `#[unique]`
+    /// fields.
+    pub fn with_lookups(mut self, lookups: Vec<Box<AccountInfo<'a>>>) -> Self {
+        self.lookups = lookups;
+        self
+    }
+
+    /// Loads the lookup account for each of the table's `#[index]` and
+    /// `#[unique]` fields, using this record's values.
+    pub fn load_lookups(&self) -> Result<Vec<NautilusLookup<'a>>, ProgramError> {
+        let keys = self.data.lookups();
+        if keys.len() != self.lookups.len() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        keys.into_iter()
+            .zip(self.lookups.iter())
+            .map(|(key, account_info)| {
+                NautilusLookup::load(self.program_id, account_info.clone(), key)
+            })
+            .collect()
+    }
+
+    /// Checks that this record exists and is the one referenced by another
+    /// table's `#[foreign_key(..)]` field, given that field's value.
+    pub fn check_reference(
+        &self,
+        table_name: &str,
+        field_name: &str,
+        primary_key: Vec<u8>,
+    ) -> ProgramResult {
+        let (pda, _) = self.pda();
+        if self.account_info.owner != self.program_id
+            || self.account_info.data_is_empty()
+            || self.account_info.key != &pda
+            || self.primary_key() != primary_key
+        {
+            return Err(NautilusError::ForeignKeyViolated(
+                table_name.to_string(),
+                field_name.to_string(),
+            )
+            .into());
+        }
+        Ok(())
+    }
 }
 
 impl<'a, T> NautilusAccountInfo<'a> for Record<'a, T>
@@ -121,7 +196,16 @@ where
     }
 
     fn span(&self) -> Result<usize, ProgramError> {
-        Ok(self.data.try_to_vec()?.len())
+        let len = self.data.try_to_vec()?.len();
+        match T::SPACE {
+            Some(space) if len > space => Err(NautilusError::MaxLengthExceeded(
+                T::TABLE_NAME.to_string(),
+                self.account_info.key.to_string(),
+            )
+            .into()),
+            Some(space) => Ok(space),
+            None => Ok(len),
+        }
     }
 }
 
@@ -170,6 +254,72 @@ where
     }
 }
 
+impl<'a, T> NautilusDelete<'a> for Mut<Record<'a, T>>
+where
+    T: NautilusRecordData,
+{
+    /// Deletes the record and refunds its rent to the receiver, removing it
+    /// from any lookups for the table's `#[index]` and `#[unique]` fields.
+    ///
+    /// The table's count in its `NautilusIndex` or `NautilusCounter` is
+    /// deliberately left as-is: for autoincrement tables it's the last primary
+    /// key handed out, so keeping it means a deleted record's primary key (and
+    /// therefore its address) is never reused by a new record. The number of
+    /// records currently in the table - see `get_record_count(..)` - goes down
+    /// instead.
+    fn delete_with_authorities(
+        &self,
+        authorities: Vec<AccountInfo>,
+        receiver: impl NautilusMut<'a>,
+    ) -> ProgramResult {
+        self.self_account.check_authorities(authorities)?;
+        let primary_key = self.self_account.primary_key();
+        for mut lookup in self.self_account.load_lookups()? {
+            lookup.remove_record(&primary_key, receiver.clone())?;
+        }
+        if T::AUTO_INCREMENT {
+            self.self_account
+                .index
+                .clone()
+                .remove_record(T::TABLE_NAME)?;
+        }
+        self.transfer_lamports(receiver, self.lamports())?;
+        let account_info = self.account_info();
+        account_info.data.borrow_mut().fill(0);
+        account_info.realloc(0, false)?;
+        account_info.assign(&solana_program::system_program::ID);
+        Ok(())
+    }
+}
+
+impl<'a, T> NautilusUpdate<'a> for Mut<Record<'a, T>>
+where
+    T: NautilusRecordData,
+{
+    fn update(&self) -> ProgramResult {
+        if self.required_rent()? > self.lamports() {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+        let account_info = self.account_info();
+        account_info.realloc(self.span()?, false)?;
+        self.self_account
+            .data
+            .serialize(&mut &mut account_info.data.borrow_mut()[..])?;
+        Ok(())
+    }
+
+    fn update_with_payer(&self, payer: impl NautilusSigner<'a>) -> ProgramResult {
+        let required_rent = self.required_rent()?;
+        let lamports = self.lamports();
+        if required_rent > lamports {
+            cpi::system::transfer(payer, self.clone(), required_rent - lamports)?;
+        } else if lamports > required_rent {
+            self.transfer_lamports(Mut::new(payer)?, lamports - required_rent)?;
+        }
+        self.update()
+    }
+}
+
 impl<'a, T> Create<'a, Record<'a, T>>
 where
     T: NautilusRecordData,
@@ -190,44 +340,51 @@ where
             system_program: self.system_program.to_owned(),
         })?;
         let (pda, bump) = self.pda();
-        assert_eq!(
-            &pda,
-            self.key(),
-            "Derived PDA does not match data for account {:#?}",
-            self.key()
-        );
+        check_pda(&self.self_account.account_info, &pda, T::TABLE_NAME)?;
         let mut signer_seeds_vec = self.seeds();
         signer_seeds_vec.push(vec![bump]);
         let signer_seeds: Vec<&[u8]> = signer_seeds_vec.iter().map(AsRef::as_ref).collect();
         cpi::system::create_pda(
             self.self_account.clone(),
             self.self_account.program_id,
-            payer,
+            payer.clone(),
             self.self_account.data.clone(),
             signer_seeds,
-        )
+        )?;
+        self.add_to_lookups(payer)
     }
 
     /// This function is the same as `create_record(&mut self, ..)` but allows
     /// you to specify a rent payer.
     pub fn create_record_with_payer(&mut self, payer: impl NautilusSigner<'a>) -> ProgramResult {
         let (pda, bump) = self.pda();
-        assert_eq!(
-            &pda,
-            self.key(),
-            "Derived PDA does not match data for account {:#?}",
-            self.key()
-        );
+        check_pda(&self.self_account.account_info, &pda, T::TABLE_NAME)?;
         let mut signer_seeds_vec = self.seeds();
         signer_seeds_vec.push(vec![bump]);
         let signer_seeds: Vec<&[u8]> = signer_seeds_vec.iter().map(AsRef::as_ref).collect();
         cpi::system::create_pda(
             self.self_account.clone(),
             self.self_account.program_id,
-            payer,
+            payer.clone(),
             self.self_account.data.clone(),
             signer_seeds,
-        )
+        )?;
+        self.add_to_lookups(payer)
+    }
+}
+
+impl<'a, T> Create<'a, Record<'a, T>>
+where
+    T: NautilusRecordData,
+{
+    /// Adds the new record to the lookups for the table's `#[index]` and
+    /// `#[unique]` fields.
+    fn add_to_lookups(&self, payer: impl NautilusSigner<'a>) -> ProgramResult {
+        let primary_key = self.primary_key();
+        for mut lookup in self.self_account.load_lookups()? {
+            lookup.add_record(primary_key.clone(), payer.clone())?;
+        }
+        Ok(())
     }
 }
 
@@ -259,3 +416,382 @@ where
         self.self_account.count_authorities()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+    use super::Record;
+    use crate::{
+        discriminator, error::NautilusError, versioned_discriminator, Create, NautilusIndex,
+        NautilusRecordData,
+    };
+
+    /// A record with a `#[max_len(8)] name: String`, serialized the way the
+    /// derive macro serializes it.
+    #[derive(Clone, Default)]
+    struct Person {
+        id: u8,
+        name: String,
+    }
+
+    impl BorshDeserialize for Person {
+        fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+            let _discrim: [u8; 8] = BorshDeserialize::deserialize(buf)?;
+            Ok(Self {
+                id: BorshDeserialize::deserialize(buf)?,
+                name: BorshDeserialize::deserialize(buf)?,
+            })
+        }
+    }
+
+    impl BorshSerialize for Person {
+        fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+            BorshSerialize::serialize(&self.discriminator(), writer)?;
+            BorshSerialize::serialize(&self.id, writer)?;
+            BorshSerialize::serialize(&self.name, writer)
+        }
+    }
+
+    impl NautilusRecordData for Person {
+        const TABLE_NAME: &'static str = "person";
+        const AUTO_INCREMENT: bool = false;
+        const SPACE: Option<usize> = Some(8 + 1 + 4 + 8);
+
+        fn primary_key(&self) -> Vec<u8> {
+            vec![self.id]
+        }
+
+        fn check_authorities(&self, _accounts: Vec<AccountInfo>) -> Result<(), ProgramError> {
+            Ok(())
+        }
+
+        fn count_authorities(&self) -> u8 {
+            0
+        }
+    }
+
+    /// A versioned record at version 1, which migrates its unversioned layout
+    /// (version 0) by defaulting its new `nickname`.
+    #[derive(Clone, Default)]
+    struct Pet {
+        id: u8,
+        nickname: String,
+    }
+
+    #[derive(BorshDeserialize)]
+    struct PetV0 {
+        id: u8,
+    }
+
+    impl From<PetV0> for Pet {
+        fn from(value: PetV0) -> Self {
+            Self {
+                id: value.id,
+                nickname: String::from("unnamed"),
+            }
+        }
+    }
+
+    impl BorshDeserialize for Pet {
+        fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+            let discrim: [u8; 8] = BorshDeserialize::deserialize(buf)?;
+            if discrim == versioned_discriminator(Self::TABLE_NAME) {
+                return match <u8 as BorshDeserialize>::deserialize(buf)? {
+                    1 => Ok(Self {
+                        id: BorshDeserialize::deserialize(buf)?,
+                        nickname: BorshDeserialize::deserialize(buf)?,
+                    }),
+                    version => Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("Unknown account data version: {}", version),
+                    )),
+                };
+            }
+            if discrim == discriminator(Self::TABLE_NAME) {
+                return <PetV0 as BorshDeserialize>::deserialize(buf).map(Self::from);
+            }
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Unknown account data discriminator",
+            ))
+        }
+    }
+
+    impl BorshSerialize for Pet {
+        fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+            BorshSerialize::serialize(&self.discriminator(), writer)?;
+            BorshSerialize::serialize(&1u8, writer)?;
+            BorshSerialize::serialize(&self.id, writer)?;
+            BorshSerialize::serialize(&self.nickname, writer)
+        }
+    }
+
+    impl NautilusRecordData for Pet {
+        const TABLE_NAME: &'static str = "pet";
+        const AUTO_INCREMENT: bool = false;
+        const VERSIONED: bool = true;
+        const MIGRATES_UNVERSIONED: bool = true;
+
+        fn primary_key(&self) -> Vec<u8> {
+            vec![self.id]
+        }
+
+        fn check_authorities(&self, _accounts: Vec<AccountInfo>) -> Result<(), ProgramError> {
+            Ok(())
+        }
+
+        fn count_authorities(&self) -> u8 {
+            0
+        }
+    }
+
+    /// Loads a record of type `T` from an account at `key` owned by `owner`.
+    fn load_record<T: NautilusRecordData>(
+        program_id: &Pubkey,
+        key: &Pubkey,
+        owner: &Pubkey,
+        data: &mut [u8],
+    ) -> Result<Box<T>, ProgramError> {
+        let index_key = Pubkey::new_unique();
+        let (mut lamports, mut index_lamports) = (0, 0);
+        let mut index_data = vec![];
+        let account_info =
+            AccountInfo::new(key, false, false, &mut lamports, data, owner, false, 0);
+        let index_info = AccountInfo::new(
+            &index_key,
+            false,
+            false,
+            &mut index_lamports,
+            &mut index_data,
+            program_id,
+            false,
+            0,
+        );
+        let index = NautilusIndex::new(program_id, Box::new(index_info));
+        Record::<T>::load(program_id, Box::new(account_info), index.into())
+            .map(|record| record.data)
+    }
+
+    fn load_person(program_id: &Pubkey, data: &mut [u8]) -> Result<Box<Person>, ProgramError> {
+        let key = Person {
+            id: 1,
+            ..Default::default()
+        }
+        .pda(program_id)
+        .0;
+        load_record(program_id, &key, program_id, data)
+    }
+
+    fn load_pet(program_id: &Pubkey, data: &mut [u8]) -> Result<Box<Pet>, ProgramError> {
+        let key = Pet {
+            id: 1,
+            ..Default::default()
+        }
+        .pda(program_id)
+        .0;
+        load_record(program_id, &key, program_id, data)
+    }
+
+    #[test]
+    fn load_reads_data_padded_to_space() {
+        let program_id = Pubkey::new_unique();
+        let mut data = Person {
+            id: 1,
+            name: String::from("joe"),
+        }
+        .try_to_vec()
+        .unwrap();
+        data.resize(Person::SPACE.unwrap(), 0);
+
+        let person = load_person(&program_id, &mut data).unwrap();
+        assert_eq!(person.id, 1);
+        assert_eq!(person.name, "joe");
+    }
+
+    #[test]
+    fn load_reads_current_version() {
+        let program_id = Pubkey::new_unique();
+        let mut data = Pet {
+            id: 1,
+            nickname: String::from("rex"),
+        }
+        .try_to_vec()
+        .unwrap();
+
+        let pet = load_pet(&program_id, &mut data).unwrap();
+        assert_eq!(pet.nickname, "rex");
+    }
+
+    #[test]
+    fn load_migrates_unversioned_data() {
+        let program_id = Pubkey::new_unique();
+        let mut data = discriminator("pet").to_vec();
+        data.push(1);
+
+        let pet = load_pet(&program_id, &mut data).unwrap();
+        assert_eq!(pet.id, 1);
+        assert_eq!(pet.nickname, "unnamed");
+    }
+
+    #[test]
+    fn load_rejects_unknown_version() {
+        let program_id = Pubkey::new_unique();
+        let mut data = versioned_discriminator("pet").to_vec();
+        data.extend_from_slice(&[2, 1]);
+
+        assert_eq!(
+            load_pet(&program_id, &mut data).err(),
+            Some(NautilusError::DeserializeDataFailed(String::new(), String::new()).into())
+        );
+    }
+
+    fn person_data() -> Vec<u8> {
+        Person {
+            id: 1,
+            name: String::from("joe"),
+        }
+        .try_to_vec()
+        .unwrap()
+    }
+
+    #[test]
+    fn load_rejects_wrong_owner() {
+        let program_id = Pubkey::new_unique();
+        let key = Person {
+            id: 1,
+            ..Default::default()
+        }
+        .pda(&program_id)
+        .0;
+
+        assert_eq!(
+            load_record::<Person>(&program_id, &key, &Pubkey::new_unique(), &mut person_data())
+                .err(),
+            Some(NautilusError::InvalidOwner(String::new(), String::new()).into())
+        );
+    }
+
+    #[test]
+    fn load_rejects_wrong_discriminator() {
+        let program_id = Pubkey::new_unique();
+        let mut data = person_data();
+        data[..8].copy_from_slice(&discriminator("pet"));
+
+        assert_eq!(
+            load_person(&program_id, &mut data).err(),
+            Some(NautilusError::DiscriminatorMismatch(String::new(), String::new()).into())
+        );
+    }
+
+    #[test]
+    fn load_rejects_wrong_address() {
+        let program_id = Pubkey::new_unique();
+        let key = Person {
+            id: 2,
+            ..Default::default()
+        }
+        .pda(&program_id)
+        .0;
+
+        assert_eq!(
+            load_record::<Person>(&program_id, &key, &program_id, &mut person_data()).err(),
+            Some(NautilusError::PdaMismatch(String::new(), String::new()).into())
+        );
+    }
+
+    #[test]
+    fn load_rejects_undeserializable_data() {
+        let program_id = Pubkey::new_unique();
+        let mut data = discriminator("person").to_vec();
+
+        assert_eq!(
+            load_person(&program_id, &mut data).err(),
+            Some(NautilusError::DeserializeDataFailed(String::new(), String::new()).into())
+        );
+    }
+
+    #[test]
+    fn create_record_rejects_wrong_address() {
+        let program_id = Pubkey::new_unique();
+        let system_program = solana_program::system_program::ID;
+        let (key, index_key, payer_key) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let (mut lamports, mut index_lamports, mut payer_lamports) = (0, 0, 1_000_000_000);
+        let (mut data, mut index_data, mut payer_data) = (vec![], vec![], vec![]);
+        let (mut system_lamports, mut system_data) = (1, vec![]);
+        let (mut rent_lamports, mut rent_data) = (1, vec![]);
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &system_program,
+            false,
+            0,
+        );
+        let index_info = AccountInfo::new(
+            &index_key,
+            false,
+            true,
+            &mut index_lamports,
+            &mut index_data,
+            &program_id,
+            false,
+            0,
+        );
+        let payer_info = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            &mut payer_lamports,
+            &mut payer_data,
+            &system_program,
+            false,
+            0,
+        );
+        let system_program_info = AccountInfo::new(
+            &system_program,
+            false,
+            false,
+            &mut system_lamports,
+            &mut system_data,
+            &system_program,
+            true,
+            0,
+        );
+        let rent_info = AccountInfo::new(
+            &solana_program::sysvar::rent::ID,
+            false,
+            false,
+            &mut rent_lamports,
+            &mut rent_data,
+            &solana_program::sysvar::ID,
+            false,
+            0,
+        );
+        let index = NautilusIndex::new(&program_id, Box::new(index_info));
+        let mut record = Record::<Person>::new(&program_id, Box::new(account_info), index.into());
+        record.data = Box::new(Person {
+            id: 1,
+            name: String::from("joe"),
+        });
+        let mut create = Create::new(
+            Box::new(payer_info),
+            Box::new(system_program_info),
+            Box::new(rent_info),
+            record,
+        )
+        .unwrap();
+
+        assert_eq!(
+            create.create_record().err(),
+            Some(NautilusError::PdaMismatch(String::new(), String::new()).into())
+        );
+    }
+}
@@ -0,0 +1,29 @@
+//! The `Clock` Nautilus object and all associated trait implementations.
+use solana_program::{clock::Clock as ClockState, program_error::ProgramError, sysvar::Sysvar};
+
+/// The Nautilus object representing the Clock sysvar.
+///
+/// The clock is read with `Sysvar::get()`, so no account is required for it.
+/// Its fields - such as `slot` and `unix_timestamp` - can be read directly
+/// from the object.
+#[derive(Clone)]
+pub struct Clock {
+    pub data: ClockState,
+}
+
+impl Clock {
+    /// Instantiate a new `Clock` and load the sysvar's data.
+    pub fn load() -> Result<Self, ProgramError> {
+        Ok(Self {
+            data: ClockState::get()?,
+        })
+    }
+}
+
+impl std::ops::Deref for Clock {
+    type Target = ClockState;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
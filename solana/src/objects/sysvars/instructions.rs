@@ -0,0 +1,542 @@
+//! The `Instructions` Nautilus object and all associated trait
+//! implementations.
+use solana_program::{
+    account_info::AccountInfo, ed25519_program, entrypoint::ProgramResult,
+    instruction::Instruction, program_error::ProgramError, pubkey::Pubkey, secp256k1_program,
+    sysvar,
+};
+
+use crate::{error::NautilusError, NautilusAccountInfo};
+
+/// The start of the signature offsets in an ed25519 program instruction, after
+/// the signature count and a padding byte.
+const ED25519_OFFSETS_START: usize = 2;
+/// The size of a single signature's offsets in an ed25519 program instruction.
+const ED25519_OFFSETS_SIZE: usize = 14;
+/// The instruction index the ed25519 program reads as the verifying
+/// instruction itself.
+const ED25519_CURRENT_INSTRUCTION: u16 = u16::MAX;
+/// The start of the signature offsets in a secp256k1 program instruction,
+/// after the signature count.
+const SECP256K1_OFFSETS_START: usize = 1;
+/// The size of a single signature's offsets in a secp256k1 program
+/// instruction.
+const SECP256K1_OFFSETS_SIZE: usize = 11;
+
+/// The Nautilus object representing the Instructions sysvar.
+///
+/// The underlying account - designated in field `account_info` - is the
+/// Instructions sysvar account, since this sysvar can't be read with
+/// `Sysvar::get()`.
+///
+/// The sysvar holds every instruction of the current transaction, so a program
+/// can inspect the instructions around the one it's executing - such as the
+/// ed25519 or secp256k1 program instructions verifying an off-chain signed
+/// message.
+#[derive(Clone)]
+pub struct Instructions<'a> {
+    pub account_info: Box<AccountInfo<'a>>,
+}
+
+impl<'a> Instructions<'a> {
+    /// Instantiate a new `Instructions`, checking that its account is the
+    /// Instructions sysvar.
+    pub fn load(account_info: Box<AccountInfo<'a>>) -> Result<Self, ProgramError> {
+        if !sysvar::instructions::check_id(account_info.key) {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(Self { account_info })
+    }
+
+    /// The index of the instruction currently executing in the transaction.
+    pub fn current_index(&self) -> Result<u16, ProgramError> {
+        sysvar::instructions::load_current_index_checked(&self.account_info)
+    }
+
+    /// The instruction at `index` in the transaction.
+    pub fn get(&self, index: usize) -> Result<Instruction, ProgramError> {
+        sysvar::instructions::load_instruction_at_checked(index, &self.account_info)
+    }
+
+    /// The instruction `offset` places away from the one currently executing,
+    /// ie. `-1` for the previous instruction.
+    pub fn get_relative(&self, offset: i64) -> Result<Instruction, ProgramError> {
+        sysvar::instructions::get_instruction_relative(offset, &self.account_info)
+    }
+
+    /// The number of instructions in the transaction.
+    pub fn len(&self) -> Result<usize, ProgramError> {
+        let data = self.account_info.try_borrow_data()?;
+        Ok(read_u16(&data, 0)? as usize)
+    }
+
+    /// Whether the transaction holds no instructions.
+    pub fn is_empty(&self) -> Result<bool, ProgramError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Every instruction in the transaction, including the one currently
+    /// executing.
+    pub fn all(&self) -> Result<Vec<Instruction>, ProgramError> {
+        (0..self.len()?).map(|index| self.get(index)).collect()
+    }
+
+    /// Every other instruction in the transaction, in order, leaving out the
+    /// one currently executing.
+    pub fn siblings(&self) -> Result<Vec<Instruction>, ProgramError> {
+        let current_index = self.current_index()? as usize;
+        (0..self.len()?)
+            .filter(|index| *index != current_index)
+            .map(|index| self.get(index))
+            .collect()
+    }
+
+    /// Checks that an ed25519 program instruction in the transaction verifies
+    /// `signature` of `message` by `pubkey`.
+    ///
+    /// The ed25519 program verifies its signatures before any instruction is
+    /// executed, so the transaction would have failed already if the
+    /// signature were invalid. It's only left to check that the signature
+    /// verified is the expected one.
+    pub fn verify_ed25519(
+        &self,
+        pubkey: &Pubkey,
+        message: &[u8],
+        signature: &[u8; 64],
+    ) -> ProgramResult {
+        for (index, ix) in self.all()?.iter().enumerate() {
+            if !ed25519_program::check_id(&ix.program_id) {
+                continue;
+            }
+            let count = *ix
+                .data
+                .first()
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            for i in 0..count as usize {
+                let start = ED25519_OFFSETS_START + i * ED25519_OFFSETS_SIZE;
+                let offsets = ix
+                    .data
+                    .get(start..start + ED25519_OFFSETS_SIZE)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                // Offsets are read as `(offset, instruction index)` pairs, and
+                // the message as `(offset, size, instruction index)`.
+                let source = |instruction_index: u16| match instruction_index {
+                    ED25519_CURRENT_INSTRUCTION => index,
+                    instruction_index => instruction_index as usize,
+                };
+                let signed = self.referenced_data(
+                    (index, &ix.data),
+                    source(read_u16(offsets, 2)?),
+                    read_u16(offsets, 0)?,
+                    64,
+                )? == signature
+                    && self.referenced_data(
+                        (index, &ix.data),
+                        source(read_u16(offsets, 6)?),
+                        read_u16(offsets, 4)?,
+                        32,
+                    )? == pubkey.as_ref()
+                    && self.referenced_data(
+                        (index, &ix.data),
+                        source(read_u16(offsets, 12)?),
+                        read_u16(offsets, 8)?,
+                        read_u16(offsets, 10)? as usize,
+                    )? == message;
+                if signed {
+                    return Ok(());
+                }
+            }
+        }
+        Err(NautilusError::SignatureNotVerified(String::from("ed25519"), pubkey.to_string()).into())
+    }
+
+    /// Checks that a secp256k1 program instruction in the transaction verifies
+    /// `signature` of `message` by the Ethereum address `eth_address`.
+    ///
+    /// Just like `verify_ed25519(..)`, the signature itself was verified before
+    /// any instruction was executed.
+    pub fn verify_secp256k1(
+        &self,
+        eth_address: &[u8; 20],
+        message: &[u8],
+        signature: &[u8; 64],
+        recovery_id: u8,
+    ) -> ProgramResult {
+        for (index, ix) in self.all()?.iter().enumerate() {
+            if !secp256k1_program::check_id(&ix.program_id) {
+                continue;
+            }
+            let count = *ix
+                .data
+                .first()
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            for i in 0..count as usize {
+                let start = SECP256K1_OFFSETS_START + i * SECP256K1_OFFSETS_SIZE;
+                let offsets = ix
+                    .data
+                    .get(start..start + SECP256K1_OFFSETS_SIZE)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                // The signature is followed by its recovery ID.
+                let signed = self.referenced_data(
+                    (index, &ix.data),
+                    offsets[2] as usize,
+                    read_u16(offsets, 0)?,
+                    65,
+                )? == [signature.as_ref(), &[recovery_id]].concat()
+                    && self.referenced_data(
+                        (index, &ix.data),
+                        offsets[5] as usize,
+                        read_u16(offsets, 3)?,
+                        20,
+                    )? == eth_address
+                    && self.referenced_data(
+                        (index, &ix.data),
+                        offsets[10] as usize,
+                        read_u16(offsets, 6)?,
+                        read_u16(offsets, 8)? as usize,
+                    )? == message;
+                if signed {
+                    return Ok(());
+                }
+            }
+        }
+        Err(NautilusError::SignatureNotVerified(
+            String::from("secp256k1"),
+            eth_address.iter().map(|b| format!("{:02x}", b)).collect(),
+        )
+        .into())
+    }
+
+    /// Reads `size` bytes at `offset` of the data of the instruction at
+    /// `instruction_index`, which is usually the verifying instruction itself.
+    fn referenced_data(
+        &self,
+        (verifying_index, verifying_data): (usize, &[u8]),
+        instruction_index: usize,
+        offset: u16,
+        size: usize,
+    ) -> Result<Vec<u8>, ProgramError> {
+        let offset = offset as usize;
+        let read = |data: &[u8]| {
+            data.get(offset..offset + size)
+                .map(|bytes| bytes.to_vec())
+                .ok_or(ProgramError::InvalidInstructionData)
+        };
+        match instruction_index == verifying_index {
+            true => read(verifying_data),
+            false => read(&self.get(instruction_index)?.data),
+        }
+    }
+}
+
+/// Reads a little-endian `u16` at `offset`.
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, ProgramError> {
+    let bytes = data
+        .get(offset..offset + 2)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    Ok(u16::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+impl<'a> NautilusAccountInfo<'a> for Instructions<'a> {
+    fn account_info(&self) -> Box<AccountInfo<'a>> {
+        self.account_info.clone()
+    }
+
+    fn key(&self) -> &'a Pubkey {
+        self.account_info.key
+    }
+
+    fn is_signer(&self) -> bool {
+        self.account_info.is_signer
+    }
+
+    fn is_writable(&self) -> bool {
+        self.account_info.is_writable
+    }
+
+    fn lamports(&self) -> u64 {
+        self.account_info.lamports()
+    }
+
+    fn mut_lamports(&self) -> Result<std::cell::RefMut<'_, &'a mut u64>, ProgramError> {
+        self.account_info.try_borrow_mut_lamports()
+    }
+
+    fn owner(&self) -> &'a Pubkey {
+        self.account_info.owner
+    }
+
+    fn span(&self) -> Result<usize, ProgramError> {
+        Ok(self.account_info.data_len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{
+        account_info::AccountInfo,
+        ed25519_program,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        secp256k1_program,
+        sysvar::{
+            self,
+            instructions::{construct_instructions_data, store_current_index, BorrowedInstruction},
+        },
+    };
+
+    use super::Instructions;
+    use crate::error::NautilusError;
+
+    const MESSAGE: &[u8] = b"nautilus";
+    const SIGNATURE: [u8; 64] = [7; 64];
+    const RECOVERY_ID: u8 = 1;
+    const ETH_ADDRESS: [u8; 20] = [9; 20];
+
+    /// Builds the Instructions sysvar's data for a transaction of the provided
+    /// instructions, with the instruction at `current_index` executing.
+    fn sysvar_data(instructions: &[(Pubkey, Vec<u8>)], current_index: u16) -> Vec<u8> {
+        let borrowed: Vec<BorrowedInstruction> = instructions
+            .iter()
+            .map(|(program_id, data)| BorrowedInstruction {
+                program_id,
+                accounts: vec![],
+                data,
+            })
+            .collect();
+        let mut data = construct_instructions_data(&borrowed);
+        store_current_index(&mut data, current_index);
+        data
+    }
+
+    /// Runs `f` against the Instructions sysvar holding the provided data.
+    fn with_instructions<R>(mut data: Vec<u8>, f: impl FnOnce(&Instructions) -> R) -> R {
+        let key = sysvar::instructions::ID;
+        let owner = sysvar::ID;
+        let mut lamports = 0;
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        f(&Instructions::load(Box::new(account_info)).unwrap())
+    }
+
+    /// An ed25519 program instruction verifying one signature, whose offsets
+    /// point at `(signature, pubkey, message)` in the instructions at the
+    /// provided indexes - `u16::MAX` being the ed25519 instruction itself.
+    ///
+    /// The signature, pubkey and message are laid out in that order right
+    /// after the offsets, in whichever instruction they're read from.
+    fn ed25519_data(pubkey: &Pubkey, instruction_indexes: [u16; 3]) -> Vec<u8> {
+        let signature_offset: u16 = 2 + 14;
+        let pubkey_offset = signature_offset + 64;
+        let message_offset = pubkey_offset + 32;
+        let mut data = vec![1, 0];
+        for value in [
+            signature_offset,
+            instruction_indexes[0],
+            pubkey_offset,
+            instruction_indexes[1],
+            message_offset,
+            MESSAGE.len() as u16,
+            instruction_indexes[2],
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&SIGNATURE);
+        data.extend_from_slice(pubkey.as_ref());
+        data.extend_from_slice(MESSAGE);
+        data
+    }
+
+    /// A secp256k1 program instruction verifying one signature, referencing
+    /// its own data as the instruction at `own_index`.
+    fn secp256k1_data(own_index: u8) -> Vec<u8> {
+        let signature_offset: u16 = 1 + 11;
+        let eth_address_offset = signature_offset + 65;
+        let message_offset = eth_address_offset + 20;
+        let mut data = vec![1];
+        data.extend_from_slice(&signature_offset.to_le_bytes());
+        data.push(own_index);
+        data.extend_from_slice(&eth_address_offset.to_le_bytes());
+        data.push(own_index);
+        data.extend_from_slice(&message_offset.to_le_bytes());
+        data.extend_from_slice(&(MESSAGE.len() as u16).to_le_bytes());
+        data.push(own_index);
+        data.extend_from_slice(&SIGNATURE);
+        data.push(RECOVERY_ID);
+        data.extend_from_slice(&ETH_ADDRESS);
+        data.extend_from_slice(MESSAGE);
+        data
+    }
+
+    fn not_verified() -> ProgramError {
+        NautilusError::SignatureNotVerified(String::new(), String::new()).into()
+    }
+
+    #[test]
+    fn load_rejects_other_accounts() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = sysvar_data(&[], 0);
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &sysvar::ID,
+            false,
+            0,
+        );
+        assert_eq!(
+            Instructions::load(Box::new(account_info)).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn lists_siblings() {
+        let program_ids = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let instructions: Vec<(Pubkey, Vec<u8>)> =
+            program_ids.iter().map(|id| (*id, vec![])).collect();
+        with_instructions(sysvar_data(&instructions, 1), |ixs| {
+            assert_eq!(ixs.len().unwrap(), 3);
+            assert_eq!(ixs.current_index().unwrap(), 1);
+            let siblings: Vec<Pubkey> = ixs
+                .siblings()
+                .unwrap()
+                .iter()
+                .map(|ix| ix.program_id)
+                .collect();
+            assert_eq!(siblings, vec![program_ids[0], program_ids[2]]);
+        });
+    }
+
+    #[test]
+    fn verifies_ed25519_signature_in_own_data() {
+        let pubkey = Pubkey::new_unique();
+        let instructions = vec![
+            (ed25519_program::ID, ed25519_data(&pubkey, [u16::MAX; 3])),
+            (Pubkey::new_unique(), vec![]),
+        ];
+        with_instructions(sysvar_data(&instructions, 1), |ixs| {
+            assert_eq!(ixs.verify_ed25519(&pubkey, MESSAGE, &SIGNATURE), Ok(()));
+            assert_eq!(
+                ixs.verify_ed25519(&pubkey, b"other", &SIGNATURE),
+                Err(not_verified())
+            );
+            assert_eq!(
+                ixs.verify_ed25519(&Pubkey::new_unique(), MESSAGE, &SIGNATURE),
+                Err(not_verified())
+            );
+            assert_eq!(
+                ixs.verify_ed25519(&pubkey, MESSAGE, &[0; 64]),
+                Err(not_verified())
+            );
+        });
+    }
+
+    #[test]
+    fn verifies_ed25519_signature_in_another_instruction() {
+        let pubkey = Pubkey::new_unique();
+        // The offsets point at the same layout in the program's instruction,
+        // at index `1`.
+        let referenced = ed25519_data(&pubkey, [1; 3]);
+        let mut verifying = referenced[..16].to_vec();
+        verifying.extend_from_slice(&[0; 64 + 32]);
+        let instructions = vec![
+            (ed25519_program::ID, verifying),
+            (Pubkey::new_unique(), referenced),
+        ];
+        with_instructions(sysvar_data(&instructions, 1), |ixs| {
+            assert_eq!(ixs.verify_ed25519(&pubkey, MESSAGE, &SIGNATURE), Ok(()));
+        });
+    }
+
+    #[test]
+    fn rejects_truncated_ed25519_data() {
+        let pubkey = Pubkey::new_unique();
+        let mut truncated_offsets = ed25519_data(&pubkey, [u16::MAX; 3]);
+        truncated_offsets.truncate(10);
+        let mut truncated_message = ed25519_data(&pubkey, [u16::MAX; 3]);
+        truncated_message.truncate(truncated_message.len() - 1);
+        for data in [truncated_offsets, truncated_message] {
+            let instructions = vec![(ed25519_program::ID, data), (Pubkey::new_unique(), vec![])];
+            with_instructions(sysvar_data(&instructions, 1), |ixs| {
+                assert_eq!(
+                    ixs.verify_ed25519(&pubkey, MESSAGE, &SIGNATURE),
+                    Err(ProgramError::InvalidInstructionData)
+                );
+            });
+        }
+    }
+
+    #[test]
+    fn ignores_signatures_of_other_programs() {
+        let pubkey = Pubkey::new_unique();
+        let instructions = vec![
+            (Pubkey::new_unique(), ed25519_data(&pubkey, [u16::MAX; 3])),
+            (Pubkey::new_unique(), vec![]),
+        ];
+        with_instructions(sysvar_data(&instructions, 1), |ixs| {
+            assert_eq!(
+                ixs.verify_ed25519(&pubkey, MESSAGE, &SIGNATURE),
+                Err(not_verified())
+            );
+        });
+    }
+
+    #[test]
+    fn verifies_secp256k1_signature() {
+        let instructions = vec![
+            (Pubkey::new_unique(), vec![]),
+            (secp256k1_program::ID, secp256k1_data(1)),
+            (Pubkey::new_unique(), vec![]),
+        ];
+        with_instructions(sysvar_data(&instructions, 2), |ixs| {
+            assert_eq!(
+                ixs.verify_secp256k1(&ETH_ADDRESS, MESSAGE, &SIGNATURE, RECOVERY_ID),
+                Ok(())
+            );
+            assert_eq!(
+                ixs.verify_secp256k1(&ETH_ADDRESS, MESSAGE, &SIGNATURE, 0),
+                Err(not_verified())
+            );
+            assert_eq!(
+                ixs.verify_secp256k1(&[0; 20], MESSAGE, &SIGNATURE, RECOVERY_ID),
+                Err(not_verified())
+            );
+            assert_eq!(
+                ixs.verify_secp256k1(&ETH_ADDRESS, b"other", &SIGNATURE, RECOVERY_ID),
+                Err(not_verified())
+            );
+        });
+    }
+
+    #[test]
+    fn rejects_truncated_secp256k1_data() {
+        let mut data = secp256k1_data(0);
+        data.truncate(8);
+        let instructions = vec![
+            (secp256k1_program::ID, data),
+            (Pubkey::new_unique(), vec![]),
+        ];
+        with_instructions(sysvar_data(&instructions, 1), |ixs| {
+            assert_eq!(
+                ixs.verify_secp256k1(&ETH_ADDRESS, MESSAGE, &SIGNATURE, RECOVERY_ID),
+                Err(ProgramError::InvalidInstructionData)
+            );
+        });
+    }
+}
@@ -0,0 +1,9 @@
+//! Submodule containing all sysvar Nautilus objects and their associated trait
+//! implementations.
+//!
+//! Sysvars are read-only, so these objects can't be wrapped in `Create<..>`,
+//! `Signer<..>` or `Mut<..>`.
+pub mod clock;
+pub mod instructions;
+pub mod rent;
+pub mod slot_hashes;
@@ -0,0 +1,29 @@
+//! The `Rent` Nautilus object and all associated trait implementations.
+use solana_program::{program_error::ProgramError, rent::Rent as RentState, sysvar::Sysvar};
+
+/// The Nautilus object representing the Rent sysvar.
+///
+/// The rent is read with `Sysvar::get()`, so no account is required for it.
+/// Its functions - such as `minimum_balance(..)` - can be called directly on
+/// the object.
+#[derive(Clone)]
+pub struct Rent {
+    pub data: RentState,
+}
+
+impl Rent {
+    /// Instantiate a new `Rent` and load the sysvar's data.
+    pub fn load() -> Result<Self, ProgramError> {
+        Ok(Self {
+            data: RentState::get()?,
+        })
+    }
+}
+
+impl std::ops::Deref for Rent {
+    type Target = RentState;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
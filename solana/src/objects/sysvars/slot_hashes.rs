@@ -0,0 +1,245 @@
+//! The `SlotHashes` Nautilus object and all associated trait implementations.
+use solana_program::{
+    account_info::AccountInfo, clock::Slot, hash::Hash, program_error::ProgramError,
+    pubkey::Pubkey, sysvar,
+};
+
+use crate::NautilusAccountInfo;
+
+/// The size of the entry count at the start of the sysvar's data.
+const LEN_SIZE: usize = 8;
+/// The size of a single `(Slot, Hash)` entry.
+const ENTRY_SIZE: usize = 8 + 32;
+
+/// The Nautilus object representing the SlotHashes sysvar.
+///
+/// The underlying account - designated in field `account_info` - is the
+/// SlotHashes sysvar account, since this sysvar can't be read with
+/// `Sysvar::get()`.
+///
+/// The sysvar holds the hashes of the most recent slots, ordered from the most
+/// recent. Since it's too large to deserialize within the compute budget, its
+/// entries are read straight from the account's data.
+#[derive(Clone)]
+pub struct SlotHashes<'a> {
+    pub account_info: Box<AccountInfo<'a>>,
+}
+
+impl<'a> SlotHashes<'a> {
+    /// Instantiate a new `SlotHashes`, checking that its account is the
+    /// SlotHashes sysvar.
+    pub fn load(account_info: Box<AccountInfo<'a>>) -> Result<Self, ProgramError> {
+        if !sysvar::slot_hashes::check_id(account_info.key) {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(Self { account_info })
+    }
+
+    /// The number of slot hashes held by the sysvar.
+    pub fn len(&self) -> Result<usize, ProgramError> {
+        let data = self.account_info.try_borrow_data()?;
+        read_len(&data)
+    }
+
+    /// Whether the sysvar holds no slot hashes.
+    pub fn is_empty(&self) -> Result<bool, ProgramError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// The most recent slot and its hash.
+    pub fn most_recent(&self) -> Result<Option<(Slot, Hash)>, ProgramError> {
+        let data = self.account_info.try_borrow_data()?;
+        match read_len(&data)? {
+            0 => Ok(None),
+            _ => Ok(Some(read_entry(&data, 0)?)),
+        }
+    }
+
+    /// The hash of a slot, if it's recent enough to still be held by the
+    /// sysvar.
+    pub fn get(&self, slot: Slot) -> Result<Option<Hash>, ProgramError> {
+        let data = self.account_info.try_borrow_data()?;
+        // Entries are sorted by descending slot.
+        let (mut low, mut high) = (0, read_len(&data)?);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (entry_slot, hash) = read_entry(&data, mid)?;
+            match entry_slot.cmp(&slot) {
+                std::cmp::Ordering::Equal => return Ok(Some(hash)),
+                std::cmp::Ordering::Greater => low = mid + 1,
+                std::cmp::Ordering::Less => high = mid,
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Reads the number of entries at the start of the sysvar's data.
+fn read_len(data: &[u8]) -> Result<usize, ProgramError> {
+    let len = data
+        .get(..LEN_SIZE)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    Ok(u64::from_le_bytes(len.try_into().unwrap()) as usize)
+}
+
+/// Reads the entry at `index` from the sysvar's data.
+fn read_entry(data: &[u8], index: usize) -> Result<(Slot, Hash), ProgramError> {
+    let offset = LEN_SIZE + index * ENTRY_SIZE;
+    let entry = data
+        .get(offset..offset + ENTRY_SIZE)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    Ok((
+        u64::from_le_bytes(entry[..8].try_into().unwrap()),
+        Hash::new(&entry[8..]),
+    ))
+}
+
+impl<'a> NautilusAccountInfo<'a> for SlotHashes<'a> {
+    fn account_info(&self) -> Box<AccountInfo<'a>> {
+        self.account_info.clone()
+    }
+
+    fn key(&self) -> &'a Pubkey {
+        self.account_info.key
+    }
+
+    fn is_signer(&self) -> bool {
+        self.account_info.is_signer
+    }
+
+    fn is_writable(&self) -> bool {
+        self.account_info.is_writable
+    }
+
+    fn lamports(&self) -> u64 {
+        self.account_info.lamports()
+    }
+
+    fn mut_lamports(&self) -> Result<std::cell::RefMut<'_, &'a mut u64>, ProgramError> {
+        self.account_info.try_borrow_mut_lamports()
+    }
+
+    fn owner(&self) -> &'a Pubkey {
+        self.account_info.owner
+    }
+
+    fn span(&self) -> Result<usize, ProgramError> {
+        Ok(self.account_info.data_len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{
+        account_info::AccountInfo, clock::Slot, hash::Hash, program_error::ProgramError,
+        pubkey::Pubkey, sysvar,
+    };
+
+    use super::SlotHashes;
+
+    /// Builds the sysvar's data from entries ordered from the most recent.
+    fn slot_hashes_data(entries: &[(Slot, Hash)]) -> Vec<u8> {
+        let mut data = (entries.len() as u64).to_le_bytes().to_vec();
+        for (slot, hash) in entries {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(hash.as_ref());
+        }
+        data
+    }
+
+    fn entries() -> Vec<(Slot, Hash)> {
+        [90, 70, 50, 30, 10]
+            .into_iter()
+            .map(|slot| (slot, Hash::new_from_array([slot as u8; 32])))
+            .collect()
+    }
+
+    fn with_slot_hashes<R>(data: &mut [u8], f: impl FnOnce(&SlotHashes) -> R) -> R {
+        let key = sysvar::slot_hashes::ID;
+        let owner = sysvar::ID;
+        let mut lamports = 1;
+        let account_info =
+            AccountInfo::new(&key, false, false, &mut lamports, data, &owner, false, 0);
+        f(&SlotHashes::load(Box::new(account_info)).unwrap())
+    }
+
+    #[test]
+    fn get_finds_nothing_in_empty_list() {
+        with_slot_hashes(&mut slot_hashes_data(&[]), |slot_hashes| {
+            assert!(slot_hashes.is_empty().unwrap());
+            assert_eq!(slot_hashes.most_recent().unwrap(), None);
+            assert_eq!(slot_hashes.get(10).unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn get_finds_held_slot() {
+        with_slot_hashes(&mut slot_hashes_data(&entries()), |slot_hashes| {
+            assert_eq!(slot_hashes.len().unwrap(), 5);
+            assert_eq!(
+                slot_hashes.get(70).unwrap(),
+                Some(Hash::new_from_array([70; 32]))
+            );
+        });
+    }
+
+    #[test]
+    fn get_finds_first_and_last_entries() {
+        with_slot_hashes(&mut slot_hashes_data(&entries()), |slot_hashes| {
+            assert_eq!(
+                slot_hashes.most_recent().unwrap(),
+                Some((90, Hash::new_from_array([90; 32])))
+            );
+            assert_eq!(
+                slot_hashes.get(90).unwrap(),
+                Some(Hash::new_from_array([90; 32]))
+            );
+            assert_eq!(
+                slot_hashes.get(10).unwrap(),
+                Some(Hash::new_from_array([10; 32]))
+            );
+        });
+    }
+
+    #[test]
+    fn get_misses_slot_not_held() {
+        with_slot_hashes(&mut slot_hashes_data(&entries()), |slot_hashes| {
+            for slot in [0, 20, 60, 100] {
+                assert_eq!(slot_hashes.get(slot).unwrap(), None);
+            }
+        });
+    }
+
+    #[test]
+    fn get_rejects_truncated_data() {
+        let mut data = slot_hashes_data(&entries());
+        data.truncate(data.len() - 1);
+        with_slot_hashes(&mut data, |slot_hashes| {
+            assert_eq!(
+                slot_hashes.get(10).err(),
+                Some(ProgramError::InvalidAccountData)
+            );
+        });
+    }
+
+    #[test]
+    fn load_rejects_other_account() {
+        let (key, owner) = (Pubkey::new_unique(), sysvar::ID);
+        let mut lamports = 1;
+        let mut data = slot_hashes_data(&[]);
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        assert_eq!(
+            SlotHashes::load(Box::new(account_info)).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+    }
+}
@@ -6,17 +6,19 @@ use solana_program::{
 };
 pub use spl_token::state::Account as AssociatedTokenAccountState;
 
+use super::check_token_program_owner;
 use crate::{
-    cpi, error::NautilusError, Create, Mint, Mut, NautilusAccountInfo, NautilusMut, NautilusSigner,
-    Signer, Wallet,
+    cpi, error::NautilusError, properties::data::check_pda, Create, Mint, Mut, NautilusAccountInfo,
+    NautilusMut, NautilusSigner, Signer, Wallet,
 };
 
 /// The Nautilus object representing an associated token account.
 ///
 /// The underlying account - designated in field `account_info` - is the
-/// associated token account.
+/// associated token account. It can be owned by either the Token Program or the
+/// Token-2022 Program, and its owner is the program used for CPI operations.
 ///
-/// We also include the read-only Token Program and Associated Token Program for
+/// We also include the read-only token program and Associated Token Program for
 /// any CPI operations necessary, since we do not own this account.
 #[derive(Clone)]
 pub struct AssociatedTokenAccount<'a> {
@@ -44,14 +46,22 @@ impl<'a> AssociatedTokenAccount<'a> {
 
     /// Instantiate a new `AssociatedTokenAccount` and load the account inner
     /// data from on-chain.
+    ///
+    /// The account must be owned by the token program provided, and its address
+    /// must be the associated token address of its owner and mint for that
+    /// program. Only the base account state is read, so a Token-2022 account's
+    /// extensions are ignored.
     pub fn load(
         account_info: Box<AccountInfo<'a>>,
         token_program: Box<AccountInfo<'a>>,
         associated_token_program: Box<AccountInfo<'a>>,
     ) -> Result<Self, ProgramError> {
+        check_token_program_owner(&account_info, &token_program, "associated_token_account")?;
         let data =
             match AssociatedTokenAccountState::unpack(match &account_info.try_borrow_data() {
-                Ok(acct_data) => acct_data,
+                Ok(acct_data) => acct_data
+                    .get(..AssociatedTokenAccountState::LEN)
+                    .unwrap_or_default(),
                 Err(_) => {
                     return Err(NautilusError::LoadDataFailed(
                         String::from("associated_token_account"),
@@ -69,6 +79,15 @@ impl<'a> AssociatedTokenAccount<'a> {
                     .into())
                 }
             };
+        check_pda(
+            &account_info,
+            &spl_associated_token_account::get_associated_token_address_with_program_id(
+                &data.owner,
+                &data.mint,
+                account_info.owner,
+            ),
+            "associated_token_account",
+        )?;
         Ok(Self {
             account_info,
             token_program,
@@ -108,7 +127,10 @@ impl<'a> NautilusAccountInfo<'a> for AssociatedTokenAccount<'a> {
     }
 
     fn span(&self) -> Result<usize, ProgramError> {
-        Ok(AssociatedTokenAccountState::LEN)
+        match self.account_info.data_is_empty() {
+            true => Ok(AssociatedTokenAccountState::LEN),
+            false => Ok(self.account_info.data_len()),
+        }
     }
 }
 
@@ -121,14 +143,7 @@ impl<'a> Mut<AssociatedTokenAccount<'a>> {
         amount: u64,
     ) -> ProgramResult {
         let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
-        cpi::token::burn(
-            self.self_account.token_program.key,
-            self.clone(),
-            mint,
-            authority,
-            multisigs,
-            amount,
-        )
+        cpi::token::burn(self.clone(), mint, authority, multisigs, amount)
     }
 
     /// Freeze token movement from this associated token account.
@@ -138,13 +153,7 @@ impl<'a> Mut<AssociatedTokenAccount<'a>> {
         freeze_authority: impl NautilusSigner<'a>,
     ) -> ProgramResult {
         let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
-        cpi::token::freeze_account(
-            self.self_account.token_program.key,
-            self.clone(),
-            mint,
-            freeze_authority,
-            multisigs,
-        )
+        cpi::token::freeze_account(self.clone(), mint, freeze_authority, multisigs)
     }
 
     /// Thaw this associated token account. It should be already frozen.
@@ -154,13 +163,7 @@ impl<'a> Mut<AssociatedTokenAccount<'a>> {
         freeze_authority: impl NautilusSigner<'a>,
     ) -> ProgramResult {
         let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
-        cpi::token::thaw_account(
-            self.self_account.token_program.key,
-            self.clone(),
-            mint,
-            freeze_authority,
-            multisigs,
-        )
+        cpi::token::thaw_account(self.clone(), mint, freeze_authority, multisigs)
     }
 
     /// Transfer tokens from this associated token account to another.
@@ -171,14 +174,7 @@ impl<'a> Mut<AssociatedTokenAccount<'a>> {
         amount: u64,
     ) -> ProgramResult {
         let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
-        cpi::token::transfer(
-            self.self_account.token_program.key,
-            self.clone(),
-            to,
-            authority,
-            multisigs,
-            amount,
-        )
+        cpi::token::transfer(self.clone(), to, authority, multisigs, amount)
     }
 }
 
@@ -0,0 +1,308 @@
+//! The `AssociatedTokenAccount2022<T>` Nautilus object and all associated trait
+//! implementations.
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_token_2022::extension::{
+    cpi_guard::CpiGuard, immutable_owner::ImmutableOwner, memo_transfer::MemoTransfer,
+    non_transferable::NonTransferableAccount, transfer_fee::TransferFeeAmount,
+    BaseStateWithExtensions, Extension, ExtensionType, StateWithExtensions,
+};
+pub use spl_token_2022::state::Account as AssociatedTokenAccount2022State;
+
+use super::check_token_program_owner;
+use crate::{
+    cpi, error::NautilusError, properties::data::check_pda, Create, Mint2022, Mut,
+    NautilusAccountInfo, NautilusMut, NautilusSigner, Signer, Wallet,
+};
+
+/// The Nautilus object representing an associated token account owned by
+/// either the Token Program or the Token-2022 Program.
+///
+/// The underlying account - designated in field `account_info` - is the
+/// associated token account. Its owner determines which token program is used
+/// for CPI operations, and any Token-2022 extensions can be read from its TLV
+/// data.
+///
+/// We also include the read-only token program and Associated Token Program for
+/// any CPI operations necessary, since we do not own this account.
+#[derive(Clone)]
+pub struct AssociatedTokenAccount2022<'a> {
+    pub account_info: Box<AccountInfo<'a>>,
+    pub token_program: Box<AccountInfo<'a>>,
+    pub associated_token_program: Box<AccountInfo<'a>>,
+    pub data: AssociatedTokenAccount2022State,
+    pub extension_types: Vec<ExtensionType>,
+}
+
+impl<'a> AssociatedTokenAccount2022<'a> {
+    /// Instantiate a new `AssociatedTokenAccount2022` without loading the
+    /// account inner data from on-chain.
+    pub fn new(
+        account_info: Box<AccountInfo<'a>>,
+        token_program: Box<AccountInfo<'a>>,
+        associated_token_program: Box<AccountInfo<'a>>,
+    ) -> Self {
+        Self {
+            account_info,
+            token_program,
+            associated_token_program,
+            data: AssociatedTokenAccount2022State::default(),
+            extension_types: vec![],
+        }
+    }
+
+    /// Instantiate a new `AssociatedTokenAccount2022` and load the account
+    /// inner data from on-chain.
+    pub fn load(
+        account_info: Box<AccountInfo<'a>>,
+        token_program: Box<AccountInfo<'a>>,
+        associated_token_program: Box<AccountInfo<'a>>,
+    ) -> Result<Self, ProgramError> {
+        check_token_program_owner(
+            &account_info,
+            &token_program,
+            "associated_token_account_2022",
+        )?;
+        let (data, extension_types) = match &account_info.try_borrow_data() {
+            Ok(acct_data) => {
+                match StateWithExtensions::<AssociatedTokenAccount2022State>::unpack(acct_data)
+                    .and_then(|state| Ok((state.base, state.get_extension_types()?)))
+                {
+                    Ok(state_data) => state_data,
+                    Err(_) => {
+                        return Err(NautilusError::DeserializeDataFailed(
+                            String::from("associated_token_account_2022"),
+                            account_info.key.to_string(),
+                        )
+                        .into())
+                    }
+                }
+            }
+            Err(_) => {
+                return Err(NautilusError::LoadDataFailed(
+                    String::from("associated_token_account_2022"),
+                    account_info.key.to_string(),
+                )
+                .into())
+            }
+        };
+        check_pda(
+            &account_info,
+            &spl_associated_token_account::get_associated_token_address_with_program_id(
+                &data.owner,
+                &data.mint,
+                account_info.owner,
+            ),
+            "associated_token_account_2022",
+        )?;
+        Ok(Self {
+            account_info,
+            token_program,
+            associated_token_program,
+            data,
+            extension_types,
+        })
+    }
+
+    /// Whether this account is owned by the Token-2022 Program rather than the
+    /// legacy Token Program.
+    pub fn is_token_2022(&self) -> bool {
+        self.account_info.owner.eq(&spl_token_2022::ID)
+    }
+
+    // Extension state
+
+    /// Reads an extension's state from the account's TLV data, or `None` if
+    /// the account doesn't have that extension.
+    pub fn get_extension<V: Extension + bytemuck::Pod>(&self) -> Result<Option<V>, ProgramError> {
+        let data = self.account_info.try_borrow_data()?;
+        let state = StateWithExtensions::<AssociatedTokenAccount2022State>::unpack(&data)?;
+        match state.get_extension_types()?.contains(&V::TYPE) {
+            true => Ok(Some(*state.get_extension::<V>()?)),
+            false => Ok(None),
+        }
+    }
+
+    /// The transfer fees withheld in this account, if its mint charges a
+    /// transfer fee.
+    pub fn transfer_fee_amount(&self) -> Result<Option<TransferFeeAmount>, ProgramError> {
+        self.get_extension::<TransferFeeAmount>()
+    }
+
+    /// Whether this account holds tokens of a non-transferable mint.
+    pub fn is_non_transferable(&self) -> Result<bool, ProgramError> {
+        Ok(self.get_extension::<NonTransferableAccount>()?.is_some())
+    }
+
+    /// Whether this account's owner can't be reassigned.
+    pub fn is_immutable_owner(&self) -> Result<bool, ProgramError> {
+        Ok(self.get_extension::<ImmutableOwner>()?.is_some())
+    }
+
+    /// The account's memo-transfer requirement, if any.
+    pub fn memo_transfer(&self) -> Result<Option<MemoTransfer>, ProgramError> {
+        self.get_extension::<MemoTransfer>()
+    }
+
+    /// The account's CPI guard, if any.
+    pub fn cpi_guard(&self) -> Result<Option<CpiGuard>, ProgramError> {
+        self.get_extension::<CpiGuard>()
+    }
+}
+
+impl<'a> NautilusAccountInfo<'a> for AssociatedTokenAccount2022<'a> {
+    fn account_info(&self) -> Box<AccountInfo<'a>> {
+        self.account_info.clone()
+    }
+
+    fn key(&self) -> &'a Pubkey {
+        self.account_info.key
+    }
+
+    fn is_signer(&self) -> bool {
+        self.account_info.is_signer
+    }
+
+    fn is_writable(&self) -> bool {
+        self.account_info.is_writable
+    }
+
+    fn lamports(&self) -> u64 {
+        self.account_info.lamports()
+    }
+
+    fn mut_lamports(&self) -> Result<std::cell::RefMut<'_, &'a mut u64>, ProgramError> {
+        self.account_info.try_borrow_mut_lamports()
+    }
+
+    fn owner(&self) -> &'a Pubkey {
+        self.account_info.owner
+    }
+
+    fn span(&self) -> Result<usize, ProgramError> {
+        match self.account_info.data_is_empty() {
+            true => ExtensionType::try_calculate_account_len::<AssociatedTokenAccount2022State>(
+                &self.extension_types,
+            ),
+            false => Ok(self.account_info.data_len()),
+        }
+    }
+}
+
+impl<'a> Mut<AssociatedTokenAccount2022<'a>> {
+    /// Burn tokens from this associated token account.
+    pub fn burn(
+        &self,
+        mint: impl NautilusAccountInfo<'a>,
+        authority: impl NautilusSigner<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
+        cpi::token_2022::burn(
+            self.self_account.token_program.key,
+            self.clone(),
+            mint,
+            authority,
+            multisigs,
+            amount,
+        )
+    }
+
+    /// Freeze token movement from this associated token account.
+    pub fn freeze(
+        &self,
+        mint: impl NautilusAccountInfo<'a>,
+        freeze_authority: impl NautilusSigner<'a>,
+    ) -> ProgramResult {
+        let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
+        cpi::token_2022::freeze_account(
+            self.self_account.token_program.key,
+            self.clone(),
+            mint,
+            freeze_authority,
+            multisigs,
+        )
+    }
+
+    /// Thaw this associated token account. It should be already frozen.
+    pub fn thaw(
+        &self,
+        mint: impl NautilusAccountInfo<'a>,
+        freeze_authority: impl NautilusSigner<'a>,
+    ) -> ProgramResult {
+        let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
+        cpi::token_2022::thaw_account(
+            self.self_account.token_program.key,
+            self.clone(),
+            mint,
+            freeze_authority,
+            multisigs,
+        )
+    }
+
+    /// Transfer tokens from this associated token account to another. Mints
+    /// with a transfer fee withhold the fee in the recipient's account.
+    pub fn transfer(
+        &self,
+        mint: Mint2022<'a>,
+        to: impl NautilusMut<'a>,
+        authority: impl NautilusSigner<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
+        let decimals = mint.data.decimals;
+        cpi::token_2022::transfer_checked(
+            self.self_account.token_program.key,
+            self.clone(),
+            mint,
+            to,
+            authority,
+            multisigs,
+            amount,
+            decimals,
+        )
+    }
+}
+
+impl<'a> Create<'a, AssociatedTokenAccount2022<'a>> {
+    /// Create a new Associated Token Account using the Associated Token
+    /// Program.
+    pub fn create(
+        &mut self,
+        mint: Mint2022<'a>,
+        owner: impl NautilusAccountInfo<'a>,
+    ) -> ProgramResult {
+        let payer = Signer::new(Wallet {
+            account_info: self.fee_payer.to_owned(),
+            system_program: self.system_program.to_owned(),
+        })?;
+        self.create_with_payer(mint, owner, payer)
+    }
+
+    /// This function is the same as `create(&mut self, ..)` but allows you to
+    /// specify a rent payer.
+    pub fn create_with_payer(
+        &mut self,
+        mint: Mint2022<'a>,
+        owner: impl NautilusAccountInfo<'a>,
+        payer: impl NautilusSigner<'a>,
+    ) -> ProgramResult {
+        cpi::associated_token::create_associated_token_account(
+            self.self_account.clone(),
+            owner,
+            mint,
+            payer,
+            self.system_program.to_owned(),
+            self.self_account.token_program.to_owned(),
+            self.self_account.associated_token_program.to_owned(),
+        )?;
+        self.self_account = AssociatedTokenAccount2022::load(
+            self.self_account.account_info.clone(),
+            self.self_account.token_program.clone(),
+            self.self_account.associated_token_program.clone(),
+        )?;
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@ pub use mpl_token_metadata::state::{
 };
 use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
 
-use crate::{error::NautilusError, NautilusAccountInfo};
+use crate::{error::NautilusError, properties::data::check_owner, NautilusAccountInfo};
 
 /// The Nautilus object representing an Edition of an NFT.
 ///
@@ -45,6 +45,7 @@ impl<'a> Edition<'a> {
         token_metadata_program: Box<AccountInfo<'a>>,
         rent: Box<AccountInfo<'a>>,
     ) -> Result<Self, ProgramError> {
+        check_owner(&account_info, &mpl_token_metadata::ID, "token_metadata")?;
         let data = match EditionState::safe_deserialize(match &account_info.try_borrow_data() {
             Ok(acct_data) => acct_data,
             Err(_) => {
@@ -145,6 +146,7 @@ impl<'a> MasterEdition<'a> {
         token_metadata_program: Box<AccountInfo<'a>>,
         rent: Box<AccountInfo<'a>>,
     ) -> Result<Self, ProgramError> {
+        check_owner(&account_info, &mpl_token_metadata::ID, "token_metadata")?;
         let data =
             match MasterEditionState::safe_deserialize(match &account_info.try_borrow_data() {
                 Ok(acct_data) => acct_data,
@@ -6,7 +6,10 @@ use solana_program::{
 };
 
 use crate::{
-    cpi, error::NautilusError, Create, Mint, NautilusAccountInfo, NautilusSigner, Signer, Wallet,
+    cpi,
+    error::NautilusError,
+    properties::data::{check_owner, check_pda},
+    Create, NautilusAccountInfo, NautilusSigner, Signer, Wallet,
 };
 
 /// The Nautilus object representing a token metadata account.
@@ -39,10 +42,14 @@ impl<'a> Metadata<'a> {
 
     /// Instantiate a new `Metadata` and load the account inner data from
     /// on-chain.
+    ///
+    /// The account must be owned by the Token Metadata Program, and its address
+    /// must be the metadata address of its mint.
     pub fn load(
         account_info: Box<AccountInfo<'a>>,
         token_metadata_program: Box<AccountInfo<'a>>,
     ) -> Result<Self, ProgramError> {
+        check_owner(&account_info, &mpl_token_metadata::ID, "token_metadata")?;
         let data = match MetadataState::safe_deserialize(match &account_info.try_borrow_data() {
             Ok(acct_data) => acct_data,
             Err(_) => {
@@ -62,6 +69,11 @@ impl<'a> Metadata<'a> {
                 .into())
             }
         };
+        check_pda(
+            &account_info,
+            &mpl_token_metadata::pda::find_metadata_account(&data.mint).0,
+            "token_metadata",
+        )?;
         Ok(Self {
             account_info,
             token_metadata_program,
@@ -112,7 +124,7 @@ impl<'a> Create<'a, Metadata<'a>> {
         title: String,
         symbol: String,
         uri: String,
-        mint: Mint<'a>,
+        mint: impl NautilusAccountInfo<'a>,
         mint_authority: impl NautilusSigner<'a>,
         update_authority: impl NautilusAccountInfo<'a>,
     ) -> ProgramResult {
@@ -147,7 +159,7 @@ impl<'a> Create<'a, Metadata<'a>> {
         title: String,
         symbol: String,
         uri: String,
-        mint: super::mint::Mint<'a>,
+        mint: impl NautilusAccountInfo<'a>,
         mint_authority: impl NautilusSigner<'a>,
         update_authority: impl NautilusAccountInfo<'a>,
         payer: impl NautilusSigner<'a>,
@@ -6,6 +6,7 @@ use solana_program::{
 use spl_token::instruction::AuthorityType;
 pub use spl_token::state::Mint as MintState;
 
+use super::check_token_program_owner;
 use crate::{
     cpi, error::NautilusError, Create, Mut, NautilusAccountInfo, NautilusMut, NautilusSigner,
     Signer, Wallet,
@@ -14,9 +15,10 @@ use crate::{
 /// The Nautilus object representing a mint account.
 ///
 /// The underlying account - designated in field `account_info` - is the mint
-/// account.
+/// account. It can be owned by either the Token Program or the Token-2022
+/// Program, and its owner is the program used for CPI operations.
 ///
-/// We also include the read-only Token Program for any CPI operations
+/// We also include the read-only token program for any CPI operations
 /// necessary, since we do not own this account.
 #[derive(Clone)]
 pub struct Mint<'a> {
@@ -38,13 +40,18 @@ impl<'a> Mint<'a> {
         }
     }
 
-    /// Instantiate a new `Mint` and load the account inner data from on-chain.
+    /// Instantiate a new `Mint` and load the account inner data from on-chain,
+    /// checking that it's owned by the token program provided.
+    ///
+    /// Only the base mint state is read, so a Token-2022 mint's extensions are
+    /// ignored. Use `Mint2022` to read them.
     pub fn load(
         account_info: Box<AccountInfo<'a>>,
         token_program: Box<AccountInfo<'a>>,
     ) -> Result<Self, ProgramError> {
+        check_token_program_owner(&account_info, &token_program, "token_mint")?;
         let data = match MintState::unpack(match &account_info.try_borrow_data() {
-            Ok(acct_data) => acct_data,
+            Ok(acct_data) => acct_data.get(..MintState::LEN).unwrap_or_default(),
             Err(_) => {
                 return Err(NautilusError::LoadDataFailed(
                     String::from("token_mint"),
@@ -102,7 +109,10 @@ impl<'a> NautilusAccountInfo<'a> for Mint<'a> {
     }
 
     fn span(&self) -> Result<usize, ProgramError> {
-        Ok(spl_token::state::Mint::LEN)
+        match self.account_info.data_is_empty() {
+            true => Ok(MintState::LEN),
+            false => Ok(self.account_info.data_len()),
+        }
     }
 }
 
@@ -115,14 +125,7 @@ impl<'a> Mut<Mint<'a>> {
         amount: u64,
     ) -> ProgramResult {
         let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
-        cpi::token::mint_to(
-            self.self_account.token_program.key,
-            self.clone(),
-            recipient,
-            mint_authority,
-            multisigs,
-            amount,
-        )
+        cpi::token::mint_to(self.clone(), recipient, mint_authority, multisigs, amount)
     }
 
     /// Change the mint's authority.
@@ -134,7 +137,6 @@ impl<'a> Mut<Mint<'a>> {
     ) -> ProgramResult {
         let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
         cpi::token::set_authority(
-            self.self_account.token_program.key,
             self.clone(),
             new_authority,
             authority_type,
@@ -145,7 +147,7 @@ impl<'a> Mut<Mint<'a>> {
 }
 
 impl<'a> Create<'a, Mint<'a>> {
-    /// Create a new SPL mint with a Token Program.
+    /// Create a new SPL mint with the provided token program.
     pub fn create(
         &mut self,
         decimals: u8,
@@ -158,7 +160,6 @@ impl<'a> Create<'a, Mint<'a>> {
         })?;
         cpi::system::create_account(self.clone(), self.self_account.token_program.key, payer)?;
         cpi::token::initialize_mint(
-            self.self_account.token_program.key,
             self.clone(),
             mint_authority.key(),
             freeze_authority.map(|f| f.key()),
@@ -183,7 +184,6 @@ impl<'a> Create<'a, Mint<'a>> {
     ) -> ProgramResult {
         cpi::system::create_account(self.clone(), self.self_account.token_program.key, payer)?;
         cpi::token::initialize_mint(
-            self.self_account.token_program.key,
             self.clone(),
             mint_authority.key(),
             freeze_authority.map(|f| f.key()),
@@ -197,3 +197,59 @@ impl<'a> Create<'a, Mint<'a>> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{
+        account_info::AccountInfo, program_option::COption, program_pack::Pack, pubkey::Pubkey,
+    };
+
+    use super::{Mint, MintState};
+
+    #[test]
+    fn load_reads_base_state_of_token_2022_mint() {
+        let (key, mint_authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let token_program = spl_token_2022::ID;
+        let mut data = vec![0; MintState::LEN];
+        MintState::pack(
+            MintState {
+                mint_authority: COption::Some(mint_authority),
+                supply: 100,
+                decimals: 6,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+            &mut data,
+        )
+        .unwrap();
+        // A Token-2022 mint's extensions follow its base state.
+        data.resize(MintState::LEN + 100, 1);
+        let (mut lamports, mut program_lamports) = (0, 0);
+        let mut program_data = vec![];
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &token_program,
+            false,
+            0,
+        );
+        let token_program_info = AccountInfo::new(
+            &token_program,
+            false,
+            false,
+            &mut program_lamports,
+            &mut program_data,
+            &token_program,
+            true,
+            0,
+        );
+
+        let mint = Mint::load(Box::new(account_info), Box::new(token_program_info)).unwrap();
+        assert_eq!(mint.data.mint_authority, COption::Some(mint_authority));
+        assert_eq!(mint.data.supply, 100);
+        assert_eq!(mint.data.decimals, 6);
+    }
+}
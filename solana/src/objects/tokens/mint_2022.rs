@@ -0,0 +1,368 @@
+//! The `Mint2022<T>` Nautilus object and all associated trait implementations.
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+pub use spl_token_2022::state::Mint as Mint2022State;
+use spl_token_2022::{
+    extension::{
+        interest_bearing_mint::InterestBearingConfig, metadata_pointer::MetadataPointer,
+        mint_close_authority::MintCloseAuthority, non_transferable::NonTransferable,
+        permanent_delegate::PermanentDelegate, transfer_fee::TransferFeeConfig,
+        BaseStateWithExtensions, Extension, ExtensionType, StateWithExtensions,
+    },
+    instruction::AuthorityType,
+};
+
+use super::check_token_program_owner;
+use crate::{
+    cpi, error::NautilusError, Create, Mut, NautilusAccountInfo, NautilusMut, NautilusSigner,
+    Signer, Wallet,
+};
+
+/// An extension to initialize on a new Token-2022 mint.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MintExtension {
+    /// Charge a fee on every transfer, withheld in the recipient's account.
+    TransferFeeConfig {
+        transfer_fee_config_authority: Option<Pubkey>,
+        withdraw_withheld_authority: Option<Pubkey>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    },
+    /// Accrue interest on the UI amount of all tokens of this mint.
+    InterestBearingConfig {
+        rate_authority: Option<Pubkey>,
+        rate: i16,
+    },
+    /// Tokens of this mint can't be transferred.
+    NonTransferable,
+    /// Give an authority unlimited delegate privileges over all tokens of this
+    /// mint.
+    PermanentDelegate { delegate: Pubkey },
+    /// Point to the account holding this mint's metadata.
+    MetadataPointer {
+        authority: Option<Pubkey>,
+        metadata_address: Option<Pubkey>,
+    },
+    /// Allow an authority to close this mint once its supply is zero.
+    MintCloseAuthority { close_authority: Option<Pubkey> },
+}
+
+impl MintExtension {
+    /// The Token-2022 `ExtensionType` this extension occupies in the mint's
+    /// TLV data.
+    pub fn extension_type(&self) -> ExtensionType {
+        match self {
+            Self::TransferFeeConfig { .. } => ExtensionType::TransferFeeConfig,
+            Self::InterestBearingConfig { .. } => ExtensionType::InterestBearingConfig,
+            Self::NonTransferable => ExtensionType::NonTransferable,
+            Self::PermanentDelegate { .. } => ExtensionType::PermanentDelegate,
+            Self::MetadataPointer { .. } => ExtensionType::MetadataPointer,
+            Self::MintCloseAuthority { .. } => ExtensionType::MintCloseAuthority,
+        }
+    }
+}
+
+/// The Nautilus object representing a mint account owned by either the Token
+/// Program or the Token-2022 Program.
+///
+/// The underlying account - designated in field `account_info` - is the mint
+/// account. Its owner determines which token program is used for CPI
+/// operations, and any Token-2022 extensions can be read from its TLV data.
+///
+/// We also include the read-only token program for any CPI operations
+/// necessary, since we do not own this account.
+#[derive(Clone)]
+pub struct Mint2022<'a> {
+    pub account_info: Box<AccountInfo<'a>>,
+    pub token_program: Box<AccountInfo<'a>>,
+    pub data: Mint2022State,
+    pub extension_types: Vec<ExtensionType>,
+}
+
+impl<'a> Mint2022<'a> {
+    // Inner data state associated functions
+
+    /// Instantiate a new `Mint2022` without loading the account inner data
+    /// from on-chain.
+    pub fn new(account_info: Box<AccountInfo<'a>>, token_program: Box<AccountInfo<'a>>) -> Self {
+        Self {
+            account_info,
+            token_program,
+            data: Mint2022State::default(),
+            extension_types: vec![],
+        }
+    }
+
+    /// Instantiate a new `Mint2022` and load the account inner data from
+    /// on-chain, checking that it's owned by the token program provided.
+    pub fn load(
+        account_info: Box<AccountInfo<'a>>,
+        token_program: Box<AccountInfo<'a>>,
+    ) -> Result<Self, ProgramError> {
+        check_token_program_owner(&account_info, &token_program, "token_mint_2022")?;
+        let (data, extension_types) = match &account_info.try_borrow_data() {
+            Ok(acct_data) => match StateWithExtensions::<Mint2022State>::unpack(acct_data)
+                .and_then(|state| Ok((state.base, state.get_extension_types()?)))
+            {
+                Ok(state_data) => state_data,
+                Err(_) => {
+                    return Err(NautilusError::DeserializeDataFailed(
+                        String::from("token_mint_2022"),
+                        account_info.key.to_string(),
+                    )
+                    .into())
+                }
+            },
+            Err(_) => {
+                return Err(NautilusError::LoadDataFailed(
+                    String::from("token_mint_2022"),
+                    account_info.key.to_string(),
+                )
+                .into())
+            }
+        };
+        Ok(Self {
+            account_info,
+            token_program,
+            data,
+            extension_types,
+        })
+    }
+
+    /// Whether this mint is owned by the Token-2022 Program rather than the
+    /// legacy Token Program.
+    pub fn is_token_2022(&self) -> bool {
+        self.account_info.owner.eq(&spl_token_2022::ID)
+    }
+
+    // Extension state
+
+    /// Reads an extension's state from the mint's TLV data, or `None` if the
+    /// mint doesn't have that extension.
+    pub fn get_extension<V: Extension + bytemuck::Pod>(&self) -> Result<Option<V>, ProgramError> {
+        let data = self.account_info.try_borrow_data()?;
+        let state = StateWithExtensions::<Mint2022State>::unpack(&data)?;
+        match state.get_extension_types()?.contains(&V::TYPE) {
+            true => Ok(Some(*state.get_extension::<V>()?)),
+            false => Ok(None),
+        }
+    }
+
+    /// The mint's transfer fee configuration, if any.
+    pub fn transfer_fee_config(&self) -> Result<Option<TransferFeeConfig>, ProgramError> {
+        self.get_extension::<TransferFeeConfig>()
+    }
+
+    /// The mint's interest-bearing configuration, if any.
+    pub fn interest_bearing_config(&self) -> Result<Option<InterestBearingConfig>, ProgramError> {
+        self.get_extension::<InterestBearingConfig>()
+    }
+
+    /// Whether tokens of this mint are non-transferable.
+    pub fn is_non_transferable(&self) -> Result<bool, ProgramError> {
+        Ok(self.get_extension::<NonTransferable>()?.is_some())
+    }
+
+    /// The mint's permanent delegate, if any.
+    pub fn permanent_delegate(&self) -> Result<Option<PermanentDelegate>, ProgramError> {
+        self.get_extension::<PermanentDelegate>()
+    }
+
+    /// The mint's metadata pointer, if any.
+    pub fn metadata_pointer(&self) -> Result<Option<MetadataPointer>, ProgramError> {
+        self.get_extension::<MetadataPointer>()
+    }
+
+    /// The mint's close authority, if any.
+    pub fn mint_close_authority(&self) -> Result<Option<MintCloseAuthority>, ProgramError> {
+        self.get_extension::<MintCloseAuthority>()
+    }
+}
+
+impl<'a> NautilusAccountInfo<'a> for Mint2022<'a> {
+    fn account_info(&self) -> Box<AccountInfo<'a>> {
+        self.account_info.clone()
+    }
+
+    fn key(&self) -> &'a Pubkey {
+        self.account_info.key
+    }
+
+    fn is_signer(&self) -> bool {
+        self.account_info.is_signer
+    }
+
+    fn is_writable(&self) -> bool {
+        self.account_info.is_writable
+    }
+
+    fn lamports(&self) -> u64 {
+        self.account_info.lamports()
+    }
+
+    fn mut_lamports(&self) -> Result<std::cell::RefMut<'_, &'a mut u64>, ProgramError> {
+        self.account_info.try_borrow_mut_lamports()
+    }
+
+    fn owner(&self) -> &'a Pubkey {
+        self.account_info.owner
+    }
+
+    fn span(&self) -> Result<usize, ProgramError> {
+        match self.account_info.data_is_empty() {
+            true => {
+                ExtensionType::try_calculate_account_len::<Mint2022State>(&self.extension_types)
+            }
+            false => Ok(self.account_info.data_len()),
+        }
+    }
+}
+
+impl<'a> Mut<Mint2022<'a>> {
+    /// Mint new tokens to a token account.
+    pub fn mint_to(
+        &self,
+        recipient: impl NautilusMut<'a>,
+        mint_authority: impl NautilusSigner<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
+        cpi::token_2022::mint_to(
+            self.self_account.token_program.key,
+            self.clone(),
+            recipient,
+            mint_authority,
+            multisigs,
+            amount,
+        )
+    }
+
+    /// Change one of the mint's authorities.
+    pub fn set_authority(
+        &self,
+        new_authority: Option<&Pubkey>,
+        authority_type: AuthorityType,
+        current_authority: impl NautilusSigner<'a>,
+    ) -> ProgramResult {
+        let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
+        cpi::token_2022::set_authority(
+            self.self_account.token_program.key,
+            self.clone(),
+            new_authority,
+            authority_type,
+            current_authority,
+            multisigs,
+        )
+    }
+}
+
+impl<'a> Create<'a, Mint2022<'a>> {
+    /// Create a new mint with the provided token program, initializing any
+    /// Token-2022 extensions.
+    pub fn create(
+        &mut self,
+        decimals: u8,
+        mint_authority: impl NautilusSigner<'a>,
+        freeze_authority: Option<impl NautilusAccountInfo<'a>>,
+        extensions: Vec<MintExtension>,
+    ) -> ProgramResult {
+        let payer = Signer::new(Wallet {
+            account_info: self.fee_payer.to_owned(),
+            system_program: self.system_program.to_owned(),
+        })?;
+        self.create_with_payer(
+            decimals,
+            mint_authority,
+            freeze_authority,
+            extensions,
+            payer,
+        )
+    }
+
+    /// This function is the same as `create(&mut self, ..)` but allows you to
+    /// specify a rent payer.
+    pub fn create_with_payer(
+        &mut self,
+        decimals: u8,
+        mint_authority: impl NautilusSigner<'a>,
+        freeze_authority: Option<impl NautilusAccountInfo<'a>>,
+        extensions: Vec<MintExtension>,
+        payer: impl NautilusSigner<'a>,
+    ) -> ProgramResult {
+        self.self_account.extension_types = extensions.iter().map(|e| e.extension_type()).collect();
+        cpi::system::create_account(self.clone(), self.self_account.token_program.key, payer)?;
+        for extension in extensions {
+            self.initialize_extension(extension)?;
+        }
+        cpi::token_2022::initialize_mint(
+            self.self_account.token_program.key,
+            self.clone(),
+            mint_authority.key(),
+            freeze_authority.map(|f| f.key()),
+            decimals,
+            self.rent.to_owned(),
+        )?;
+        self.self_account = Mint2022::load(
+            self.self_account.account_info.clone(),
+            self.self_account.token_program.clone(),
+        )?;
+        Ok(())
+    }
+
+    /// Initializes a single extension on the newly created mint account.
+    fn initialize_extension(&self, extension: MintExtension) -> ProgramResult {
+        let token_program_id = self.self_account.token_program.key;
+        match extension {
+            MintExtension::TransferFeeConfig {
+                transfer_fee_config_authority,
+                withdraw_withheld_authority,
+                transfer_fee_basis_points,
+                maximum_fee,
+            } => cpi::token_2022::initialize_transfer_fee_config(
+                token_program_id,
+                self.clone(),
+                transfer_fee_config_authority.as_ref(),
+                withdraw_withheld_authority.as_ref(),
+                transfer_fee_basis_points,
+                maximum_fee,
+            ),
+            MintExtension::InterestBearingConfig {
+                rate_authority,
+                rate,
+            } => cpi::token_2022::initialize_interest_bearing_mint(
+                token_program_id,
+                self.clone(),
+                rate_authority,
+                rate,
+            ),
+            MintExtension::NonTransferable => {
+                cpi::token_2022::initialize_non_transferable_mint(token_program_id, self.clone())
+            }
+            MintExtension::PermanentDelegate { delegate } => {
+                cpi::token_2022::initialize_permanent_delegate(
+                    token_program_id,
+                    self.clone(),
+                    &delegate,
+                )
+            }
+            MintExtension::MetadataPointer {
+                authority,
+                metadata_address,
+            } => cpi::token_2022::initialize_metadata_pointer(
+                token_program_id,
+                self.clone(),
+                authority,
+                metadata_address,
+            ),
+            MintExtension::MintCloseAuthority { close_authority } => {
+                cpi::token_2022::initialize_mint_close_authority(
+                    token_program_id,
+                    self.clone(),
+                    close_authority.as_ref(),
+                )
+            }
+        }
+    }
+}
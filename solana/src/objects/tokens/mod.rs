@@ -1,8 +1,97 @@
 //! Submodule containing all token-based Nautilus objects and their associated
 //! trait implementations.
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
+
+use crate::error::NautilusError;
+
 pub mod associated_token;
+pub mod associated_token_2022;
 pub mod edition;
 pub mod metadata;
 pub mod mint;
+pub mod mint_2022;
 pub mod nft;
 pub mod token;
+pub mod token_2022;
+
+/// Checks that a token account or mint is owned by either the legacy Token
+/// Program or the Token-2022 Program, and that the token program provided
+/// alongside it is the one that owns it.
+pub(crate) fn check_token_program_owner(
+    account_info: &AccountInfo,
+    token_program: &AccountInfo,
+    state_type: &str,
+) -> ProgramResult {
+    match (account_info.owner.eq(&spl_token::ID) || account_info.owner.eq(&spl_token_2022::ID))
+        && account_info.owner.eq(token_program.key)
+    {
+        true => Ok(()),
+        false => Err(NautilusError::InvalidOwner(
+            String::from(state_type),
+            account_info.key.to_string(),
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+    use super::check_token_program_owner;
+    use crate::error::NautilusError;
+
+    fn check(owner: &Pubkey, token_program: &Pubkey) -> solana_program::entrypoint::ProgramResult {
+        let key = Pubkey::new_unique();
+        let (mut lamports, mut program_lamports) = (0, 0);
+        let (mut data, mut program_data) = (vec![], vec![]);
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            owner,
+            false,
+            0,
+        );
+        let token_program_info = AccountInfo::new(
+            token_program,
+            false,
+            false,
+            &mut program_lamports,
+            &mut program_data,
+            owner,
+            true,
+            0,
+        );
+        check_token_program_owner(&account_info, &token_program_info, "token_mint")
+    }
+
+    #[test]
+    fn accepts_either_token_program() {
+        assert!(check(&spl_token::ID, &spl_token::ID).is_ok());
+        assert!(check(&spl_token_2022::ID, &spl_token_2022::ID).is_ok());
+    }
+
+    #[test]
+    fn rejects_other_token_program_than_owner() {
+        assert_eq!(
+            check(&spl_token_2022::ID, &spl_token::ID).err(),
+            Some(NautilusError::InvalidOwner(String::new(), String::new()).into())
+        );
+        assert_eq!(
+            check(&spl_token::ID, &spl_token_2022::ID).err(),
+            Some(NautilusError::InvalidOwner(String::new(), String::new()).into())
+        );
+    }
+
+    #[test]
+    fn rejects_owner_other_than_token_programs() {
+        let owner = Pubkey::new_unique();
+        assert_eq!(
+            check(&owner, &owner).err(),
+            Some(NautilusError::InvalidOwner(String::new(), String::new()).into())
+        );
+    }
+}
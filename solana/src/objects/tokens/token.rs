@@ -6,8 +6,8 @@ use solana_program::{
 use spl_token::instruction::AuthorityType;
 
 use crate::{
-    cpi, Create, Metadata, Mint, Mut, NautilusAccountInfo, NautilusMut, NautilusSigner, Signer,
-    Wallet,
+    cpi, error::NautilusError, Create, Metadata, Mint, Mut, NautilusAccountInfo, NautilusMut,
+    NautilusSigner, Signer, Wallet,
 };
 
 /// The Nautilus object representing the combination of a mint account and a
@@ -41,16 +41,24 @@ impl<'a> Token<'a> {
     }
 
     /// Instantiate a new `Token` and load the account inner data from on-chain.
+    ///
+    /// The metadata account must be the metadata of the mint.
     pub fn load(
         mint_account: Box<AccountInfo<'a>>,
         metadata_account: Box<AccountInfo<'a>>,
         token_program: Box<AccountInfo<'a>>,
         token_metadata_program: Box<AccountInfo<'a>>,
     ) -> Result<Self, ProgramError> {
-        Ok(Self {
-            mint: Mint::load(mint_account, token_program)?,
-            metadata: Metadata::load(metadata_account, token_metadata_program)?,
-        })
+        let mint = Mint::load(mint_account, token_program)?;
+        let metadata = Metadata::load(metadata_account, token_metadata_program)?;
+        if metadata.data.mint.ne(mint.account_info.key) {
+            return Err(NautilusError::PdaMismatch(
+                String::from("token_metadata"),
+                metadata.account_info.key.to_string(),
+            )
+            .into());
+        }
+        Ok(Self { mint, metadata })
     }
 }
 
@@ -97,14 +105,7 @@ impl<'a> Mut<Token<'a>> {
         amount: u64,
     ) -> ProgramResult {
         let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
-        cpi::token::mint_to(
-            self.self_account.mint.token_program.key,
-            self.clone(),
-            recipient,
-            mint_authority,
-            multisigs,
-            amount,
-        )
+        cpi::token::mint_to(self.clone(), recipient, mint_authority, multisigs, amount)
     }
 
     /// Change the mint's authority.
@@ -116,7 +117,6 @@ impl<'a> Mut<Token<'a>> {
     ) -> ProgramResult {
         let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
         cpi::token::set_authority(
-            self.self_account.mint.token_program.key,
             self.clone(),
             new_authority,
             authority_type,
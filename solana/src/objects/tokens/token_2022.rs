@@ -0,0 +1,242 @@
+//! The `Token2022<T>` Nautilus object and all associated trait implementations.
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_token_2022::instruction::AuthorityType;
+
+use crate::{
+    cpi, error::NautilusError, Create, Metadata, Mint2022, MintExtension, Mut, NautilusAccountInfo,
+    NautilusMut, NautilusSigner, Signer, Wallet,
+};
+
+/// The Nautilus object representing the combination of a mint account owned by
+/// either the Token Program or the Token-2022 Program and a token metadata
+/// account.
+///
+/// This is the `Token` object for Token-2022 mints: it's comprised of both a
+/// `Mint2022` and `Metadata` struct, which allows you to access either
+/// individually, and most of its implemented methods access the mint account.
+#[derive(Clone)]
+pub struct Token2022<'a> {
+    pub mint: Mint2022<'a>,
+    pub metadata: Metadata<'a>,
+}
+
+impl<'a> Token2022<'a> {
+    /// Instantiate a new `Token2022` without loading the account inner data
+    /// from on-chain.
+    pub fn new(
+        mint_account: Box<AccountInfo<'a>>,
+        metadata_account: Box<AccountInfo<'a>>,
+        token_program: Box<AccountInfo<'a>>,
+        token_metadata_program: Box<AccountInfo<'a>>,
+    ) -> Self {
+        Self {
+            mint: Mint2022::new(mint_account, token_program),
+            metadata: Metadata::new(metadata_account, token_metadata_program),
+        }
+    }
+
+    /// Instantiate a new `Token2022` and load the account inner data from
+    /// on-chain.
+    ///
+    /// The metadata account must be the metadata of the mint.
+    pub fn load(
+        mint_account: Box<AccountInfo<'a>>,
+        metadata_account: Box<AccountInfo<'a>>,
+        token_program: Box<AccountInfo<'a>>,
+        token_metadata_program: Box<AccountInfo<'a>>,
+    ) -> Result<Self, ProgramError> {
+        let mint = Mint2022::load(mint_account, token_program)?;
+        let metadata = Metadata::load(metadata_account, token_metadata_program)?;
+        if metadata.data.mint.ne(mint.account_info.key) {
+            return Err(NautilusError::PdaMismatch(
+                String::from("token_metadata"),
+                metadata.account_info.key.to_string(),
+            )
+            .into());
+        }
+        Ok(Self { mint, metadata })
+    }
+}
+
+impl<'a> NautilusAccountInfo<'a> for Token2022<'a> {
+    fn account_info(&self) -> Box<AccountInfo<'a>> {
+        self.mint.account_info()
+    }
+
+    fn key(&self) -> &'a Pubkey {
+        self.mint.account_info.key
+    }
+
+    fn is_signer(&self) -> bool {
+        self.mint.account_info.is_signer
+    }
+
+    fn is_writable(&self) -> bool {
+        self.mint.account_info.is_writable
+    }
+
+    fn lamports(&self) -> u64 {
+        self.mint.account_info.lamports()
+    }
+
+    fn mut_lamports(&self) -> Result<std::cell::RefMut<'_, &'a mut u64>, ProgramError> {
+        self.mint.account_info.try_borrow_mut_lamports()
+    }
+
+    fn owner(&self) -> &'a Pubkey {
+        self.mint.account_info.owner
+    }
+
+    fn span(&self) -> Result<usize, ProgramError> {
+        self.mint.span()
+    }
+}
+
+impl<'a> Mut<Token2022<'a>> {
+    /// Mint new tokens to a token account.
+    pub fn mint_to(
+        &self,
+        recipient: impl NautilusMut<'a>,
+        mint_authority: impl NautilusSigner<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
+        cpi::token_2022::mint_to(
+            self.self_account.mint.token_program.key,
+            self.clone(),
+            recipient,
+            mint_authority,
+            multisigs,
+            amount,
+        )
+    }
+
+    /// Change one of the mint's authorities.
+    pub fn set_authority(
+        &self,
+        new_authority: Option<&Pubkey>,
+        authority_type: AuthorityType,
+        current_authority: impl NautilusSigner<'a>,
+    ) -> ProgramResult {
+        let multisigs: Option<Vec<Signer<Wallet>>> = None; // TODO: Multisig support
+        cpi::token_2022::set_authority(
+            self.self_account.mint.token_program.key,
+            self.clone(),
+            new_authority,
+            authority_type,
+            current_authority,
+            multisigs,
+        )
+    }
+}
+
+impl<'a> Create<'a, Token2022<'a>> {
+    /// Create a new mint with the provided token program, initializing any
+    /// Token-2022 extensions, and a new SPL metadata account with Token
+    /// Metadata Program.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create(
+        &mut self,
+        decimals: u8,
+        title: String,
+        symbol: String,
+        uri: String,
+        mint_authority: impl NautilusSigner<'a>,
+        update_authority: impl NautilusAccountInfo<'a>,
+        freeze_authority: Option<impl NautilusAccountInfo<'a>>,
+        extensions: Vec<MintExtension>,
+    ) -> ProgramResult {
+        let mut create_mint: Create<Mint2022> = self.clone().into();
+        let mut create_metadata: Create<Metadata> = self.clone().into();
+        create_mint.create(
+            decimals,
+            mint_authority.clone(),
+            freeze_authority,
+            extensions,
+        )?;
+        create_metadata.create(
+            title,
+            symbol,
+            uri,
+            self.self_account.mint.to_owned(),
+            mint_authority,
+            update_authority,
+        )?;
+        Ok(())
+    }
+
+    /// This function is the same as `create(&mut self, ..)` but allows you to
+    /// specify a rent payer.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_with_payer(
+        &mut self,
+        decimals: u8,
+        title: String,
+        symbol: String,
+        uri: String,
+        mint_authority: impl NautilusSigner<'a>,
+        update_authority: impl NautilusAccountInfo<'a>,
+        freeze_authority: Option<impl NautilusAccountInfo<'a>>,
+        extensions: Vec<MintExtension>,
+        payer: impl NautilusSigner<'a>,
+    ) -> ProgramResult {
+        let mut create_mint: Create<Mint2022> = self.clone().into();
+        let mut create_metadata: Create<Metadata> = self.clone().into();
+        create_mint.create_with_payer(
+            decimals,
+            mint_authority.clone(),
+            freeze_authority,
+            extensions,
+            payer.clone(),
+        )?;
+        create_metadata.create_with_payer(
+            title,
+            symbol,
+            uri,
+            self.self_account.mint.to_owned(),
+            mint_authority,
+            update_authority,
+            payer,
+        )?;
+        Ok(())
+    }
+}
+
+// Converters
+
+impl<'a> From<Token2022<'a>> for Mint2022<'a> {
+    fn from(value: Token2022<'a>) -> Self {
+        value.mint
+    }
+}
+
+impl<'a> From<Create<'a, Token2022<'a>>> for Create<'a, Mint2022<'a>> {
+    fn from(value: Create<'a, Token2022<'a>>) -> Self {
+        Self {
+            self_account: value.self_account.into(),
+            fee_payer: value.fee_payer,
+            rent: value.rent,
+            system_program: value.system_program,
+        }
+    }
+}
+
+impl<'a> From<Token2022<'a>> for Metadata<'a> {
+    fn from(value: Token2022<'a>) -> Self {
+        value.metadata
+    }
+}
+
+impl<'a> From<Create<'a, Token2022<'a>>> for Create<'a, Metadata<'a>> {
+    fn from(value: Create<'a, Token2022<'a>>) -> Self {
+        Self {
+            self_account: value.self_account.into(),
+            fee_payer: value.fee_payer,
+            rent: value.rent,
+            system_program: value.system_program,
+        }
+    }
+}
@@ -1,6 +1,11 @@
 //! Traits used for managing the account data of Nautilus objects.
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{error::NautilusError, NautilusLookupKey};
 
 use super::NautilusAccountInfo;
 
@@ -13,10 +18,38 @@ use super::NautilusAccountInfo;
 pub trait NautilusAccountData: BorshDeserialize + BorshSerialize + Clone + Default {
     const DISCRIMINATOR_STR: &'static str;
 
+    /// The static span of an account containing this data type, if every
+    /// field has a maximum size - bounding any `String` or `Vec` with
+    /// `#[max_len(..)]`.
+    ///
+    /// Accounts are allocated to this span when it's known, rather than to the
+    /// size of their current data.
+    const SPACE: Option<usize> = None;
+
+    /// Whether this data type is versioned with `#[version(..)]`, in which case
+    /// its accounts are marked with `versioned_discriminator(..)`.
+    const VERSIONED: bool = false;
+
+    /// Whether a versioned data type can still be read from its layout from
+    /// before it was versioned, declared with `#[migrate(from = 0, ..)]`.
+    const MIGRATES_UNVERSIONED: bool = false;
+
     /// The 8-bit discriminator applied to this account as a prefix on any
     /// account data containing this data type.
     fn discriminator(&self) -> [u8; 8] {
-        discriminator(Self::DISCRIMINATOR_STR)
+        match Self::VERSIONED {
+            true => versioned_discriminator(Self::DISCRIMINATOR_STR),
+            false => discriminator(Self::DISCRIMINATOR_STR),
+        }
+    }
+
+    /// The program-derived address of an account containing this data, if its
+    /// seeds don't take any parameters.
+    ///
+    /// When it's known, an account's address is checked against it as the
+    /// account is loaded.
+    fn derived_pda(&self, _program_id: &Pubkey) -> Option<(Pubkey, u8)> {
+        None
     }
 
     /// Checks authorities against the data's declared authorities.
@@ -36,10 +69,29 @@ pub trait NautilusRecordData: BorshDeserialize + BorshSerialize + Clone + Defaul
     const TABLE_NAME: &'static str;
     const AUTO_INCREMENT: bool;
 
+    /// The static span of a record containing this data type, if every field
+    /// has a maximum size - bounding any `String` or `Vec` with
+    /// `#[max_len(..)]`.
+    ///
+    /// Records are allocated to this span when it's known, rather than to the
+    /// size of their current data.
+    const SPACE: Option<usize> = None;
+
+    /// Whether this data type is versioned with `#[version(..)]`, in which case
+    /// its records are marked with `versioned_discriminator(..)`.
+    const VERSIONED: bool = false;
+
+    /// Whether a versioned data type can still be read from its layout from
+    /// before it was versioned, declared with `#[migrate(from = 0, ..)]`.
+    const MIGRATES_UNVERSIONED: bool = false;
+
     /// The 8-bit discriminator applied to this account as a prefix on any
     /// account data containing this data type.
     fn discriminator(&self) -> [u8; 8] {
-        discriminator(Self::TABLE_NAME)
+        match Self::VERSIONED {
+            true => versioned_discriminator(Self::TABLE_NAME),
+            false => discriminator(Self::TABLE_NAME),
+        }
     }
 
     /// The primary key of this particular record's account data type. This will
@@ -66,6 +118,12 @@ pub trait NautilusRecordData: BorshDeserialize + BorshSerialize + Clone + Defaul
         Pubkey::find_program_address(&seeds, program_id)
     }
 
+    /// The secondary indexes declared on this table with `#[index]` or
+    /// `#[unique]`, along with this record's value for each of them.
+    fn lookups(&self) -> Vec<NautilusLookupKey> {
+        vec![]
+    }
+
     /// Checks authorities against the data's declared authorities.
     fn check_authorities(&self, accounts: Vec<AccountInfo>) -> Result<(), ProgramError>;
 
@@ -121,9 +179,192 @@ pub trait NautilusRecord<'a>: NautilusAccountInfo<'a> {
 }
 
 /// Helper function to return the 8-bit discriminator of an account data type.
-fn discriminator(discrim_str: &str) -> [u8; 8] {
+pub fn discriminator(discrim_str: &str) -> [u8; 8] {
     let mut discriminator = [0u8; 8];
     let preimage = format!("{}:{}", "global", discrim_str);
     discriminator.copy_from_slice(&solana_program::hash::hash(preimage.as_bytes()).to_bytes()[..8]); // First 8 bytes
     discriminator
 }
+
+/// Helper function to return the 8-bit discriminator of a versioned account
+/// data type, which is followed by the data's version.
+///
+/// It differs from the data type's unversioned discriminator, so versioned
+/// data can never be mistaken for data from before the type was versioned.
+pub fn versioned_discriminator(discrim_str: &str) -> [u8; 8] {
+    discriminator(&format!("{}:versioned", discrim_str))
+}
+
+/// Deserializes an account's data into its data type.
+///
+/// A data type with a static span is allocated to it, so its serialized data
+/// may be followed by zero padding wherever a `#[max_len(..)]` field isn't
+/// full. The padding is left unread rather than rejected.
+pub(crate) fn deserialize_data<T: BorshDeserialize>(
+    data: &[u8],
+    space: Option<usize>,
+) -> std::io::Result<T> {
+    match space {
+        Some(_) => T::deserialize(&mut &data[..]),
+        None => T::try_from_slice(data),
+    }
+}
+
+/// Checks that an account is owned by the program expected to own an account
+/// of its type.
+pub(crate) fn check_owner(
+    account_info: &AccountInfo,
+    owner: &Pubkey,
+    state_type: &str,
+) -> ProgramResult {
+    match account_info.owner.eq(owner) {
+        true => Ok(()),
+        false => Err(NautilusError::InvalidOwner(
+            String::from(state_type),
+            account_info.key.to_string(),
+        )
+        .into()),
+    }
+}
+
+/// Checks that an account's data begins with the 8-bit discriminator of its
+/// expected data type.
+pub(crate) fn check_discriminator(account_info: &AccountInfo, discrim_str: &str) -> ProgramResult {
+    check_discriminator_in(account_info, discrim_str, &[discriminator(discrim_str)])
+}
+
+/// Checks that an account's data begins with the 8-bit discriminator of a
+/// data type that may be versioned.
+///
+/// A versioned data type's accounts begin with its versioned discriminator, or
+/// its unversioned one if they can be migrated from before it was versioned.
+pub(crate) fn check_data_discriminator(
+    account_info: &AccountInfo,
+    discrim_str: &str,
+    versioned: bool,
+    migrates_unversioned: bool,
+) -> ProgramResult {
+    match (versioned, migrates_unversioned) {
+        (false, _) => check_discriminator(account_info, discrim_str),
+        (true, false) => check_discriminator_in(
+            account_info,
+            discrim_str,
+            &[versioned_discriminator(discrim_str)],
+        ),
+        (true, true) => check_discriminator_in(
+            account_info,
+            discrim_str,
+            &[
+                versioned_discriminator(discrim_str),
+                discriminator(discrim_str),
+            ],
+        ),
+    }
+}
+
+/// Checks that an account's data begins with one of the provided
+/// discriminators.
+fn check_discriminator_in(
+    account_info: &AccountInfo,
+    discrim_str: &str,
+    discriminators: &[[u8; 8]],
+) -> ProgramResult {
+    let data = match account_info.try_borrow_data() {
+        Ok(data) => data,
+        Err(_) => {
+            return Err(NautilusError::LoadDataFailed(
+                String::from(discrim_str),
+                account_info.key.to_string(),
+            )
+            .into())
+        }
+    };
+    match data.len() >= 8 && discriminators.iter().any(|d| data[..8] == d[..]) {
+        true => Ok(()),
+        false => Err(NautilusError::DiscriminatorMismatch(
+            String::from(discrim_str),
+            account_info.key.to_string(),
+        )
+        .into()),
+    }
+}
+
+/// Checks that an account's address is the program-derived address expected
+/// for it.
+pub(crate) fn check_pda(
+    account_info: &AccountInfo,
+    pda: &Pubkey,
+    state_type: &str,
+) -> ProgramResult {
+    match account_info.key.eq(pda) {
+        true => Ok(()),
+        false => Err(NautilusError::PdaMismatch(
+            String::from(state_type),
+            account_info.key.to_string(),
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+    use super::*;
+
+    fn check(data: &mut [u8], versioned: bool, migrates_unversioned: bool) -> ProgramResult {
+        let (key, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut lamports = 0;
+        let account_info =
+            AccountInfo::new(&key, false, false, &mut lamports, data, &owner, false, 0);
+        check_data_discriminator(&account_info, "person", versioned, migrates_unversioned)
+    }
+
+    fn mismatch() -> ProgramError {
+        NautilusError::DiscriminatorMismatch(String::new(), String::new()).into()
+    }
+
+    #[test]
+    fn versioned_discriminator_differs_from_unversioned() {
+        assert_ne!(discriminator("person"), versioned_discriminator("person"));
+    }
+
+    #[test]
+    fn unversioned_data_requires_unversioned_discriminator() {
+        assert_eq!(check(&mut discriminator("person"), false, false), Ok(()));
+        assert_eq!(
+            check(&mut versioned_discriminator("person"), false, false),
+            Err(mismatch())
+        );
+    }
+
+    #[test]
+    fn versioned_data_requires_versioned_discriminator() {
+        assert_eq!(
+            check(&mut versioned_discriminator("person"), true, false),
+            Ok(())
+        );
+        assert_eq!(
+            check(&mut discriminator("person"), true, false),
+            Err(mismatch())
+        );
+    }
+
+    #[test]
+    fn versioned_data_migrating_unversioned_accepts_both_discriminators() {
+        assert_eq!(
+            check(&mut versioned_discriminator("person"), true, true),
+            Ok(())
+        );
+        assert_eq!(check(&mut discriminator("person"), true, true), Ok(()));
+        assert_eq!(
+            check(&mut discriminator("other"), true, true),
+            Err(mismatch())
+        );
+    }
+
+    #[test]
+    fn rejects_data_shorter_than_discriminator() {
+        assert_eq!(check(&mut [0u8; 4], false, false), Err(mismatch()));
+    }
+}
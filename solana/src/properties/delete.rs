@@ -0,0 +1,28 @@
+//! Traits used for deleting (closing) Nautilus objects.
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
+
+use super::{mutable::NautilusMut, NautilusAccountInfo};
+
+/// The trait that allows a program-owned Nautilus object to be closed.
+///
+/// Deleting an object zeroes out its account data, drains all of its Lamports
+/// to a designated receiver and assigns the account back to the System Program,
+/// which makes the rent paid for the account refundable.
+pub trait NautilusDelete<'a>: NautilusAccountInfo<'a> {
+    /// Deletes the object, sending its Lamports to the receiver.
+    ///
+    /// This will fail if the object's data declares any authorities, since
+    /// none are provided - use `delete_with_authorities(..)` instead.
+    fn delete(&self, receiver: impl NautilusMut<'a>) -> ProgramResult {
+        self.delete_with_authorities(vec![], receiver)
+    }
+
+    /// This function is the same as `delete(&self, ..)` but first checks the
+    /// provided accounts against the object's declared authorities using
+    /// `check_authorities(..)`.
+    fn delete_with_authorities(
+        &self,
+        authorities: Vec<AccountInfo>,
+        receiver: impl NautilusMut<'a>,
+    ) -> ProgramResult;
+}
@@ -8,8 +8,11 @@ use self::mutable::NautilusMut;
 
 pub mod create;
 pub mod data;
+pub mod delete;
 pub mod mutable;
+pub mod remaining;
 pub mod signer;
+pub mod update;
 
 /// The core trait that marks an object in a Nautilus program as being comprised
 /// of Solana accounts.
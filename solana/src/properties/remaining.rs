@@ -0,0 +1,68 @@
+//! The wrapper for an instruction's remaining accounts.
+use std::marker::PhantomData;
+
+use super::NautilusAccountInfo;
+
+/// The struct holding an instruction's remaining accounts - any accounts passed
+/// after the ones the instruction declares - each loaded as a Nautilus object
+/// `T`.
+///
+/// A user declares a `Remaining<'_, T>` parameter to accept any number of
+/// accounts of the same kind, such as the recipients of an airdrop. Each
+/// account is loaded just like a parameter of type `T` would be, so wrapping
+/// `T` in `Mut<T>` or `Signer<T>` checks every account accordingly.
+#[derive(Clone)]
+pub struct Remaining<'a, T>
+where
+    T: Clone + NautilusAccountInfo<'a>,
+{
+    pub objects: Vec<T>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a, T> Remaining<'a, T>
+where
+    T: Clone + NautilusAccountInfo<'a>,
+{
+    pub fn new(objects: Vec<T>) -> Self {
+        Self {
+            objects,
+            _lifetime: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> std::ops::Deref for Remaining<'a, T>
+where
+    T: Clone + NautilusAccountInfo<'a>,
+{
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.objects
+    }
+}
+
+impl<'a, T> IntoIterator for Remaining<'a, T>
+where
+    T: Clone + NautilusAccountInfo<'a>,
+{
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.objects.into_iter()
+    }
+}
+
+impl<'r, 'a, T> IntoIterator for &'r Remaining<'a, T>
+where
+    T: Clone + NautilusAccountInfo<'a>,
+{
+    type Item = &'r T;
+    type IntoIter = std::slice::Iter<'r, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.objects.iter()
+    }
+}
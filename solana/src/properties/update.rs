@@ -0,0 +1,45 @@
+//! Traits used for updating Nautilus objects.
+use solana_program::entrypoint::ProgramResult;
+
+use super::{signer::NautilusSigner, NautilusAccountInfo};
+
+/// The trait that allows a program-owned Nautilus object to write its inner
+/// data back into its underlying account.
+///
+/// Since data types containing `String` or `Vec` fields can change in size,
+/// the account is reallocated to the new span of the data whenever it's
+/// updated.
+pub trait NautilusUpdate<'a>: NautilusAccountInfo<'a> {
+    /// Re-serializes the object's inner data into its account, reallocating
+    /// the account if necessary.
+    ///
+    /// Since there's no payer, this will fail if the new span of the data would
+    /// require more rent than the account holds, and any excess rent is left
+    /// in the account.
+    fn update(&self) -> ProgramResult;
+
+    /// This function is the same as `update(&self)` but allows you to specify
+    /// a payer, which tops up the account's rent if the data grows or is
+    /// refunded the excess rent if it shrinks.
+    fn update_with_payer(&self, payer: impl NautilusSigner<'a>) -> ProgramResult;
+}
+
+pub trait NautilusUpdateMetadata<'a> {
+    fn update_metadata(
+        &self,
+        title: Option<String>,
+        symbol: Option<String>,
+        uri: Option<String>,
+        update_authority: impl NautilusSigner<'a>,
+    ) -> ProgramResult;
+}
+
+pub trait NautilusUpdateToken<'a> {
+    fn update_metadata(
+        &self,
+        title: Option<String>,
+        symbol: Option<String>,
+        uri: Option<String>,
+        update_authority: impl NautilusSigner<'a>,
+    ) -> ProgramResult;
+}
@@ -0,0 +1,536 @@
+//! Builds typed Cross-Program Invocations (CPI) into another Nautilus program
+//! from its IDL, declared with `nautilus::declare_program!("path/to/idl.json")`.
+use std::str::FromStr;
+
+use nautilus_idl::{
+    converters::rust::{rust_ident, RustConverter},
+    idl_instruction::{IdlInstruction, IdlInstructionAccount},
+    idl_type_def::{IdlTypeDef, IdlTypeDefType},
+    util::account_discrim_str,
+    Idl,
+};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use solana_program::pubkey::Pubkey;
+use syn::{Ident, LitStr, Type};
+
+/// The struct containing the IDL of the Nautilus program to invoke.
+/// * `idl_path`: The absolute path of the IDL, so the crate is rebuilt whenever
+///   it changes.
+/// * `idl`: The IDL itself.
+#[derive(Clone, Debug)]
+pub struct NautilusCpi {
+    pub idl_path: String,
+    pub idl: Idl,
+}
+
+impl NautilusCpi {
+    /// Reads the IDL at the provided path, relative to the crate's root.
+    ///
+    /// The crate's root is its manifest directory, which Cargo provides to the
+    /// macro even when the build runs from elsewhere, such as a workspace root.
+    pub fn from_path(path: &LitStr) -> syn::Result<Self> {
+        let read_error = |e: std::io::Error| {
+            syn::Error::new_spanned(
+                path,
+                format!("Failed to read the IDL of the program to invoke: {}", e),
+            )
+        };
+        let crate_root = match std::env::var_os("CARGO_MANIFEST_DIR") {
+            Some(dir) => Ok(std::path::PathBuf::from(dir)),
+            None => std::env::current_dir(),
+        };
+        let idl_path = crate_root
+            .and_then(|dir| dir.join(path.value()).canonicalize())
+            .map_err(read_error)?;
+        let idl_path = idl_path.to_string_lossy().to_string();
+        let idl = Idl::load_from_json(&idl_path).map_err(read_error)?;
+        Ok(Self { idl_path, idl })
+    }
+}
+
+impl ToTokens for NautilusCpi {
+    /// Extends the existing compiler tokens by the tokens generated by the
+    /// `NautilusCpi`.
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match TokenStream::try_from(self) {
+            Ok(t) => tokens.extend(t),
+            Err(e) => tokens.extend(e.to_compile_error()),
+        }
+    }
+}
+
+impl TryFrom<&NautilusCpi> for TokenStream {
+    type Error = syn::Error;
+
+    /// Builds a module named after the program, holding a CPI function for
+    /// each of its instructions and the types its instructions take as
+    /// arguments, including its account types.
+    ///
+    /// If the IDL holds the program's address, it's declared as the module's
+    /// `ID`.
+    fn try_from(ast: &NautilusCpi) -> syn::Result<Self> {
+        let idl = &ast.idl;
+        let idl_path = &ast.idl_path;
+        let mod_ident = parse_ident(&idl.name)?;
+        let declare_id = match &idl.metadata.address {
+            Some(address) => {
+                let id_bytes = Pubkey::from_str(address)
+                    .map_err(|_| {
+                        syn::Error::new(
+                            proc_macro2::Span::call_site(),
+                            format!(
+                                "The IDL's program address isn't a valid public key: {}",
+                                address
+                            ),
+                        )
+                    })?
+                    .to_bytes();
+                quote! {
+                    /// The program's ID.
+                    pub const ID: Pubkey = Pubkey::new_from_array([#(#id_bytes),*]);
+
+                    /// Returns the program's ID.
+                    pub fn id() -> Pubkey {
+                        ID
+                    }
+                }
+            }
+            None => quote!(),
+        };
+        let types = idl
+            .accounts
+            .iter()
+            .map(build_account_type)
+            .chain(
+                idl.types
+                    .iter()
+                    .map(|t| parse_rust_string::<TokenStream>(&t.to_rust_string())),
+            )
+            .collect::<syn::Result<Vec<TokenStream>>>()?;
+        let cpi_fns = idl
+            .instructions
+            .iter()
+            .map(|ix| build_cpi_fn(&idl.name, ix))
+            .collect::<syn::Result<Vec<TokenStream>>>()?;
+        let mod_doc = format!(
+            "Cross-Program Invocations (CPI) to the `{}` Nautilus program.",
+            idl.name
+        );
+        Ok(quote! {
+            #[doc = #mod_doc]
+            #[allow(clippy::too_many_arguments)]
+            pub mod #mod_ident {
+                #[allow(unused_imports)]
+                use nautilus::borsh::{self, BorshDeserialize, BorshSerialize};
+                use nautilus::solana_program::{
+                    entrypoint::ProgramResult,
+                    instruction::{AccountMeta, Instruction},
+                    program::invoke_signed,
+                    pubkey::Pubkey,
+                };
+                use nautilus::NautilusAccountInfo;
+
+                const _: &[u8] = include_bytes!(#idl_path);
+
+                #declare_id
+
+                #(#types)*
+
+                #(#cpi_fns)*
+            }
+        })
+    }
+}
+
+/// Builds the CPI function for a single instruction.
+///
+/// The function takes a Nautilus object for each of the instruction's accounts,
+/// as an `Option<..>` for optional accounts, followed by the instruction's
+/// arguments and the seeds of any PDA signing for the invocation. The
+/// instruction's data is its discriminant followed by its Borsh-serialized
+/// arguments.
+///
+/// Programs with a fixed address, such as the System Program, are filled in
+/// rather than taken as parameters. The token program and sysvars are taken as
+/// a `Box<AccountInfo<'a>>`, like the accounts of Nautilus's own CPI helpers.
+fn build_cpi_fn(program_name: &str, ix: &IdlInstruction) -> syn::Result<TokenStream> {
+    let fn_ident = parse_ident(&ix.name)?;
+    let doc = format!(
+        "Cross-Program Invocation (CPI) to the `{}` instruction of the `{}` program.",
+        ix.name, program_name
+    );
+    let account_idents = ix
+        .accounts
+        .iter()
+        .map(|a| parse_ident(&a.name))
+        .collect::<syn::Result<Vec<Ident>>>()?;
+    let account_params = ix
+        .accounts
+        .iter()
+        .zip(&account_idents)
+        .filter(|(a, _)| fixed_program_id(a).is_none())
+        .map(|(a, ident)| {
+            let ty = match is_raw_account(a) {
+                true => quote! { Box<nautilus::solana_program::account_info::AccountInfo<'a>> },
+                false => quote! { impl NautilusAccountInfo<'a> },
+            };
+            match a.is_optional {
+                true => quote! { #ident: Option<#ty> },
+                false => quote! { #ident: #ty },
+            }
+        });
+    let account_pushes = ix.accounts.iter().zip(&account_idents).map(|(a, ident)| {
+        let meta = match is_raw_account(a) {
+            true => account_meta(a, quote! { *account.key }),
+            false => account_meta(a, quote! { *account.key() }),
+        };
+        let account_info = match is_raw_account(a) {
+            true => quote! { *account.clone() },
+            false => quote! { *account.account_info() },
+        };
+        if let Some(program_id) = fixed_program_id(a) {
+            // Programs are executable, so their `AccountInfo` isn't required.
+            return quote! {
+                account_metas.push(AccountMeta::new_readonly(#program_id, false));
+            };
+        }
+        match a.is_optional {
+            // An omitted optional account is passed as the program ID.
+            true => quote! {
+                match &#ident {
+                    Some(account) => {
+                        account_metas.push(#meta);
+                        account_infos.push(#account_info);
+                    }
+                    None => account_metas.push(AccountMeta::new_readonly(*program_id, false)),
+                }
+            },
+            false => quote! {
+                {
+                    let account = &#ident;
+                    account_metas.push(#meta);
+                    account_infos.push(#account_info);
+                }
+            },
+        }
+    });
+    // Remaining accounts are appended after the instruction's accounts.
+    let (remaining_param, remaining_pushes) = match &ix.remaining_accounts {
+        Some(a) => {
+            let meta = account_meta(a, quote! { *account.key() });
+            (
+                quote! { remaining_accounts: &[impl NautilusAccountInfo<'a>], },
+                quote! {
+                    for account in remaining_accounts {
+                        account_metas.push(#meta);
+                        account_infos.push(*account.account_info());
+                    }
+                },
+            )
+        }
+        None => (quote!(), quote!()),
+    };
+    let arg_idents = ix
+        .args
+        .iter()
+        .map(|arg| parse_ident(&arg.name))
+        .collect::<syn::Result<Vec<Ident>>>()?;
+    let arg_types = ix
+        .args
+        .iter()
+        .map(|arg| parse_rust_string::<Type>(&arg.arg_type.to_rust_string()))
+        .collect::<syn::Result<Vec<Type>>>()?;
+    let discriminant = ix.discriminant.to_bytes();
+    let instruction_data = match ix.args.is_empty() {
+        true => quote! { let instruction_data = vec![#(#discriminant),*]; },
+        false => quote! {
+            let mut instruction_data = vec![#(#discriminant),*];
+            #(BorshSerialize::serialize(&#arg_idents, &mut instruction_data)?;)*
+        },
+    };
+    Ok(quote! {
+        #[doc = #doc]
+        pub fn #fn_ident<'a>(
+            program_id: &Pubkey,
+            #(#account_params,)*
+            #remaining_param
+            #(#arg_idents: #arg_types,)*
+            signer_seeds: &[&[&[u8]]],
+        ) -> ProgramResult {
+            #instruction_data
+            let mut account_metas = vec![];
+            let mut account_infos = vec![];
+            #(#account_pushes)*
+            #remaining_pushes
+            invoke_signed(
+                &Instruction {
+                    program_id: *program_id,
+                    accounts: account_metas,
+                    data: instruction_data,
+                },
+                &account_infos,
+                signer_seeds,
+            )
+        }
+    })
+}
+
+/// The ID of a program listed in an instruction, if its address is fixed -
+/// including the invoked program itself, listed as `program_id`.
+///
+/// Programs are executable, so their `AccountInfo` isn't required. The token
+/// program's address isn't fixed, since it can be either the Token Program or
+/// the Token-2022 Program, and sysvars aren't executable, so both are still
+/// taken as parameters.
+fn fixed_program_id(account: &IdlInstructionAccount) -> Option<TokenStream> {
+    match account.account_type.as_str() {
+        "account" if account.name == "program_id" => Some(quote! { *program_id }),
+        "systemProgram" => Some(quote! { nautilus::solana_program::system_program::ID }),
+        "associatedTokenProgram" => Some(quote! { nautilus::spl_associated_token_account::ID }),
+        "tokenMetadataProgram" => Some(quote! { nautilus::mpl_token_metadata::ID }),
+        _ => None,
+    }
+}
+
+/// Whether an account listed in an instruction is taken as a raw
+/// `AccountInfo`, since no Nautilus object stands for it.
+fn is_raw_account(account: &IdlInstructionAccount) -> bool {
+    matches!(
+        account.account_type.as_str(),
+        "tokenProgram" | "sysvar" | "program"
+    )
+}
+
+/// Builds the struct for one of the program's account types, which
+/// instructions may also take as arguments.
+///
+/// The program serializes these with their discriminator - and version, if
+/// versioned - ahead of their fields, so they're implemented by hand rather
+/// than derived.
+fn build_account_type(type_def: &IdlTypeDef) -> syn::Result<TokenStream> {
+    let (fields, config) = match (&type_def.idl_type, &type_def.config) {
+        (IdlTypeDefType::Struct { fields }, Some(config)) => (fields, config),
+        _ => return parse_rust_string(&type_def.to_rust_string()),
+    };
+    let ident = parse_rust_string::<Ident>(&type_def.name)?;
+    let name = &type_def.name;
+    let field_idents = fields
+        .iter()
+        .map(|f| parse_ident(&f.name))
+        .collect::<syn::Result<Vec<Ident>>>()?;
+    let field_types = fields
+        .iter()
+        .map(|f| parse_rust_string::<Type>(&f.field_data_type.to_rust_string()))
+        .collect::<syn::Result<Vec<Type>>>()?;
+    let discrim_str = account_discrim_str(type_def, config);
+    let discrim_mismatch = format!("Discriminator mismatch for account data: {}", name);
+    let (version_ser, version_deser) = match config.version {
+        Some(version) => {
+            let unsupported = format!("Unsupported {} version", name);
+            (
+                quote! { BorshSerialize::serialize(&#version, writer)?; },
+                quote! {
+                    if <u8 as BorshDeserialize>::deserialize(buf)? != #version {
+                        return Err(borsh::maybestd::io::Error::new(
+                            borsh::maybestd::io::ErrorKind::InvalidData,
+                            #unsupported,
+                        ));
+                    }
+                },
+            )
+        }
+        None => (quote!(), quote!()),
+    };
+    Ok(quote! {
+        #[derive(Clone, Debug)]
+        pub struct #ident {
+            #(pub #field_idents: #field_types,)*
+        }
+
+        impl BorshSerialize for #ident {
+            fn serialize<W: borsh::maybestd::io::Write>(
+                &self,
+                writer: &mut W,
+            ) -> borsh::maybestd::io::Result<()> {
+                BorshSerialize::serialize(&nautilus::discriminator(#discrim_str), writer)?;
+                #version_ser
+                #(BorshSerialize::serialize(&self.#field_idents, writer)?;)*
+                Ok(())
+            }
+        }
+
+        impl BorshDeserialize for #ident {
+            fn deserialize(buf: &mut &[u8]) -> borsh::maybestd::io::Result<Self> {
+                let discrim: [u8; 8] = BorshDeserialize::deserialize(buf)?;
+                if discrim != nautilus::discriminator(#discrim_str) {
+                    return Err(borsh::maybestd::io::Error::new(
+                        borsh::maybestd::io::ErrorKind::InvalidData,
+                        #discrim_mismatch,
+                    ));
+                }
+                #version_deser
+                Ok(Self {
+                    #(#field_idents: BorshDeserialize::deserialize(buf)?,)*
+                })
+            }
+        }
+    })
+}
+
+/// Builds the `AccountMeta` for an account listed in an instruction.
+fn account_meta(account: &IdlInstructionAccount, key: TokenStream) -> TokenStream {
+    let is_signer = account.is_signer;
+    match account.is_mut {
+        true => quote! { AccountMeta::new(#key, #is_signer) },
+        false => quote! { AccountMeta::new_readonly(#key, #is_signer) },
+    }
+}
+
+/// Converts an IDL name into a snake case Rust identifier.
+fn parse_ident(name: &str) -> syn::Result<Ident> {
+    parse_rust_string(&rust_ident(name))
+}
+
+/// Parses Rust code generated from the IDL.
+fn parse_rust_string<T: syn::parse::Parse>(rust_string: &str) -> syn::Result<T> {
+    syn::parse_str(rust_string).map_err(|e| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("Failed to parse the IDL of the program to invoke: {}", e),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{FnArg, Item, ItemFn, ItemMod};
+
+    use super::*;
+
+    /// Expands the CPI module of the `treasury` fixture IDL.
+    fn treasury_mod() -> ItemMod {
+        let cpi = NautilusCpi::from_path(&LitStr::new(
+            "../../tests/programs/wallets/idls/treasury.json",
+            proc_macro2::Span::call_site(),
+        ))
+        .unwrap();
+        syn::parse2(TokenStream::try_from(&cpi).unwrap()).unwrap()
+    }
+
+    fn items(item_mod: &ItemMod) -> &[Item] {
+        &item_mod.content.as_ref().unwrap().1
+    }
+
+    fn struct_names(item_mod: &ItemMod) -> Vec<String> {
+        items(item_mod)
+            .iter()
+            .filter_map(|i| match i {
+                Item::Struct(s) => Some(s.ident.to_string()),
+                Item::Enum(e) => Some(e.ident.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn cpi_fn<'a>(item_mod: &'a ItemMod, name: &str) -> &'a ItemFn {
+        items(item_mod)
+            .iter()
+            .find_map(|i| match i {
+                Item::Fn(f) if f.sig.ident == name => Some(f),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn param_names(item_fn: &ItemFn) -> Vec<String> {
+        item_fn
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|i| match i {
+                FnArg::Typed(p) => Some(p.pat.to_token_stream().to_string()),
+                FnArg::Receiver(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn declares_account_types_and_defined_types() {
+        let item_mod = treasury_mod();
+        assert_eq!(item_mod.ident, "treasury");
+        assert_eq!(struct_names(&item_mod), vec!["Vault", "Settings", "Tier"]);
+    }
+
+    #[test]
+    fn account_types_write_their_discriminator() {
+        let tokens = treasury_mod().to_token_stream().to_string();
+        assert!(tokens.contains("nautilus :: discriminator (\"vault\")"));
+        assert!(tokens.contains("nautilus :: discriminator (\"settings:versioned\")"));
+        assert!(tokens.contains("BorshSerialize :: serialize (& 2u8 , writer)"));
+    }
+
+    #[test]
+    fn fills_in_fixed_program_ids() {
+        let item_mod = treasury_mod();
+        assert_eq!(
+            param_names(cpi_fn(&item_mod, "open_vault")),
+            vec![
+                "program_id",
+                "vault",
+                "authority",
+                "index",
+                "rent",
+                "referrer",
+                "data",
+                "tier",
+                "signer_seeds",
+            ]
+        );
+        assert_eq!(
+            param_names(cpi_fn(&item_mod, "sweep")),
+            vec![
+                "program_id",
+                "authority",
+                "mint",
+                "token_program",
+                "remaining_accounts",
+                "settings",
+                "amount",
+                "signer_seeds",
+            ]
+        );
+    }
+
+    #[test]
+    fn takes_token_program_and_sysvars_as_account_infos() {
+        let item_mod = treasury_mod();
+        let param_type = |item_fn: &ItemFn, name: &str| {
+            item_fn
+                .sig
+                .inputs
+                .iter()
+                .find_map(|i| match i {
+                    FnArg::Typed(p) if p.pat.to_token_stream().to_string() == name => {
+                        Some(p.ty.to_token_stream().to_string())
+                    }
+                    _ => None,
+                })
+                .unwrap()
+        };
+        let account_info =
+            "Box < nautilus :: solana_program :: account_info :: AccountInfo < 'a > >";
+        assert_eq!(
+            param_type(cpi_fn(&item_mod, "open_vault"), "rent"),
+            account_info
+        );
+        assert_eq!(
+            param_type(cpi_fn(&item_mod, "sweep"), "token_program"),
+            account_info
+        );
+        assert_eq!(
+            param_type(cpi_fn(&item_mod, "open_vault"), "referrer"),
+            "Option < impl NautilusAccountInfo < 'a > >"
+        );
+    }
+}
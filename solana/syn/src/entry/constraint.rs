@@ -0,0 +1,465 @@
+//! Declarative constraints on the parameters of a Nautilus program's
+//! instructions.
+//!
+//! A parameter can declare `#[has_one(field)]`, `#[constraint(expr)]` or
+//! `#[address(KEY)]`, which are checked once every object has been loaded and
+//! before the user's function is called. A failed check returns
+//! `NautilusError::ConstraintViolated` with the parameter's name.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{punctuated::Punctuated, token::Comma, Attribute, Expr, FnArg, Ident, ItemFn, Pat};
+
+use crate::object::{
+    parser::NautilusObjectConfig, source::source_nautilus_names, NautilusObject, ObjectEntryConfig,
+};
+
+use super::entry_variant::CallContext;
+
+/// The attribute checking that a field of a parameter's data holds the address
+/// of another parameter.
+const HAS_ONE_ATTRIBUTE: &str = "has_one";
+/// The attribute checking that an expression holds before the instruction runs.
+const CONSTRAINT_ATTRIBUTE: &str = "constraint";
+/// The attribute checking that a parameter's account is at a given address.
+const ADDRESS_ATTRIBUTE: &str = "address";
+
+/// A constraint declared on one of an instruction's parameters.
+#[derive(Clone, Debug)]
+pub struct ParamConstraint {
+    /// The parameter the constraint is declared on.
+    pub param: Ident,
+    pub kind: ParamConstraintKind,
+}
+
+/// The possible constraints on a parameter.
+#[derive(Clone, Debug)]
+pub enum ParamConstraintKind {
+    /// `#[has_one(field)]`: the parameter's `field` holds the address of the
+    /// parameter of the same name - either a Nautilus object or a `Pubkey`
+    /// argument.
+    HasOne { field: Ident },
+    /// `#[constraint(expr)]`: the expression, which may use any of the
+    /// instruction's parameters, evaluates to `true`.
+    Constraint { expr: Expr },
+    /// `#[address(KEY)]`: the parameter's account is at the address `KEY`.
+    Address { key: Expr },
+}
+
+impl ParamConstraint {
+    /// Parses every constraint declared on the parameters of a declared
+    /// function.
+    pub fn parse_all(function: &ItemFn) -> syn::Result<Vec<Self>> {
+        let mut constraints = vec![];
+        for input in &function.sig.inputs {
+            if let FnArg::Typed(arg) = input {
+                let param = match &*arg.pat {
+                    Pat::Ident(pat_ident) => &pat_ident.ident,
+                    _ => continue,
+                };
+                for attr in arg
+                    .attrs
+                    .iter()
+                    .filter(|attr| is_constraint_attribute(attr))
+                {
+                    constraints.extend(parse_constraint_kinds(attr)?.into_iter().map(|kind| {
+                        Self {
+                            param: param.clone(),
+                            kind,
+                        }
+                    }));
+                }
+            }
+        }
+        Ok(constraints)
+    }
+
+    /// Checks that the constraint can be applied to its parameter, now that
+    /// each parameter's "call context" is known.
+    ///
+    /// `#[has_one(..)]` and `#[address(..)]` can only be declared on Nautilus
+    /// objects, and `#[has_one(..)]` only on records and accounts that already
+    /// exist, since it reads a field of their data. The target of a
+    /// `#[has_one(..)]` must be a parameter that's always provided.
+    pub fn check(&self, call_context: &[CallContext]) -> syn::Result<()> {
+        if let ParamConstraintKind::Constraint { .. } = self.kind {
+            return Ok(());
+        }
+        match (&self.kind, find_object(call_context, &self.param)) {
+            (_, None) => Err(syn::Error::new_spanned(
+                &self.param,
+                "`#[has_one(..)]` and `#[address(..)]` can only be declared on Nautilus objects.",
+            )),
+            (ParamConstraintKind::HasOne { .. }, Some((obj, _)))
+                if source_nautilus_names().contains(&obj.ident.to_string()) =>
+            {
+                Err(syn::Error::new_spanned(
+                    &self.param,
+                    format!(
+                        "`#[has_one(..)]` can only be declared on records and accounts, since `{}` has no data fields.",
+                        obj.ident
+                    ),
+                ))
+            }
+            (ParamConstraintKind::HasOne { .. }, Some((_, config))) if config.is_create => {
+                Err(syn::Error::new_spanned(
+                    &self.param,
+                    "`#[has_one(..)]` can't be declared on an object that's being created.",
+                ))
+            }
+            (ParamConstraintKind::HasOne { field }, Some(_)) => {
+                match find_param(call_context, field) {
+                    Some(CallContext::Arg(_)) => Ok(()),
+                    Some(CallContext::Nautilus(target)) => match &target.entry_config {
+                        Some(ObjectEntryConfig {
+                            is_optional: false,
+                            is_remaining: false,
+                            ..
+                        }) => Ok(()),
+                        _ => Err(syn::Error::new_spanned(
+                            field,
+                            "The target of `#[has_one(..)]` can't be an `Option<..>` or `Remaining<..>` parameter.",
+                        )),
+                    },
+                    None => Err(syn::Error::new_spanned(
+                        field,
+                        "`#[has_one(..)]` expects the name of another parameter of this instruction.",
+                    )),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Builds the check for this constraint, run in the processor once every
+    /// object has been loaded.
+    ///
+    /// The checks of an `Option<..>` parameter only run if it's provided, and
+    /// the checks of a `Remaining<..>` parameter run against each of its
+    /// objects. Within them - including a `#[constraint(..)]` expression - the
+    /// parameter's name refers to the provided object or to each object in
+    /// turn.
+    pub fn build_check(&self, call_context: &[CallContext]) -> syn::Result<TokenStream> {
+        let param = &self.param;
+        let param_name = param.to_string();
+        let violated = quote! {
+            return Err(nautilus::error::NautilusError::ConstraintViolated(
+                String::from(#param_name),
+            )
+            .into());
+        };
+        let object = find_object(call_context, param);
+        let check = match (&self.kind, object) {
+            (ParamConstraintKind::Constraint { expr }, _) => quote! {
+                if !(#expr) {
+                    #violated
+                }
+            },
+            (ParamConstraintKind::Address { key }, Some(_)) => quote! {
+                if #param.key().ne(&(#key)) {
+                    #violated
+                }
+            },
+            (ParamConstraintKind::HasOne { field }, Some((obj, config))) => {
+                let inner = match config.is_mut || config.is_signer {
+                    true => quote! { #param.self_account },
+                    false => quote! { #param },
+                };
+                let data = match &obj.object_config {
+                    Some(NautilusObjectConfig::AccountConfig {
+                        zero_copy: true, ..
+                    }) => quote! { #inner.try_borrow_data()? },
+                    _ => quote! { #inner.data },
+                };
+                let target = match find_param(call_context, field) {
+                    Some(CallContext::Nautilus(_)) => quote! { *#field.key() },
+                    _ => quote! { #field },
+                };
+                quote! {
+                    if #data.#field != #target {
+                        #violated
+                    }
+                }
+            }
+            (_, None) => return Err(syn::Error::new_spanned(
+                param,
+                "`#[has_one(..)]` and `#[address(..)]` can only be declared on Nautilus objects.",
+            )),
+        };
+        Ok(match object {
+            Some((_, config)) if config.is_remaining => quote! {
+                for #param in #param.iter() {
+                    #check
+                }
+            },
+            Some((_, config)) if config.is_optional => quote! {
+                if let Some(#param) = &#param {
+                    #check
+                }
+            },
+            _ => quote! {
+                {
+                    #check
+                }
+            },
+        })
+    }
+}
+
+/// Parses a single constraint attribute, which may declare more than one
+/// constraint, ie. `#[has_one(authority, mint)]`.
+fn parse_constraint_kinds(attr: &Attribute) -> syn::Result<Vec<ParamConstraintKind>> {
+    if attr.path.is_ident(HAS_ONE_ATTRIBUTE) {
+        let fields = attr
+            .parse_args_with(Punctuated::<Ident, Comma>::parse_terminated)
+            .map_err(|_| {
+                syn::Error::new_spanned(
+                    attr,
+                    "Expected one or more field names, such as `#[has_one(authority)]`.",
+                )
+            })?;
+        if fields.is_empty() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "Expected one or more field names, such as `#[has_one(authority)]`.",
+            ));
+        }
+        Ok(fields
+            .into_iter()
+            .map(|field| ParamConstraintKind::HasOne { field })
+            .collect())
+    } else if attr.path.is_ident(CONSTRAINT_ATTRIBUTE) {
+        Ok(vec![ParamConstraintKind::Constraint {
+            expr: attr.parse_args()?,
+        }])
+    } else {
+        Ok(vec![ParamConstraintKind::Address {
+            key: attr.parse_args()?,
+        }])
+    }
+}
+
+/// Whether an attribute on a parameter is one of the constraint attributes.
+fn is_constraint_attribute(attr: &Attribute) -> bool {
+    attr.path.is_ident(HAS_ONE_ATTRIBUTE)
+        || attr.path.is_ident(CONSTRAINT_ATTRIBUTE)
+        || attr.path.is_ident(ADDRESS_ATTRIBUTE)
+}
+
+/// Finds the "call context" of the parameter with the provided name.
+fn find_param<'c>(call_context: &'c [CallContext], ident: &Ident) -> Option<&'c CallContext> {
+    call_context.iter().find(|ctx| match ctx {
+        CallContext::Nautilus(obj) => obj
+            .entry_config
+            .as_ref()
+            .map_or(false, |config| &config.arg_ident == ident),
+        CallContext::Arg(arg) => arg == ident,
+    })
+}
+
+/// Finds the parameter with the provided name if it's a Nautilus object,
+/// along with its entrypoint configurations.
+fn find_object<'c>(
+    call_context: &'c [CallContext],
+    ident: &Ident,
+) -> Option<(&'c NautilusObject, &'c ObjectEntryConfig)> {
+    match find_param(call_context, ident) {
+        Some(CallContext::Nautilus(obj)) => obj.entry_config.as_ref().map(|config| (obj, config)),
+        _ => None,
+    }
+}
+
+/// Removes the constraint attributes from the parameters of a declared
+/// function, since they're only meaningful to the `#[nautilus]` macro.
+pub fn strip_constraints(function: &mut ItemFn) {
+    function.sig.inputs.iter_mut().for_each(|input| {
+        if let FnArg::Typed(arg) = input {
+            arg.attrs.retain(|attr| !is_constraint_attribute(attr));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+    use crate::{
+        entry::parser::parse_function,
+        object::{source::source_nautilus_objects, NautilusObjectType},
+    };
+
+    /// Parses and checks the constraints of a declared function against a
+    /// `Person` table with an `authority` field.
+    fn check_constraints(function: ItemFn) -> syn::Result<Vec<ParamConstraint>> {
+        let mut nautilus_objects = source_nautilus_objects()?;
+        nautilus_objects.push(NautilusObject::from_item_struct(
+            parse_quote! {
+                struct Person {
+                    #[primary_key]
+                    id: u32,
+                    #[authority]
+                    authority: Pubkey,
+                }
+            },
+            NautilusObjectType::Record,
+        )?);
+        let constraints = ParamConstraint::parse_all(&function)?;
+        let (_, _, _, call_context) = parse_function(&nautilus_objects, function)?;
+        for constraint in &constraints {
+            constraint.check(&call_context)?;
+        }
+        Ok(constraints)
+    }
+
+    fn check_err(function: ItemFn) -> String {
+        check_constraints(function).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parses_constraints() {
+        let constraints = check_constraints(parse_quote! {
+            fn update_person<'a>(
+                #[has_one(authority, mint)]
+                #[constraint(person.data.id > 0)]
+                person: Mut<Record<'a, Person>>,
+                authority: Signer<Wallet<'a>>,
+                mint: Pubkey,
+                #[address(solana_program::system_program::ID)]
+                treasury: Wallet<'a>,
+            ) -> ProgramResult {}
+        })
+        .unwrap();
+        let kinds: Vec<(String, &str)> = constraints
+            .iter()
+            .map(|c| {
+                let kind = match c.kind {
+                    ParamConstraintKind::HasOne { .. } => "has_one",
+                    ParamConstraintKind::Constraint { .. } => "constraint",
+                    ParamConstraintKind::Address { .. } => "address",
+                };
+                (c.param.to_string(), kind)
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (String::from("person"), "has_one"),
+                (String::from("person"), "has_one"),
+                (String::from("person"), "constraint"),
+                (String::from("treasury"), "address"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_constraints() {
+        let functions: [ItemFn; 3] = [
+            parse_quote! {
+                fn f<'a>(#[has_one] person: Record<'a, Person>) -> ProgramResult {}
+            },
+            parse_quote! {
+                fn f<'a>(#[has_one()] person: Record<'a, Person>) -> ProgramResult {}
+            },
+            parse_quote! {
+                fn f<'a>(#[has_one("authority")] person: Record<'a, Person>) -> ProgramResult {}
+            },
+        ];
+        for function in functions {
+            assert!(check_err(function).starts_with("Expected one or more field names"));
+        }
+        assert!(check_constraints(parse_quote! {
+            fn f<'a>(#[constraint] person: Record<'a, Person>) -> ProgramResult {}
+        })
+        .is_err());
+        assert!(check_constraints(parse_quote! {
+            fn f<'a>(#[address()] person: Record<'a, Person>) -> ProgramResult {}
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_has_one_and_address_on_args() {
+        assert!(check_err(parse_quote! {
+            fn f<'a>(#[has_one(authority)] authority: Pubkey) -> ProgramResult {}
+        })
+        .starts_with(
+            "`#[has_one(..)]` and `#[address(..)]` can only be declared on Nautilus objects."
+        ));
+        assert!(check_err(parse_quote! {
+            fn f<'a>(#[address(solana_program::system_program::ID)] key: Pubkey) -> ProgramResult {}
+        })
+        .starts_with(
+            "`#[has_one(..)]` and `#[address(..)]` can only be declared on Nautilus objects."
+        ));
+    }
+
+    #[test]
+    fn rejects_has_one_on_source_objects() {
+        for function in [
+            parse_quote! {
+                fn f<'a>(
+                    #[has_one(authority)] wallet: Wallet<'a>,
+                    authority: Signer<Wallet<'a>>,
+                ) -> ProgramResult {}
+            },
+            parse_quote! {
+                fn f<'a>(
+                    #[has_one(authority)] mint: Mut<Mint<'a>>,
+                    authority: Signer<Wallet<'a>>,
+                ) -> ProgramResult {}
+            },
+        ] {
+            assert!(check_err(function)
+                .starts_with("`#[has_one(..)]` can only be declared on records and accounts"));
+        }
+    }
+
+    #[test]
+    fn rejects_has_one_on_created_object() {
+        assert!(check_err(parse_quote! {
+            fn f<'a>(
+                #[has_one(authority)] person: Create<'a, Record<'a, Person>>,
+                authority: Signer<Wallet<'a>>,
+            ) -> ProgramResult {}
+        })
+        .starts_with("`#[has_one(..)]` can't be declared on an object that's being created."));
+    }
+
+    #[test]
+    fn rejects_has_one_with_missing_or_optional_target() {
+        assert!(check_err(parse_quote! {
+            fn f<'a>(#[has_one(authority)] person: Record<'a, Person>) -> ProgramResult {}
+        })
+        .starts_with("`#[has_one(..)]` expects the name of another parameter"));
+        assert!(check_err(parse_quote! {
+            fn f<'a>(
+                #[has_one(authority)] person: Record<'a, Person>,
+                authority: Option<Signer<Wallet<'a>>>,
+            ) -> ProgramResult {}
+        })
+        .starts_with(
+            "The target of `#[has_one(..)]` can't be an `Option<..>` or `Remaining<..>` parameter."
+        ));
+    }
+
+    #[test]
+    fn scopes_checks_to_optional_and_remaining_params() {
+        let function: ItemFn = parse_quote! {
+            fn f<'a>(
+                #[address(KEY)] wallet: Wallet<'a>,
+                #[address(KEY)] maybe: Option<Wallet<'a>>,
+                #[address(KEY)] rest: Remaining<'a, Wallet<'a>>,
+            ) -> ProgramResult {}
+        };
+        let constraints = check_constraints(function.clone()).unwrap();
+        let (_, _, _, call_context) =
+            parse_function(&source_nautilus_objects().unwrap(), function).unwrap();
+        let checks: Vec<String> = constraints
+            .iter()
+            .map(|c| c.build_check(&call_context).unwrap().to_string())
+            .collect();
+        assert!(checks[0].starts_with("{ if wallet . key ()"));
+        assert!(checks[1].starts_with("if let Some (maybe) = & maybe {"));
+        assert!(checks[2].starts_with("for rest in rest . iter () {"));
+    }
+}
@@ -0,0 +1,204 @@
+//! Instruction discriminators for a Nautilus program.
+//!
+//! By default an instruction's discriminant is the order in which its function
+//! is declared, which means reordering functions breaks existing clients.
+//! Programs can opt into hashed discriminators with
+//! `#[nautilus(discriminator = "hash")]`, and any function can pin its
+//! discriminant with `#[discriminant = N]`.
+use nautilus_idl::{idl_instruction::IdlInstructionDiscriminant, util::instruction_discriminator};
+use syn::{parse::Parse, Ident, ItemFn, Lit, LitStr, Meta, MetaNameValue, Token};
+
+/// The attribute used to pin an instruction's discriminant.
+const DISCRIMINANT_ATTRIBUTE: &str = "discriminant";
+
+/// How discriminants are assigned to instructions that don't pin one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiscriminatorScheme {
+    /// A single byte: the order in which the function is declared.
+    Index,
+    /// The first 8 bytes of a SHA-256 hash of the function's name.
+    Hash,
+}
+
+impl Default for DiscriminatorScheme {
+    fn default() -> Self {
+        Self::Index
+    }
+}
+
+impl Parse for DiscriminatorScheme {
+    /// Parses the arguments of the `#[nautilus(..)]` attribute, ie.
+    /// `discriminator = "index"` or `discriminator = "hash"`.
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(Self::default());
+        }
+        let key: Ident = input.parse()?;
+        if key != "discriminator" {
+            return Err(syn::Error::new(
+                key.span(),
+                "Unknown argument. Expected `discriminator = \"index\" | \"hash\"`.",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let value: LitStr = input.parse()?;
+        match value.value().as_str() {
+            "index" => Ok(Self::Index),
+            "hash" => Ok(Self::Hash),
+            _ => Err(syn::Error::new(
+                value.span(),
+                "Unknown discriminator scheme. Expected \"index\" or \"hash\".",
+            )),
+        }
+    }
+}
+
+/// An instruction's discriminant - the bytes its instruction data is
+/// prefixed with.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InstructionDiscriminant {
+    U8(u8),
+    Hashed([u8; 8]),
+}
+
+impl InstructionDiscriminant {
+    /// Determines the discriminant for a declared function, honoring any
+    /// `#[discriminant = N]` override.
+    pub fn new(scheme: DiscriminatorScheme, index: usize, function: &ItemFn) -> syn::Result<Self> {
+        if let Some(value) = parse_discriminant_override(function)? {
+            return Ok(Self::U8(value));
+        }
+        match scheme {
+            DiscriminatorScheme::Index => match u8::try_from(index) {
+                Ok(value) => Ok(Self::U8(value)),
+                Err(_) => Err(syn::Error::new_spanned(
+                    &function.sig.ident,
+                    "Too many instructions for index discriminators. Use `#[nautilus(discriminator = \"hash\")]`.",
+                )),
+            },
+            DiscriminatorScheme::Hash => Ok(Self::Hashed(instruction_discriminator(
+                &function.sig.ident.to_string(),
+            ))),
+        }
+    }
+}
+
+impl From<&InstructionDiscriminant> for IdlInstructionDiscriminant {
+    fn from(value: &InstructionDiscriminant) -> Self {
+        match value {
+            InstructionDiscriminant::U8(value) => IdlInstructionDiscriminant::new(*value),
+            InstructionDiscriminant::Hashed(value) => {
+                IdlInstructionDiscriminant::new_hashed(*value)
+            }
+        }
+    }
+}
+
+/// Parses the value of a function's `#[discriminant = N]` attribute, if any.
+fn parse_discriminant_override(function: &ItemFn) -> syn::Result<Option<u8>> {
+    match function
+        .attrs
+        .iter()
+        .find(|attr| attr.path.is_ident(DISCRIMINANT_ATTRIBUTE))
+    {
+        Some(attr) => match attr.parse_meta()? {
+            Meta::NameValue(MetaNameValue {
+                lit: Lit::Int(value),
+                ..
+            }) => Ok(Some(value.base10_parse()?)),
+            meta => Err(syn::Error::new_spanned(
+                meta,
+                "Expected `#[discriminant = N]`, where `N` is a u8.",
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Removes the `#[discriminant = N]` attribute from a declared function, since
+/// it's only meaningful to the `#[nautilus]` macro.
+pub fn strip_discriminant_override(function: &mut ItemFn) {
+    function
+        .attrs
+        .retain(|attr| !attr.path.is_ident(DISCRIMINANT_ATTRIBUTE));
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn parses_discriminator_scheme() {
+        let scheme: DiscriminatorScheme = syn::parse_str("").unwrap();
+        assert_eq!(scheme, DiscriminatorScheme::Index);
+        let scheme: DiscriminatorScheme = syn::parse_str("discriminator = \"hash\"").unwrap();
+        assert_eq!(scheme, DiscriminatorScheme::Hash);
+        assert!(syn::parse_str::<DiscriminatorScheme>("discriminator = \"sha\"").is_err());
+        assert!(syn::parse_str::<DiscriminatorScheme>("scheme = \"hash\"").is_err());
+    }
+
+    #[test]
+    fn assigns_discriminant_by_scheme() {
+        let function: ItemFn = parse_quote!(
+            fn create_person() {}
+        );
+        assert_eq!(
+            InstructionDiscriminant::new(DiscriminatorScheme::Index, 3, &function).unwrap(),
+            InstructionDiscriminant::U8(3)
+        );
+        assert_eq!(
+            InstructionDiscriminant::new(DiscriminatorScheme::Hash, 3, &function).unwrap(),
+            InstructionDiscriminant::Hashed(instruction_discriminator("create_person"))
+        );
+        assert!(InstructionDiscriminant::new(DiscriminatorScheme::Index, 256, &function).is_err());
+    }
+
+    #[test]
+    fn override_pins_discriminant() {
+        let function: ItemFn = parse_quote! {
+            #[discriminant = 7]
+            fn create_person() {}
+        };
+        for scheme in [DiscriminatorScheme::Index, DiscriminatorScheme::Hash] {
+            assert_eq!(
+                InstructionDiscriminant::new(scheme, 300, &function).unwrap(),
+                InstructionDiscriminant::U8(7)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_override() {
+        let functions: [ItemFn; 3] = [
+            parse_quote! {
+                #[discriminant(7)]
+                fn create_person() {}
+            },
+            parse_quote! {
+                #[discriminant = "7"]
+                fn create_person() {}
+            },
+            parse_quote! {
+                #[discriminant = 256]
+                fn create_person() {}
+            },
+        ];
+        for function in functions.iter() {
+            assert!(InstructionDiscriminant::new(DiscriminatorScheme::Index, 0, function).is_err());
+        }
+    }
+
+    #[test]
+    fn strips_override() {
+        let mut function: ItemFn = parse_quote! {
+            #[discriminant = 7]
+            #[inline]
+            fn create_person() {}
+        };
+        strip_discriminant_override(&mut function);
+        assert_eq!(function.attrs.len(), 1);
+        assert!(function.attrs[0].path.is_ident("inline"));
+    }
+}
@@ -6,7 +6,10 @@ use quote::quote;
 use syn::{Ident, ItemFn};
 
 use crate::{
-    entry::entry_variant::NautilusEntrypointEnumVariant, entry::parser::parse_function,
+    entry::constraint::ParamConstraint,
+    entry::discriminator::{DiscriminatorScheme, InstructionDiscriminant},
+    entry::entry_variant::NautilusEntrypointEnumVariant,
+    entry::parser::parse_function,
     object::NautilusObject,
 };
 
@@ -27,23 +30,102 @@ impl NautilusEntrypointEnum {
     /// This action will simply convert the user's declared functions into
     /// `NautilusEntrypointEnumVariant` instances, which dissolve to
     /// the required components for building out the generated program.
-    pub fn new(nautilus_objects: Vec<NautilusObject>, declared_functions: Vec<ItemFn>) -> Self {
-        let variants = declared_functions
+    ///
+    /// Each variant's discriminant is assigned according to the provided
+    /// `DiscriminatorScheme`, unless the function pins one with
+    /// `#[discriminant = N]`.
+    pub fn new(
+        nautilus_objects: Vec<NautilusObject>,
+        declared_functions: Vec<ItemFn>,
+        discriminator_scheme: DiscriminatorScheme,
+    ) -> syn::Result<Self> {
+        let variants: Vec<NautilusEntrypointEnumVariant> = declared_functions
             .into_iter()
             .enumerate()
             .map(|(i, f)| {
+                let discriminant = InstructionDiscriminant::new(discriminator_scheme, i, &f)?;
+                let constraints = ParamConstraint::parse_all(&f)?;
                 let (variant_ident, variant_args, call_ident, call_context) =
-                    parse_function(&nautilus_objects, f);
+                    parse_function(&nautilus_objects, f)?;
+                for constraint in &constraints {
+                    constraint.check(&call_context)?;
+                }
                 NautilusEntrypointEnumVariant::new(
-                    i.try_into().unwrap(),
+                    discriminant,
                     variant_ident,
                     variant_args,
                     call_ident,
                     call_context,
+                    constraints,
                 )
             })
-            .collect();
-        Self { variants }
+            .collect::<syn::Result<Vec<NautilusEntrypointEnumVariant>>>()?;
+        for (i, v) in variants.iter().enumerate() {
+            if let Some(other) = variants[..i]
+                .iter()
+                .find(|other| other.discriminant == v.discriminant)
+            {
+                return Err(syn::Error::new_spanned(
+                    &v.call_ident,
+                    format!(
+                        "Instructions `{}` and `{}` have the same discriminant: {:?}",
+                        other.call_ident, v.call_ident, v.discriminant
+                    ),
+                ));
+            }
+        }
+        Ok(Self { variants })
+    }
+
+    /// Builds the `unpack(..)` function of the instruction enum, which reads the
+    /// discriminant from the instruction data and deserializes the matching
+    /// variant's arguments from the rest of it.
+    ///
+    /// Hashed (8-byte) discriminants are matched first, falling back to
+    /// single-byte discriminants, so programs can pin legacy instructions with
+    /// `#[discriminant = N]` while using hashed discriminators for the rest.
+    fn build_unpack(&self) -> TokenStream {
+        let (hashed_arms, u8_arms) = self.variants.iter().fold(
+            (Vec::new(), Vec::new()),
+            |(mut hashed_arms, mut u8_arms), v| {
+                match v.discriminant {
+                    InstructionDiscriminant::Hashed(_) => hashed_arms.push(v.build_unpack_arm()),
+                    InstructionDiscriminant::U8(_) => u8_arms.push(v.build_unpack_arm()),
+                };
+                (hashed_arms, u8_arms)
+            },
+        );
+        let hashed_unpack = match hashed_arms.is_empty() {
+            true => quote!(),
+            false => quote! {
+                if input.len() >= 8 {
+                    let data = &mut &input[8..];
+                    match &input[..8] {
+                        #(#hashed_arms)*
+                        _ => (),
+                    }
+                }
+            },
+        };
+        let u8_unpack = match u8_arms.is_empty() {
+            true => quote! { Err(ProgramError::InvalidInstructionData) },
+            false => quote! {
+                let (discriminant, data) = input
+                    .split_first()
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let data = &mut &data[..];
+                match discriminant {
+                    #(#u8_arms)*
+                    _ => Err(ProgramError::InvalidInstructionData),
+                }
+            },
+        };
+        quote! {
+            pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+                #hashed_unpack
+                #u8_unpack
+            }
+        }
     }
 
     pub fn enum_ident() -> Ident {
@@ -51,12 +133,14 @@ impl NautilusEntrypointEnum {
     }
 }
 
-impl From<&NautilusEntrypointEnum> for (TokenStream, TokenStream, Vec<IdlInstruction>) {
+impl TryFrom<&NautilusEntrypointEnum> for (TokenStream, TokenStream, Vec<IdlInstruction>) {
+    type Error = syn::Error;
+
     /// Maps each `NautilusEntrypointEnumVariant` into the proper components and
     /// dissolves itself into the required components for building out the
     /// generated program.
     ///
-    /// Consider the `fold` operation on the `variants` field, which returns
+    /// Consider the loop over the `variants` field, which collects
     /// (`variants`, `match_arms`, `idl_instructions`):
     /// * `variants`: The variants of the instruction enum for the program.
     /// * `match_arms`: The match arms of the processor which will process
@@ -70,24 +154,26 @@ impl From<&NautilusEntrypointEnum> for (TokenStream, TokenStream, Vec<IdlInstruc
     /// * `TokenStream` (first): The instruction enum.
     /// * `TokenStream` (second): The processor.
     /// * `Vec<IdlInstruction>`: The list of IDL instructions.
-    fn from(value: &NautilusEntrypointEnum) -> Self {
+    fn try_from(value: &NautilusEntrypointEnum) -> syn::Result<Self> {
         let enum_name = NautilusEntrypointEnum::enum_ident();
-        let (variants, match_arms, idl_instructions) = value.variants.iter().fold(
-            (Vec::new(), Vec::new(), Vec::new()),
-            |(mut variants, mut match_arms, mut idl_instructions), v| {
-                let (a, b, c): (TokenStream, TokenStream, IdlInstruction) = v.into();
-                variants.push(a);
-                match_arms.push(b);
-                idl_instructions.push(c);
-                (variants, match_arms, idl_instructions)
-            },
-        );
-        (
+        let (mut variants, mut match_arms, mut idl_instructions) =
+            (Vec::new(), Vec::new(), Vec::new());
+        for v in value.variants.iter() {
+            let (a, b, c): (TokenStream, TokenStream, IdlInstruction) = v.try_into()?;
+            variants.push(a);
+            match_arms.push(b);
+            idl_instructions.push(c);
+        }
+        let unpack = value.build_unpack();
+        Ok((
             quote! {
-                #[derive(borsh::BorshDeserialize, borsh::BorshSerialize)]
                 pub enum #enum_name {
                     #(#variants)*
                 }
+
+                impl #enum_name {
+                    #unpack
+                }
             },
             quote! {
                 pub fn process_instruction<'a>(
@@ -95,7 +181,7 @@ impl From<&NautilusEntrypointEnum> for (TokenStream, TokenStream, Vec<IdlInstruc
                     accounts: &[AccountInfo],
                     input: &[u8],
                 ) -> ProgramResult {
-                    let instruction = #enum_name::try_from_slice(input)?;
+                    let instruction = #enum_name::unpack(input)?;
 
                     match instruction {
                         #(#match_arms)*
@@ -105,6 +191,72 @@ impl From<&NautilusEntrypointEnum> for (TokenStream, TokenStream, Vec<IdlInstruc
                 entrypoint!(process_instruction);
             },
             idl_instructions,
-        )
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn discriminants(
+        declared_functions: Vec<ItemFn>,
+        discriminator_scheme: DiscriminatorScheme,
+    ) -> syn::Result<Vec<InstructionDiscriminant>> {
+        NautilusEntrypointEnum::new(vec![], declared_functions, discriminator_scheme)
+            .map(|e| e.variants.into_iter().map(|v| v.discriminant).collect())
+    }
+
+    #[test]
+    fn assigns_discriminants_in_declared_order() {
+        let declared_functions = vec![
+            parse_quote!(
+                fn first() -> ProgramResult {}
+            ),
+            parse_quote!(
+                fn second() -> ProgramResult {}
+            ),
+        ];
+        assert_eq!(
+            discriminants(declared_functions, DiscriminatorScheme::Index).unwrap(),
+            vec![
+                InstructionDiscriminant::U8(0),
+                InstructionDiscriminant::U8(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_discriminants() {
+        let pinned_onto_index = vec![
+            parse_quote!(
+                fn first() -> ProgramResult {}
+            ),
+            parse_quote! {
+                #[discriminant = 0]
+                fn second() -> ProgramResult {}
+            },
+        ];
+        let pinned_twice = vec![
+            parse_quote! {
+                #[discriminant = 4]
+                fn first() -> ProgramResult {}
+            },
+            parse_quote! {
+                #[discriminant = 4]
+                fn second() -> ProgramResult {}
+            },
+        ];
+        for (declared_functions, discriminator_scheme) in [
+            (pinned_onto_index, DiscriminatorScheme::Index),
+            (pinned_twice, DiscriminatorScheme::Hash),
+        ] {
+            let err = discriminants(declared_functions, discriminator_scheme).unwrap_err();
+            assert!(err
+                .to_string()
+                .starts_with("Instructions `first` and `second` have the same discriminant"));
+        }
     }
 }
@@ -8,14 +8,19 @@ use syn::{Ident, Type};
 
 use crate::{
     entry::required_account::{to_ident_pointer, RequiredAccountSubtype},
-    object::{parser::NautilusObjectConfig, source::source_nautilus_names, NautilusObject},
+    object::{
+        parser::NautilusObjectConfig, source::source_nautilus_names, NautilusObject,
+        ObjectEntryConfig,
+    },
 };
 
 use super::{
+    constraint::ParamConstraint,
+    discriminator::InstructionDiscriminant,
     entry_enum::NautilusEntrypointEnum,
     required_account::{
-        metadata_ident, mint_authority_ident, self_account_ident, RequiredAccount,
-        RequiredAccountType,
+        metadata_ident, mint_authority_ident, self_account_ident, self_account_ident_pointer,
+        RequiredAccount, RequiredAccountType,
     },
 };
 
@@ -27,8 +32,9 @@ use super::{
 #[derive(Debug)]
 pub struct NautilusEntrypointEnumVariant {
     /// Instruction discriminant: derived from the order the functions are
-    /// declared.
-    pub discriminant: u8,
+    /// declared or a hash of the function's name, unless pinned with
+    /// `#[discriminant = N]`.
+    pub discriminant: InstructionDiscriminant,
     /// The identifier of this instruction's variant in the program instruction
     /// enum.
     pub variant_ident: Ident,
@@ -38,6 +44,10 @@ pub struct NautilusEntrypointEnumVariant {
     /// All required accounts for this instruction, in order to instantiate the
     /// declared Nautilus objects.
     pub required_accounts: Vec<RequiredAccount>,
+    /// The underlying account of the objects declared with `Remaining<..>`, if
+    /// any, which may be passed any number of times after the required
+    /// accounts.
+    pub remaining_account: Option<RequiredAccount>,
     /// The identifier of the user's declared function, in order to call it.
     pub call_ident: Ident,
     /// The "call context" of each declared parameter in the user's defined
@@ -47,6 +57,10 @@ pub struct NautilusEntrypointEnumVariant {
     /// for `CallContext`, but essentially it's information about whether or
     /// not the parameter is a Nautilus object or an instruction argument.
     pub call_context: Vec<CallContext>,
+    /// The constraints declared on the function's parameters with
+    /// `#[has_one(..)]`, `#[constraint(..)]` or `#[address(..)]`, checked
+    /// before the function is called.
+    pub constraints: Vec<ParamConstraint>,
 }
 
 /// "Call context" for each declared parameter in the user's defined function
@@ -68,38 +82,81 @@ impl NautilusEntrypointEnumVariant {
     /// This action will map each `CallContext::Nautilus(..)` for the parameters
     /// declared in the user's function to determine all required accounts
     /// for the instruction.
+    ///
+    /// The objects of a `Remaining<..>` are loaded from the accounts following
+    /// the required accounts, so their own accounts aren't required.
     pub fn new(
-        discriminant: u8,
+        discriminant: InstructionDiscriminant,
         variant_ident: Ident,
         variant_args: Vec<(Ident, Type)>,
         call_ident: Ident,
         call_context: Vec<CallContext>,
-    ) -> Self {
+        constraints: Vec<ParamConstraint>,
+    ) -> syn::Result<Self> {
+        let mut remaining_account = None;
         let required_accounts = RequiredAccount::condense(
             call_context
                 .iter()
                 .filter_map(|ctx| match ctx {
-                    CallContext::Nautilus(n) => {
-                        let req = n.get_required_accounts();
+                    CallContext::Nautilus(n) => Some(n.get_required_accounts().map(|mut req| {
+                        if let Some(ObjectEntryConfig {
+                            is_remaining: true, ..
+                        }) = &n.entry_config
+                        {
+                            remaining_account = req
+                                .0
+                                .iter()
+                                .find(|r| {
+                                    r.account_type
+                                        == RequiredAccountType::Account(
+                                            RequiredAccountSubtype::SelfAccount,
+                                        )
+                                })
+                                .cloned();
+                            req.0.retain(|r| {
+                                !matches!(r.account_type, RequiredAccountType::Account(_))
+                            });
+                        }
                         let mut accounts = vec![];
                         accounts.extend(req.0);
                         match req.1 {
                             Some(r) => accounts.extend(r),
                             None => (),
                         };
-                        Some(accounts)
-                    }
+                        accounts
+                    })),
                     CallContext::Arg(_) => None,
                 })
-                .collect(),
+                .collect::<syn::Result<Vec<Vec<RequiredAccount>>>>()?,
         );
-        Self {
+        Ok(Self {
             discriminant,
             variant_ident,
             variant_args,
             required_accounts,
+            remaining_account,
             call_ident,
             call_context,
+            constraints,
+        })
+    }
+
+    /// Builds the match arm of the instruction enum's `unpack(..)` function for
+    /// this particular declared function, which deserializes its arguments
+    /// from the instruction data following the discriminant.
+    pub fn build_unpack_arm(&self) -> TokenStream {
+        let variant_ident = &self.variant_ident;
+        let args = self
+            .variant_args
+            .iter()
+            .map(|_| quote! { BorshDeserialize::deserialize(data)? });
+        match &self.discriminant {
+            InstructionDiscriminant::U8(value) => quote! {
+                #value => Ok(Self::#variant_ident(#(#args,)*)),
+            },
+            InstructionDiscriminant::Hashed(bytes) => quote! {
+                [#(#bytes),*] => return Ok(Self::#variant_ident(#(#args,)*)),
+            },
         }
     }
 
@@ -112,7 +169,16 @@ impl NautilusEntrypointEnumVariant {
     /// entrypoint, ie. `accounts: &[AccountInfo]`, then use those
     /// accounts to create `Box` pointers and instantiate each declared Nautilus
     /// object, then call the user's function.
-    fn build_match_arm_logic(&self) -> TokenStream {
+    ///
+    /// An object declared as `Option<..>` is instantiated as `None` when the
+    /// program ID is passed in place of its underlying account.
+    ///
+    /// The objects of a `Remaining<..>` are instantiated from whichever accounts
+    /// are left in the iterator once the required accounts have been extracted.
+    ///
+    /// Any constraints declared on the function's parameters are checked once
+    /// every object has been instantiated.
+    fn build_match_arm_logic(&self) -> syn::Result<TokenStream> {
         let instruction_name = self.variant_ident.to_string();
         let mut index_init = quote!();
         // Maps all required accounts for this instruction into the proper tokens to
@@ -126,6 +192,7 @@ impl NautilusEntrypointEnumVariant {
                     RequiredAccountSubtype::SelfAccount => self_account_ident(&r.ident),
                     RequiredAccountSubtype::Metadata => metadata_ident(&r.ident),
                     RequiredAccountSubtype::MintAuthority => mint_authority_ident(&r.ident),
+                    RequiredAccountSubtype::Lookup => r.ident.clone(),
                 },
                 RequiredAccountType::IndexAccount => {
                     index_init = quote! { let nautilus_index = NautilusIndex::load(program_id, index_pointer)?; }; // TODO
@@ -147,12 +214,14 @@ impl NautilusEntrypointEnumVariant {
         // match arm will call the user's function with all of the instantiated
         // "call_args".
         {
-            self.call_context.iter().for_each(|ctx| {
+            for ctx in self.call_context.iter() {
                 match ctx {
                     CallContext::Nautilus(obj) => match &obj.entry_config {
                         Some(config) => {
                             let arg_ident = &config.arg_ident;
-                            let (obj_type, arg_ty, is_custom) = match source_nautilus_names().contains(&obj.ident.to_string()) {
+                            let (obj_type, arg_ty, is_custom) = match source_nautilus_names()
+                                .contains(&obj.ident.to_string())
+                            {
                                 true => (obj.ident.clone(), quote!(), false),
                                 false => {
                                     let ty = &obj.ident;
@@ -160,23 +229,53 @@ impl NautilusEntrypointEnumVariant {
                                         match &obj.object_config {
                                             Some(t) => match t {
                                                 NautilusObjectConfig::RecordConfig { .. } => Ident::new("Record", Span::call_site()),
+                                                NautilusObjectConfig::AccountConfig { zero_copy: true, .. } => Ident::new("ZeroCopyAccount", Span::call_site()),
                                                 NautilusObjectConfig::AccountConfig { .. } => Ident::new("Account", Span::call_site()),
                                             },
-                                            None => panic!("Object {} did not match any source Nautilus objects and was not annotated with a Nautilus #[derive(..)] macro", &obj.ident.to_string()),
+                                            None => return Err(syn::Error::new_spanned(
+                                                &obj.ident,
+                                                format!("Object {} did not match any source Nautilus objects and was not annotated with a Nautilus #[derive(..)] macro", &obj.ident),
+                                            )),
                                         },
                                         quote! { #ty },
                                         true,
                                     )
-                                },
+                                }
+                            };
+                            let required_accounts_for_obj = obj.get_required_accounts()?;
+                            let self_account_not_found = || {
+                                syn::Error::new_spanned(
+                                arg_ident,
+                                "Every Nautilus object has an underlying account, but none was found for this object.",
+                            )
                             };
-                            let required_accounts_for_obj = obj.get_required_accounts();
                             // Identifiers for all accounts required "for read" - in other words, any `Box<AccountInfo<'_>>` fields required
                             // for that Nautilus object.
-                            let read_call_idents = required_accounts_for_obj.0.iter().map(|r| {
+                            // Lookup accounts for a record's `#[index]` and `#[unique]` fields aren't passed to the initializer, but
+                            // are provided afterwards with `with_lookups(..)`.
+                            let (lookup_accounts, read_accounts): (
+                                Vec<&RequiredAccount>,
+                                Vec<&RequiredAccount>,
+                            ) = required_accounts_for_obj.0.iter().partition(|r| {
+                                r.account_type
+                                    == RequiredAccountType::Account(RequiredAccountSubtype::Lookup)
+                            });
+                            let read_call_idents = read_accounts.into_iter().map(|r| {
                                 let t: TokenStream = r.into();
                                 t
                             });
-                            match required_accounts_for_obj.1 {
+                            let with_lookups = match lookup_accounts.is_empty() {
+                                true => quote!(),
+                                false => {
+                                    let lookup_call_idents = lookup_accounts.into_iter().map(|r| {
+                                        let t: TokenStream = r.into();
+                                        t
+                                    });
+                                    quote! { .with_lookups(vec![#(#lookup_call_idents,)*]) }
+                                }
+                            };
+                            // The expression instantiating the object, and whether its binding is mutable.
+                            let (obj_init, is_mut_binding) = match required_accounts_for_obj.1 {
                                 // If the object is wrapped in `Create<'_, T>`, this option will have a value.
                                 // This means we need to get the identifiers for all accounts required "for create" as well.
                                 Some(accounts_for_create) => {
@@ -186,63 +285,131 @@ impl NautilusEntrypointEnumVariant {
                                     });
                                     let create_obj_init = match is_custom {
                                         true => quote! {
-                                            let mut #arg_ident = Create::new(
+                                            Create::new(
                                                 #(#create_call_idents,)*
-                                                #obj_type::< #arg_ty >::new(#(#read_call_idents,)*)
-                                            )?;
+                                                #obj_type::< #arg_ty >::new(#(#read_call_idents,)*)#with_lookups
+                                            )?
                                         },
                                         false => quote! {
-                                            let mut #arg_ident = Create::new(
+                                            Create::new(
                                                 #(#create_call_idents,)*
-                                                #obj_type::new(#(#read_call_idents,)*)
-                                            )?;
+                                                #obj_type::new(#(#read_call_idents,)*)#with_lookups
+                                            )?
                                         },
                                     };
-                                    object_inits.push(create_obj_init);
-                                },
+                                    (create_obj_init, true)
+                                }
                                 None => {
+                                    let obj_path = match is_custom {
+                                        true => quote! { #obj_type::< #arg_ty > },
+                                        false => quote! { #obj_type },
+                                    };
                                     if config.is_signer {
-                                        object_inits.push(
-                                            quote! { let #arg_ident = Signer::new(#obj_type::load(#(#read_call_idents,)*)?)?; },
-                                        );
+                                        (
+                                            quote! { Signer::new(#obj_path::load(#(#read_call_idents,)*)?#with_lookups)? },
+                                            false,
+                                        )
                                     } else if config.is_mut {
-                                        object_inits.push(
-                                            quote! { let #arg_ident = Mut::new(#obj_type::load(#(#read_call_idents,)*)?)?; },
-                                        );
+                                        (
+                                            quote! { Mut::new(#obj_path::load(#(#read_call_idents,)*)?#with_lookups)? },
+                                            false,
+                                        )
                                     } else {
-                                        object_inits.push(match is_custom {
-                                                true => quote! { let #arg_ident = #obj_type::< #arg_ty >::load(#(#read_call_idents,)*)?; },
-                                                false => quote! { let #arg_ident = #obj_type::load(#(#read_call_idents,)*)?; },
-                                            }
-                                        );
+                                        (quote! { #obj_path::load(#(#read_call_idents,)*)? }, false)
                                     }
-                                },
+                                }
                             };
+                            // An optional object is omitted when the program ID is passed in place of its account.
+                            let obj_init = match config.is_optional {
+                                true => {
+                                    let self_account_pointer = required_accounts_for_obj
+                                        .0
+                                        .iter()
+                                        .find(|r| {
+                                            r.account_type
+                                                == RequiredAccountType::Account(
+                                                    RequiredAccountSubtype::SelfAccount,
+                                                )
+                                        })
+                                        .map(|r| self_account_ident_pointer(&r.ident))
+                                        .ok_or_else(self_account_not_found)?;
+                                    quote! {
+                                        match #self_account_pointer.key.eq(program_id) {
+                                            true => None,
+                                            false => Some(#obj_init),
+                                        }
+                                    }
+                                }
+                                false => obj_init,
+                            };
+                            // The objects of a `Remaining<..>` are each loaded from the accounts left after the required accounts.
+                            if config.is_remaining {
+                                let self_account = required_accounts_for_obj
+                                    .0
+                                    .iter()
+                                    .find(|r| {
+                                        r.account_type
+                                            == RequiredAccountType::Account(
+                                                RequiredAccountSubtype::SelfAccount,
+                                            )
+                                    })
+                                    .map(|r| self_account_ident(&r.ident))
+                                    .ok_or_else(self_account_not_found)?;
+                                let self_account_pointer = to_ident_pointer(&self_account);
+                                object_inits.push(quote! {
+                                    let #arg_ident = Remaining::new(
+                                        accounts_iter
+                                            .map(|#self_account| -> Result<_, ProgramError> {
+                                                let #self_account_pointer = Box::new(#self_account.to_owned());
+                                                let #arg_ident = #obj_init;
+                                                Ok(#arg_ident)
+                                            })
+                                            .collect::<Result<Vec<_>, ProgramError>>()?,
+                                    );
+                                });
+                                call_args.push(quote! { #arg_ident });
+                                continue;
+                            }
+                            object_inits.push(match is_mut_binding {
+                                true => quote! { let mut #arg_ident = #obj_init; },
+                                false => quote! { let #arg_ident = #obj_init; },
+                            });
                             call_args.push(quote! { #arg_ident })
                         }
                         None => {
-                            panic!("Error processing entrypoint: `entry_config` not set.")
+                            return Err(syn::Error::new_spanned(
+                                &obj.ident,
+                                "Error processing entrypoint: `entry_config` not set.",
+                            ))
                         }
                     },
                     CallContext::Arg(arg) => call_args.push(quote! { #arg }),
                 };
-            });
+            }
         }
+        let constraint_checks = self
+            .constraints
+            .iter()
+            .map(|c| c.build_check(&self.call_context))
+            .collect::<syn::Result<Vec<TokenStream>>>()?;
         let call_ident = &self.call_ident;
-        quote::quote! {
+        Ok(quote::quote! {
             {
                 splogger::info!("Instruction: {}", #instruction_name);
                 let accounts_iter = &mut accounts.iter();
                 #(#all_accounts)*
                 #index_init
                 #(#object_inits)*
+                #(#constraint_checks)*
                 #call_ident(#(#call_args,)*)
             }
-        }
+        })
     }
 }
 
-impl From<&NautilusEntrypointEnumVariant> for (TokenStream, TokenStream, IdlInstruction) {
+impl TryFrom<&NautilusEntrypointEnumVariant> for (TokenStream, TokenStream, IdlInstruction) {
+    type Error = syn::Error;
+
     /// Dissolves the `NautilusEntrypointEnumVariant` into the proper components
     /// for building out the generated program.
     ///
@@ -259,16 +426,16 @@ impl From<&NautilusEntrypointEnumVariant> for (TokenStream, TokenStream, IdlInst
     ///   declared function.
     /// * `IdlInstruction`: The IDL instruction derived from this particular
     ///   declared function.
-    fn from(value: &NautilusEntrypointEnumVariant) -> Self {
+    fn try_from(value: &NautilusEntrypointEnumVariant) -> syn::Result<Self> {
         let variant_ident = &value.variant_ident;
         let enum_ident = NautilusEntrypointEnum::enum_ident();
         let (arg_names, arg_types): (Vec<Ident>, Vec<Type>) =
             value.variant_args.clone().into_iter().unzip();
-        let match_arm_logic = value.build_match_arm_logic();
-        (
+        let match_arm_logic = value.build_match_arm_logic()?;
+        Ok((
             quote! { #variant_ident(#(#arg_types,)*), },
             quote! { #enum_ident::#variant_ident(#(#arg_names,)*) => #match_arm_logic, },
-            value.into(),
-        )
+            value.try_into()?,
+        ))
     }
 }
@@ -8,6 +8,7 @@ use nautilus_idl::{
     },
     idl_nautilus_config::{
         IdlSeed, IdlTypeDefNautilusConfig, IdlTypeDefNautilusConfigDefaultInstruction,
+        IdlTypeDefNautilusConfigForeignKey, IdlTypeDefNautilusConfigLookup,
     },
     idl_type_def::IdlTypeDef,
 };
@@ -17,56 +18,74 @@ use crate::object::{
     NautilusObject, NautilusObjectRawType,
 };
 
-use super::{entry_variant::NautilusEntrypointEnumVariant, required_account::RequiredAccount};
+use super::{
+    entry_variant::NautilusEntrypointEnumVariant,
+    required_account::{RequiredAccount, RequiredAccountType, SysvarType},
+};
 
 /// Converts the `NautilusEntrypointEnumVariant` into an IDL instruction.
 ///
 /// This will use the configurations from the variant to build the necessary
 /// instruction in the IDL - including all required accounts.
-impl From<&NautilusEntrypointEnumVariant> for IdlInstruction {
-    fn from(value: &NautilusEntrypointEnumVariant) -> Self {
+impl TryFrom<&NautilusEntrypointEnumVariant> for IdlInstruction {
+    type Error = syn::Error;
+
+    fn try_from(value: &NautilusEntrypointEnumVariant) -> syn::Result<Self> {
         let mut name = value.variant_ident.to_string();
         name.replace_range(..1, &name[..1].to_lowercase());
-        IdlInstruction {
+        Ok(IdlInstruction {
             name,
             accounts: value.required_accounts.iter().map(|a| a.into()).collect(),
+            remaining_accounts: value.remaining_account.as_ref().map(|a| a.into()),
             args: value
                 .variant_args
                 .iter()
-                .map(|(ident, ty)| IdlInstructionArg::new(ident.to_string(), ty.into()))
-                .collect(),
-            discriminant: IdlInstructionDiscriminant::new(value.discriminant),
-        }
+                .map(|(ident, ty)| Ok(IdlInstructionArg::new(ident.to_string(), ty.try_into()?)))
+                .collect::<syn::Result<Vec<IdlInstructionArg>>>()?,
+            discriminant: IdlInstructionDiscriminant::from(&value.discriminant),
+        })
     }
 }
 
 /// Straightforward conversion from a `RequiredAccount` into its IDL
-/// representation, including configs for `is_mut` and `is_signer`.
+/// representation, including configs for `is_mut`, `is_signer` and
+/// `is_optional`.
+///
+/// Sysvars are listed with their fixed address.
 impl From<&RequiredAccount> for IdlInstructionAccount {
     fn from(value: &RequiredAccount) -> Self {
         Self {
             name: value.name.clone(),
             is_mut: value.is_mut,
             is_signer: value.is_signer,
+            is_optional: value.is_optional,
             account_type: value.account_type.to_string(),
             desc: value.desc.clone(),
+            address: match value.account_type {
+                RequiredAccountType::Sysvar => {
+                    SysvarType::from_name(&value.name).map(|s| s.address().to_string())
+                }
+                _ => None,
+            },
         }
     }
 }
 
 /// Straightforward conversion from a `NautilusObject` into its IDL type
 /// definition.
-impl From<&NautilusObject> for IdlTypeDef {
-    fn from(value: &NautilusObject) -> Self {
+impl TryFrom<&NautilusObject> for IdlTypeDef {
+    type Error = syn::Error;
+
+    fn try_from(value: &NautilusObject) -> syn::Result<Self> {
         let mut default_type_def: IdlTypeDef = match &value.raw_type {
-            NautilusObjectRawType::Struct(raw) => raw.into(),
-            NautilusObjectRawType::Enum(raw) => raw.into(),
+            NautilusObjectRawType::Struct(raw) => raw.try_into()?,
+            NautilusObjectRawType::Enum(raw) => raw.try_into()?,
         };
         match &value.object_config {
-            Some(config) => default_type_def.config = Some(config.into()),
+            Some(config) => default_type_def.config = Some(config.try_into()?),
             None => (),
         }
-        default_type_def
+        Ok(default_type_def)
     }
 }
 
@@ -76,52 +95,92 @@ impl From<&NautilusObject> for IdlTypeDef {
 /// These configurations are additional (and mostly optional) configs for the
 /// client to use to perform certain actions such as SQL queries and
 /// autoincrement.
-impl From<&NautilusObjectConfig> for IdlTypeDefNautilusConfig {
-    fn from(value: &NautilusObjectConfig) -> Self {
-        match value {
+impl TryFrom<&NautilusObjectConfig> for IdlTypeDefNautilusConfig {
+    type Error = syn::Error;
+
+    fn try_from(value: &NautilusObjectConfig) -> syn::Result<Self> {
+        Ok(match value {
             NautilusObjectConfig::RecordConfig {
                 table_name,
                 data_fields: _, // Unused in additional config.
                 autoincrement_enabled,
-                primary_key_ident,
-                primary_key_ty: _, // Unused, points to field name instead.
+                table_counter,
+                primary_keys,
                 authorities,
+                lookups,
+                foreign_keys,
                 default_instructions,
+                version,
+                space,
             } => Self {
                 discrminator_str: None,
                 table_name: Some(table_name.clone()),
-                primary_key: Some(primary_key_ident.to_string()),
+                primary_key: Some(primary_keys[0].0.to_string()),
+                primary_keys: primary_keys.iter().map(|(k, _)| k.to_string()).collect(),
                 autoincrement: Some(*autoincrement_enabled),
+                version: version.as_ref().map(|v| v.current),
+                table_counter: *table_counter,
+                zero_copy: false,
+                space: *space,
                 authorities: authorities.iter().map(|a| a.to_string()).collect(),
                 default_instructions: default_instructions
                     .iter()
                     .map(|s| s.clone().into())
                     .collect(),
                 seeds: vec![],
+                lookups: lookups
+                    .iter()
+                    .map(|l| IdlTypeDefNautilusConfigLookup {
+                        field: l.ident.to_string(),
+                        unique: l.is_unique,
+                    })
+                    .collect(),
+                foreign_keys: foreign_keys
+                    .iter()
+                    .map(|fk| IdlTypeDefNautilusConfigForeignKey {
+                        field: fk.ident.to_string(),
+                        table: fk.references.to_string().to_lowercase(),
+                    })
+                    .collect(),
             },
             NautilusObjectConfig::AccountConfig {
                 discrminator_str,
                 data_fields: _, // Unused in additional config.
                 authorities,
                 seeds,
+                version,
+                zero_copy,
+                space,
             } => Self {
                 discrminator_str: Some(discrminator_str.clone()),
                 table_name: None,
                 primary_key: None,
+                primary_keys: vec![],
                 autoincrement: None,
+                version: version.as_ref().map(|v| v.current),
+                table_counter: false,
+                zero_copy: *zero_copy,
+                space: *space,
                 authorities: authorities.iter().map(|a| a.to_string()).collect(),
                 default_instructions: vec![],
-                seeds: seeds.iter().map(|s| s.into()).collect(),
+                seeds: seeds
+                    .iter()
+                    .map(IdlSeed::try_from)
+                    .collect::<syn::Result<Vec<IdlSeed>>>()?,
+                lookups: vec![],
+                foreign_keys: vec![],
             },
-        }
+        })
     }
 }
 
 /// Converts a `Seed` from the `syn` crate into an `IdlSeed` from the `idl`
 /// crate.
-impl From<&Seed> for IdlSeed {
-    fn from(value: &Seed) -> Self {
-        match value {
+impl TryFrom<&Seed> for IdlSeed {
+    type Error = syn::Error;
+
+    fn try_from(value: &Seed) -> syn::Result<Self> {
+        Ok(match value {
             Seed::Lit { value } => IdlSeed::Lit {
                 value: value.clone(),
             },
@@ -130,9 +189,9 @@ impl From<&Seed> for IdlSeed {
             },
             Seed::Param { ident, ty } => IdlSeed::Param {
                 key: ident.to_string(),
-                value: ty.into(),
+                value: ty.try_into()?,
             },
-        }
+        })
     }
 }
 
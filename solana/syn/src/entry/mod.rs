@@ -1,4 +1,6 @@
 //! Builds the entrypoint, processor, and IDL for a Nautilus program.
+pub mod constraint;
+pub mod discriminator;
 pub mod entry_enum;
 pub mod entry_variant;
 pub mod idl;
@@ -6,7 +8,9 @@ pub mod parser;
 pub mod required_account;
 
 use nautilus_idl::{
-    converters::{py::PythonIdlWrite, ts::TypeScriptIdlWrite},
+    converters::{
+        anchor::AnchorIdlWrite, py::PythonIdlWrite, rust::RustIdlWrite, ts::TypeScriptIdlWrite,
+    },
     idl_metadata::IdlMetadata,
     Idl,
 };
@@ -14,7 +18,13 @@ use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::{parse::Parse, Item, ItemFn, ItemMod};
 
+use crate::object::{
+    default_instructions::build_default_instruction_fns, version::build_migrate_instruction_fns,
+};
+
 use self::{
+    constraint::strip_constraints,
+    discriminator::{strip_discriminant_override, DiscriminatorScheme},
     entry_enum::NautilusEntrypointEnum,
     parser::{is_use_super_star, parse_crate_context, parse_manifest},
 };
@@ -25,7 +35,9 @@ use self::{
 ///   functions.
 /// * `instruction_enum`: The built-out program instruction enum derived from
 ///   the functions and their arguments.
-/// * `declared_functions`: The user's declared functions as-is.
+/// * `declared_functions`: The user's declared functions as-is, plus any
+///   functions built from a table's `#[default_instructions(..)]` and the
+///   `migrate_<object>` function of any versioned object.
 /// * `processor`: The program's processor, built into a function
 ///   `process_instruction`.
 #[derive(Debug)]
@@ -36,7 +48,17 @@ pub struct NautilusEntrypoint {
     pub processor: TokenStream,
 }
 
-impl From<ItemMod> for NautilusEntrypoint {
+impl TryFrom<ItemMod> for NautilusEntrypoint {
+    type Error = syn::Error;
+
+    /// Converts the user's annotated module into the `NautilusEntrypoint`
+    /// struct, using the default (index) instruction discriminators.
+    fn try_from(value: ItemMod) -> syn::Result<Self> {
+        Self::new(value, DiscriminatorScheme::default())
+    }
+}
+
+impl NautilusEntrypoint {
     /// Converts the user's annotated module into the `NautilusEntrypoint`
     /// struct.
     ///
@@ -54,14 +76,21 @@ impl From<ItemMod> for NautilusEntrypoint {
     ///
     /// For more specific information see the documentation for
     /// `NautilusEntrypointEnum` and `NautilusEntrypointEnumVariant`.
-    fn from(value: ItemMod) -> Self {
+    ///
+    /// Any malformed input is returned as a `syn::Error` pointing at the
+    /// offending tokens.
+    pub fn new(value: ItemMod, discriminator_scheme: DiscriminatorScheme) -> syn::Result<Self> {
         let mut declared_functions = vec![];
 
-        let leftover_content: Vec<Item> = value
-            .content
-            .clone()
-            .unwrap()
-            .1
+        let content =
+            match value.content {
+                Some((_, content)) => content,
+                None => return Err(syn::Error::new_spanned(
+                    &value,
+                    "The `#[nautilus]` module must be declared inline, ie. `mod program { .. }`.",
+                )),
+            };
+        let leftover_content: Vec<Item> = content
             .into_iter()
             .filter_map(|item| match is_use_super_star(&item) {
                 true => None,
@@ -75,12 +104,25 @@ impl From<ItemMod> for NautilusEntrypoint {
             })
             .collect();
 
-        let (crate_version, crate_name) = parse_manifest();
-        let (nautilus_objects, idl_accounts, idl_types) = parse_crate_context();
+        let (crate_version, crate_name) = parse_manifest()?;
+        let (nautilus_objects, idl_accounts, idl_types, idl_errors) = parse_crate_context()?;
+
+        let default_instruction_fns =
+            build_default_instruction_fns(&nautilus_objects, &declared_functions)?;
+        declared_functions.extend(default_instruction_fns);
+        let migrate_fns = build_migrate_instruction_fns(&nautilus_objects, &declared_functions);
+        declared_functions.extend(migrate_fns);
 
-        let nautilus_enum =
-            &NautilusEntrypointEnum::new(nautilus_objects, declared_functions.clone());
-        let (instruction_enum, processor, idl_instructions) = nautilus_enum.into();
+        let nautilus_enum = &NautilusEntrypointEnum::new(
+            nautilus_objects,
+            declared_functions.clone(),
+            discriminator_scheme,
+        )?;
+        let (instruction_enum, processor, idl_instructions) = nautilus_enum.try_into()?;
+        declared_functions.iter_mut().for_each(|f| {
+            strip_discriminant_override(f);
+            strip_constraints(f);
+        });
 
         let idl = Idl::new(
             crate_version,
@@ -88,12 +130,17 @@ impl From<ItemMod> for NautilusEntrypoint {
             idl_instructions,
             idl_accounts,
             idl_types,
+            idl_errors,
             IdlMetadata::new_with_no_id(),
         );
         match idl.write_to_json("./target/idl") {
             Ok(()) => (),
             Err(e) => println!("[ERROR]: Error writing IDL to JSON file: {:#?}", e),
         };
+        match idl.write_to_anchor_json("./target/idl") {
+            Ok(()) => (),
+            Err(e) => println!("[ERROR]: Error writing Anchor IDL to JSON file: {:#?}", e),
+        };
         match idl.write_to_py("./target/idl") {
             Ok(()) => (),
             Err(e) => println!(
@@ -108,23 +155,27 @@ impl From<ItemMod> for NautilusEntrypoint {
                 e
             ),
         };
+        match idl.write_to_rs("./target/idl") {
+            Ok(()) => (),
+            Err(e) => println!("[ERROR]: Error writing Rust bindings to .rs file: {:#?}", e),
+        };
 
-        Self {
+        Ok(Self {
             leftover_content,
             instruction_enum,
             declared_functions,
             processor,
-        }
+        })
     }
 }
 
 impl Parse for NautilusEntrypoint {
     /// Parses the user's defined module into a `syn::ItemMod`, which is an
-    /// already pre-fabricated function, and calls Into<NautilusEntrypoint>
-    /// to fire the `from(value: ItemMod)` in the trait implementation `impl
-    /// From<ItemMod> for NautilusEntrypoint`, which does all the magic.
+    /// already pre-fabricated function, and calls TryInto<NautilusEntrypoint>
+    /// to fire the `try_from(value: ItemMod)` in the trait implementation `impl
+    /// TryFrom<ItemMod> for NautilusEntrypoint`, which does all the magic.
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        Ok(ItemMod::parse(input)?.into())
+        ItemMod::parse(input)?.try_into()
     }
 }
 
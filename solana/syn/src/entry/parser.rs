@@ -1,59 +1,83 @@
 //! Parses information about the user's entire crate.
 use cargo_toml::Manifest;
 use convert_case::{Case::Pascal, Casing};
-use nautilus_idl::idl_type_def::IdlTypeDef;
+use nautilus_idl::{idl_error::IdlErrorCode, idl_type_def::IdlTypeDef};
 use proc_macro2::Span;
 use quote::quote;
 use shank_macro_impl::krate::CrateContext;
-use syn::{FnArg, Ident, Item, ItemFn, Pat, PathArguments, Type, TypePath, UseTree};
+use syn::{Attribute, FnArg, Ident, Item, ItemFn, Pat, PathArguments, Type, TypePath, UseTree};
 use syn::{Meta, NestedMeta};
 
-use crate::object::source::source_nautilus_objects;
+use crate::error::NautilusCustomError;
+use crate::object::source::{source_nautilus_names, source_nautilus_objects};
 use crate::object::ObjectEntryConfig;
 use crate::object::{NautilusObject, NautilusObjectType};
 
-use super::entry_variant::CallContext;
+use super::{
+    entry_variant::CallContext,
+    required_account::{ObjectType, RequiredAccount, RequiredAccountType},
+};
 
 /// Parses metadata from the user's `Cargo.toml`
-pub fn parse_manifest() -> (String, String) {
-    let manifest = Manifest::from_path("Cargo.toml")
-        .expect("Failed to detect `Cargo.toml`. Is your Cargo.toml file structured properly ?");
-    let package = manifest
-        .package
-        .expect("Failed to parse `Cargo.toml`. Is your Cargo.toml file structured properly ?");
-    let crate_version = package
-        .version
-        .get()
-        .expect("Failed to parse crate version from `Cargo.toml`. Did you provide one ?");
-    (String::from(crate_version), package.name)
+pub fn parse_manifest() -> syn::Result<(String, String)> {
+    let manifest = Manifest::from_path("Cargo.toml").map_err(|_| {
+        call_site_error(
+            "Failed to detect `Cargo.toml`. Is your Cargo.toml file structured properly ?",
+        )
+    })?;
+    let package = manifest.package.ok_or_else(|| {
+        call_site_error(
+            "Failed to parse `Cargo.toml`. Is your Cargo.toml file structured properly ?",
+        )
+    })?;
+    let crate_version = package.version.get().map_err(|_| {
+        call_site_error("Failed to parse crate version from `Cargo.toml`. Did you provide one ?")
+    })?;
+    Ok((String::from(crate_version), package.name))
 }
 
+/// The parts of the user's crate used to build the program and its IDL,
+/// described on `parse_crate_context()`.
+type ParsedCrateContext = (
+    Vec<NautilusObject>,
+    Vec<IdlTypeDef>,
+    Vec<IdlTypeDef>,
+    Vec<IdlErrorCode>,
+);
+
 /// Uses Metaplex's `shank_macro_impl` to parse all of the contents of the
 /// user's crate.
 ///
-/// It uses this information to build the rest of the IDL (accounts and types),
-/// and return all defined Nautilus objects annotated with a Nautilus derive
-/// macro.
+/// It uses this information to build the rest of the IDL (accounts, types, and
+/// errors), and return all defined Nautilus objects annotated with a Nautilus
+/// derive macro.
 ///
 /// Consider the return type: (`Vec<NautilusObject>`, `Vec<IdlTypeDef>`,
-/// `Vec<IdlTypeDef>`):
+/// `Vec<IdlTypeDef>`, `Vec<IdlErrorCode>`):
 /// * `Vec<NautilusObject>`: All Nautilus objects defined in the crate using
 ///   Nautilus derive macros.
 /// * `Vec<IdlTypeDef>` (first): All accounts for the IDL (Nautilus objects).
 /// * `Vec<IdlTypeDef>` (second): All types for the IDL (non-Nautilus objects
 ///   defined in the crate).
-pub fn parse_crate_context() -> (Vec<NautilusObject>, Vec<IdlTypeDef>, Vec<IdlTypeDef>) {
-    let root = std::env::current_dir().unwrap().join("src/lib.rs");
-    let crate_context = CrateContext::parse(root).expect(
-        "Failed to detect `src/lib.rs`. Are you sure you've built your program with `--lib` ?",
-    );
+/// * `Vec<IdlErrorCode>`: All custom errors for the IDL (enums annotated with
+///   `#[derive(NautilusError)]`).
+pub fn parse_crate_context() -> syn::Result<ParsedCrateContext> {
+    let crate_context_error = || {
+        call_site_error(
+            "Failed to detect `src/lib.rs`. Are you sure you've built your program with `--lib` ?",
+        )
+    };
+    let root = std::env::current_dir()
+        .map_err(|_| crate_context_error())?
+        .join("src/lib.rs");
+    let crate_context = CrateContext::parse(root).map_err(|_| crate_context_error())?;
 
     let mut idl_accounts: Vec<IdlTypeDef> = vec![];
     let mut idl_types: Vec<IdlTypeDef> = vec![];
 
     let mut nautilus_objects: Vec<NautilusObject> = crate_context
         .structs()
-        .filter_map(|s| {
+        .filter_map(|s| -> Option<syn::Result<NautilusObject>> {
             if let Some(attr) = s.attrs.iter().find(|attr| attr.path.is_ident("derive")) {
                 if let Ok(Meta::List(meta_list)) = attr.parse_meta() {
                     let matched_macro =
@@ -74,26 +98,78 @@ pub fn parse_crate_context() -> (Vec<NautilusObject>, Vec<IdlTypeDef>, Vec<IdlTy
                             });
 
                     if let Some(nautilus_ty) = matched_macro {
-                        let nautilus_obj: NautilusObject =
-                            NautilusObject::from_item_struct(s.clone(), nautilus_ty);
-                        let i = &nautilus_obj;
-                        idl_accounts.push(i.into());
-                        return Some(nautilus_obj);
+                        return Some(
+                            NautilusObject::from_item_struct(s.clone(), nautilus_ty).and_then(
+                                |nautilus_obj| {
+                                    idl_accounts.push(IdlTypeDef::try_from(&nautilus_obj)?);
+                                    Ok(nautilus_obj)
+                                },
+                            ),
+                        );
                     }
                 }
             }
-            idl_types.push(s.into());
-            None
+            match IdlTypeDef::try_from(s) {
+                Ok(type_def) => {
+                    idl_types.push(type_def);
+                    None
+                }
+                Err(e) => Some(Err(e)),
+            }
         })
-        .collect();
+        .collect::<syn::Result<Vec<NautilusObject>>>()?;
 
-    nautilus_objects.extend(source_nautilus_objects());
+    nautilus_objects.extend(source_nautilus_objects()?);
 
-    crate_context.enums().for_each(|e| idl_types.push(e.into()));
+    let mut idl_errors: Vec<IdlErrorCode> = vec![];
+    for e in crate_context.enums() {
+        if derives(&e.attrs, "NautilusError") {
+            for variant in NautilusCustomError::from_item_enum(e)?.variants {
+                if let Some(existing) = idl_errors.iter().find(|err| err.code == variant.code) {
+                    return Err(syn::Error::new_spanned(
+                        &e.ident,
+                        format!(
+                            "`{}::{}` shares the code {} with `{}`. Give one of these enums a different `#[error_offset(..)]`.",
+                            e.ident, variant.ident, variant.code, existing.name
+                        ),
+                    ));
+                }
+                idl_errors.push(IdlErrorCode::new(
+                    variant.code,
+                    variant.ident.to_string(),
+                    variant.msg,
+                ));
+            }
+        } else {
+            idl_types.push(IdlTypeDef::try_from(e)?);
+        }
+    }
 
-    (nautilus_objects, idl_accounts, idl_types)
+    Ok((nautilus_objects, idl_accounts, idl_types, idl_errors))
 }
 
+/// Checks whether an item's `#[derive(..)]` attributes include the named
+/// derive macro.
+fn derives(attrs: &[Attribute], derive_macro: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("derive"))
+        .any(|attr| match attr.parse_meta() {
+            Ok(Meta::List(meta_list)) => meta_list.nested.iter().any(|nested_meta| {
+                matches!(
+                    nested_meta,
+                    NestedMeta::Meta(Meta::Path(path))
+                        if path.segments.last().map_or(false, |s| s.ident == derive_macro)
+                )
+            }),
+            _ => false,
+        })
+}
+
+/// The parts of a declared function used to build its entrypoint variant,
+/// described on `parse_function(..)`.
+type ParsedFunction = (Ident, Vec<(Ident, Type)>, Ident, Vec<CallContext>);
+
 /// Parses all required information from a user's defined function.
 ///
 /// All Nautilus objects - both from the source crate itself and the user's
@@ -116,13 +192,14 @@ pub fn parse_crate_context() -> (Vec<NautilusObject>, Vec<IdlTypeDef>, Vec<IdlTy
 pub fn parse_function(
     nautilus_objects: &Vec<NautilusObject>,
     function: ItemFn,
-) -> (Ident, Vec<(Ident, Type)>, Ident, Vec<CallContext>) {
+) -> syn::Result<ParsedFunction> {
     let variant_ident = Ident::new(
         &function.sig.ident.to_string().to_case(Pascal),
         Span::call_site(),
     );
     let call_ident = function.sig.ident.clone();
     let mut variant_args = vec![];
+    let mut has_remaining = false;
     let call_context = function
         .sig
         .inputs
@@ -130,7 +207,8 @@ pub fn parse_function(
         .map(|input| match input {
             FnArg::Typed(arg) => match *arg.pat {
                 Pat::Ident(ref pat_ident) => {
-                    let (type_string, is_create, is_signer, is_mut) = parse_type(&arg.ty);
+                    let (type_string, is_create, is_signer, is_mut, is_optional, is_remaining) =
+                        parse_type(&arg.ty)?;
                     for obj in nautilus_objects {
                         if obj.ident == &type_string {
                             let mut nautilus_obj = obj.clone();
@@ -139,23 +217,56 @@ pub fn parse_function(
                                 is_create,
                                 is_signer,
                                 is_mut,
+                                is_optional,
+                                is_remaining,
                             });
-                            return CallContext::Nautilus(nautilus_obj);
+                            check_sysvar(&nautilus_obj, &arg.ty)?;
+                            if is_remaining {
+                                check_remaining(&nautilus_obj, &arg.ty, &mut has_remaining)?;
+                            }
+                            return Ok(CallContext::Nautilus(nautilus_obj));
                         }
                     }
+                    if is_remaining {
+                        return Err(syn::Error::new_spanned(
+                            &arg.ty,
+                            "`Remaining<..>` expects a Nautilus object, ie. `Remaining<'a, Mut<Wallet<'a>>>`.",
+                        ));
+                    }
                     variant_args.push((pat_ident.ident.clone(), *arg.ty.clone()));
-                    return CallContext::Arg(pat_ident.ident.clone());
+                    Ok(CallContext::Arg(pat_ident.ident.clone()))
                 }
-                _ => panic!("Error parsing function."),
+                ref pat => Err(syn::Error::new_spanned(
+                    pat,
+                    "Expected an identifier. Patterns aren't supported in instruction parameters.",
+                )),
             },
-            _ => panic!("Error parsing function."),
+            FnArg::Receiver(receiver) => Err(syn::Error::new_spanned(
+                receiver,
+                "Instructions can't take a `self` parameter.",
+            )),
         })
-        .collect();
-    (variant_ident, variant_args, call_ident, call_context)
+        .collect::<syn::Result<Vec<CallContext>>>()?;
+    Ok((variant_ident, variant_args, call_ident, call_context))
 }
 
 /// Parses the type of a parameter of a user's defined function signature.
-pub fn parse_type(ty: &Type) -> (String, bool, bool, bool) {
+///
+/// A type wrapped in `Option<..>` is parsed as its inner type, and flagged as
+/// optional. If the inner type isn't a Nautilus object, the parameter is
+/// simply an optional instruction argument.
+///
+/// Likewise, the objects of a `Remaining<'_, T>` are parsed as `T`, and flagged
+/// as the instruction's remaining accounts.
+pub fn parse_type(ty: &Type) -> syn::Result<(String, bool, bool, bool, bool, bool)> {
+    if let Some(inner_ty) = derive_wrapped_type(ty, "Option") {
+        let (type_name, is_create, is_signer, is_mut, _, is_remaining) = parse_type(inner_ty)?;
+        return Ok((type_name, is_create, is_signer, is_mut, true, is_remaining));
+    }
+    if let Some(inner_ty) = derive_wrapped_type(ty, "Remaining") {
+        let (type_name, is_create, is_signer, is_mut, is_optional, _) = parse_type(inner_ty)?;
+        return Ok((type_name, is_create, is_signer, is_mut, is_optional, true));
+    }
     let mut is_create = false;
     let mut is_signer = false;
     let mut is_mut = false;
@@ -174,7 +285,10 @@ pub fn parse_type(ty: &Type) -> (String, bool, bool, bool) {
             } else if segment.ident == "Mut" {
                 is_mut = true;
                 (child_type, is_pda) = derive_child_type(&segment.arguments)
-            } else if segment.ident == "Record" || segment.ident == "Account" {
+            } else if segment.ident == "Record"
+                || segment.ident == "Account"
+                || segment.ident == "ZeroCopyAccount"
+            {
                 is_pda = true;
                 (child_type, _) = derive_child_type(&segment.arguments)
             }
@@ -185,17 +299,37 @@ pub fn parse_type(ty: &Type) -> (String, bool, bool, bool) {
         is_signer = false;
     }
     let type_name = if is_create || is_signer || is_mut || is_pda {
-        if let Some(t) = &child_type {
-            format!("{}", quote! { #t })
-        } else {
-            panic!("Could not parse provided type: {:#?}", ty);
+        match &child_type {
+            Some(t) => format!("{}", quote! { #t }),
+            None => return Err(syn::Error::new_spanned(
+                ty,
+                "Could not parse provided type. Expected a Nautilus object, ie. `Mut<Wallet<'a>>`.",
+            )),
         }
     } else {
         let mut new_t = ty.clone();
         remove_lifetimes_from_type(&mut new_t);
         format!("{}", quote! { #new_t })
     };
-    (type_name, is_create, is_signer, is_mut)
+    Ok((type_name, is_create, is_signer, is_mut, false, false))
+}
+
+/// Returns the inner type of a wrapper type with the provided name, ie: `T` in
+/// `Option<T>`.
+fn derive_wrapped_type<'t>(ty: &'t Type, wrapper: &str) -> Option<&'t Type> {
+    if let Type::Path(TypePath { path, .. }) = ty {
+        if let Some(segment) = path.segments.last() {
+            if segment.ident == wrapper {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    return args.args.iter().find_map(|arg| match arg {
+                        syn::GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    });
+                }
+            }
+        }
+    }
+    None
 }
 
 /// Derives the child type of a compound object with angle-bracket generic
@@ -208,7 +342,10 @@ fn derive_child_type(arguments: &PathArguments) -> (Option<Type>, bool) {
                 remove_lifetimes_from_type(&mut new_ty);
                 if let Type::Path(TypePath { path, .. }) = &new_ty {
                     if let Some(segment) = path.segments.first() {
-                        if segment.ident == "Record" || segment.ident == "Account" {
+                        if segment.ident == "Record"
+                            || segment.ident == "Account"
+                            || segment.ident == "ZeroCopyAccount"
+                        {
                             return derive_child_type(&segment.arguments);
                         }
                     }
@@ -256,6 +393,81 @@ fn remove_lifetimes_from_type(t: &mut Type) {
     }
 }
 
+/// Checks that a Nautilus object can be loaded from each of an instruction's
+/// remaining accounts.
+///
+/// Each remaining account is loaded as the object's underlying account, so the
+/// object can't require any other accounts of its own - such as a token's
+/// metadata or a record's lookups - and can't be created. An instruction can
+/// only declare one `Remaining<..>`.
+fn check_remaining(obj: &NautilusObject, ty: &Type, has_remaining: &mut bool) -> syn::Result<()> {
+    if *has_remaining {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "An instruction can only declare one `Remaining<..>`.",
+        ));
+    }
+    *has_remaining = true;
+    let (read_accounts, create_accounts) = obj.get_required_accounts()?;
+    if create_accounts.is_some() {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "`Remaining<..>` can't create accounts. Remove `Create<..>`.",
+        ));
+    }
+    if matches!(
+        &obj.entry_config,
+        Some(ObjectEntryConfig {
+            is_optional: true,
+            ..
+        })
+    ) {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "`Remaining<..>` can't be optional, since it may already hold no accounts.",
+        ));
+    }
+    if read_accounts
+        .iter()
+        .filter(|r| matches!(r.account_type, RequiredAccountType::Account(_)))
+        .count()
+        > 1
+    {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "`Remaining<..>` only supports objects with a single account of their own, such as a `Wallet`, `Mint`, `Account` or a `Record` without lookups.",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that a sysvar object isn't wrapped in `Create<..>`, `Signer<..>`,
+/// `Mut<..>`, `Option<..>` or `Remaining<..>`, since sysvars are read-only and
+/// always available.
+fn check_sysvar(obj: &NautilusObject, ty: &Type) -> syn::Result<()> {
+    let config = match &obj.entry_config {
+        Some(config) if source_nautilus_names().contains(&obj.ident.to_string()) => config,
+        _ => return Ok(()),
+    };
+    let is_sysvar = matches!(
+        RequiredAccount::derive_object_type(&obj.ident, false, None)?,
+        ObjectType::Sysvar(_)
+    );
+    if is_sysvar
+        && (config.is_create
+            || config.is_signer
+            || config.is_mut
+            || config.is_optional
+            || config.is_remaining)
+    {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "Sysvars are read-only and always available, so they can't be wrapped in `Create<..>`, `Signer<..>`, `Mut<..>`, `Option<..>` or `Remaining<..>`.",
+        ));
+    }
+    Ok(())
+}
+
 /// Is the item `use super::*;`
 pub fn is_use_super_star(item: &Item) -> bool {
     if let Item::Use(use_item) = item {
@@ -268,6 +480,12 @@ pub fn is_use_super_star(item: &Item) -> bool {
     false
 }
 
+/// Builds a `syn::Error` for failures that can't be attributed to any token in
+/// the annotated module, such as a missing `Cargo.toml`.
+fn call_site_error(message: &str) -> syn::Error {
+    syn::Error::new(Span::call_site(), message)
+}
+
 pub fn type_to_string(ty: &syn::Type) -> Option<String> {
     if let syn::Type::Path(type_path) = ty {
         if let Some(segment) = type_path.path.segments.last() {
@@ -18,6 +18,10 @@ pub struct RequiredAccount {
     pub is_signer: bool,
     pub desc: String,
     pub account_type: RequiredAccountType,
+    /// Whether the account can be omitted, by passing the program ID in its
+    /// place. Only the accounts of an object declared as `Option<..>` are
+    /// optional.
+    pub is_optional: bool,
 }
 
 /// The type of account required.
@@ -25,6 +29,7 @@ pub struct RequiredAccount {
 pub enum RequiredAccountType {
     ProgramId,
     IndexAccount,
+    CounterAccount(String),
     Account(RequiredAccountSubtype), // Any general account not matching the other variants
     FeePayer,
     Sysvar,
@@ -44,6 +49,7 @@ pub enum RequiredAccountSubtype {
     SelfAccount, // The underlying account for any Nautilus object
     Metadata,
     MintAuthority,
+    Lookup, // A lookup account for a record's `#[index]` or `#[unique]` field
 }
 
 /// The type of Nautilus object
@@ -56,6 +62,7 @@ pub enum ObjectType {
     Mint(bool),
     Metadata,
     AssociatedTokenAccount,
+    Sysvar(SysvarType),
     Record(bool, Vec<Construct>),  // Table record
     Account(bool, Vec<Construct>), // State account
 }
@@ -74,9 +81,11 @@ pub enum ObjectType {
 pub enum Construct {
     ProgramId,
     Index(bool),
+    Counter(String, bool),
     SelfAccount(String, String, bool, bool),
     Metadata(String, String, bool),
     MintAuthority(String, String, bool, bool),
+    Lookup(String, String, bool),
     FeePayer,
     Sysvar(SysvarType),
     SystemProgram,
@@ -89,7 +98,52 @@ pub enum Construct {
 pub enum SysvarType {
     Clock,
     EpochSchedule,
+    Instructions,
     Rent,
+    SlotHashes,
+}
+
+impl SysvarType {
+    /// The sysvar's name, as its account is named in the IDL.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SysvarType::Clock => "clock",
+            SysvarType::EpochSchedule => "epochSchedule",
+            SysvarType::Instructions => "instructions",
+            SysvarType::Rent => "rent",
+            SysvarType::SlotHashes => "slotHashes",
+        }
+    }
+
+    /// The sysvar's fixed address.
+    pub fn address(&self) -> &'static str {
+        match self {
+            SysvarType::Clock => "SysvarC1ock11111111111111111111111111111111",
+            SysvarType::EpochSchedule => "SysvarEpochSchedu1e111111111111111111111111",
+            SysvarType::Instructions => "Sysvar1nstructions1111111111111111111111111",
+            SysvarType::Rent => "SysvarRent111111111111111111111111111111111",
+            SysvarType::SlotHashes => "SysvarS1otHashes111111111111111111111111111",
+        }
+    }
+
+    /// Finds a sysvar by the name of its account.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            SysvarType::Clock,
+            SysvarType::EpochSchedule,
+            SysvarType::Instructions,
+            SysvarType::Rent,
+            SysvarType::SlotHashes,
+        ]
+        .into_iter()
+        .find(|sysvar_type| sysvar_type.name() == name)
+    }
+
+    /// Whether the sysvar can be read with `Sysvar::get()`, in which case its
+    /// object doesn't require its account.
+    pub fn has_getter(&self) -> bool {
+        !matches!(self, SysvarType::Instructions | SysvarType::SlotHashes)
+    }
 }
 
 impl From<Construct> for RequiredAccount {
@@ -105,6 +159,7 @@ impl From<Construct> for RequiredAccount {
                     is_mut: false,
                     is_signer: false,
                     desc: name,
+                    is_optional: false,
                     account_type: RequiredAccountType::ProgramId,
                 }
             }
@@ -116,9 +171,22 @@ impl From<Construct> for RequiredAccount {
                     is_mut,
                     is_signer: false,
                     desc: "The Nautilus Index for this program".to_string(),
+                    is_optional: false,
                     account_type: RequiredAccountType::IndexAccount,
                 }
             }
+            Construct::Counter(table_name, is_mut) => {
+                let name = format!("{}_counter", table_name);
+                RequiredAccount {
+                    ident: name_to_ident_snake(&name),
+                    name,
+                    is_mut,
+                    is_signer: false,
+                    desc: format!("The record counter for table: {}", table_name),
+                    is_optional: false,
+                    account_type: RequiredAccountType::CounterAccount(table_name),
+                }
+            }
             Construct::SelfAccount(name, desc, is_mut, is_signer) => {
                 let ident = name_to_ident_snake(&name);
                 RequiredAccount {
@@ -127,6 +195,7 @@ impl From<Construct> for RequiredAccount {
                     is_mut,
                     is_signer,
                     desc,
+                    is_optional: false,
                     account_type: RequiredAccountType::Account(RequiredAccountSubtype::SelfAccount),
                 }
             }
@@ -138,6 +207,7 @@ impl From<Construct> for RequiredAccount {
                     is_mut,
                     is_signer: false,
                     desc,
+                    is_optional: false,
                     account_type: RequiredAccountType::Account(RequiredAccountSubtype::Metadata),
                 }
             }
@@ -149,11 +219,24 @@ impl From<Construct> for RequiredAccount {
                     is_mut,
                     is_signer,
                     desc,
+                    is_optional: false,
                     account_type: RequiredAccountType::Account(
                         RequiredAccountSubtype::MintAuthority,
                     ),
                 }
             }
+            Construct::Lookup(name, desc, is_mut) => {
+                let ident = name_to_ident_snake(&name);
+                RequiredAccount {
+                    ident,
+                    name,
+                    is_mut,
+                    is_signer: false,
+                    desc,
+                    is_optional: false,
+                    account_type: RequiredAccountType::Account(RequiredAccountSubtype::Lookup),
+                }
+            }
             Construct::FeePayer => {
                 let account_type = RequiredAccountType::FeePayer;
                 let name = account_type.to_string();
@@ -163,21 +246,19 @@ impl From<Construct> for RequiredAccount {
                     is_mut: true,
                     is_signer: true,
                     desc: "The transaction fee payer".to_string(),
+                    is_optional: false,
                     account_type,
                 }
             }
             Construct::Sysvar(sysvar_type) => {
-                let name = match sysvar_type {
-                    SysvarType::Clock => "clock".to_string(),
-                    SysvarType::EpochSchedule => "epochSchedule".to_string(),
-                    SysvarType::Rent => "rent".to_string(),
-                };
+                let name = sysvar_type.name().to_string();
                 RequiredAccount {
                     ident: name_to_ident_snake(&name),
                     name: name.clone(),
                     is_mut: false,
                     is_signer: false,
                     desc: format!("The Sysvar: {}", &(name.to_case(Case::Title))).to_string(),
+                    is_optional: false,
                     account_type: RequiredAccountType::Sysvar,
                 }
             }
@@ -190,6 +271,7 @@ impl From<Construct> for RequiredAccount {
                     is_mut: false,
                     is_signer: false,
                     desc: "The System Program".to_string(),
+                    is_optional: false,
                     account_type,
                 }
             }
@@ -202,6 +284,7 @@ impl From<Construct> for RequiredAccount {
                     is_mut: false,
                     is_signer: false,
                     desc: "The Token Program".to_string(),
+                    is_optional: false,
                     account_type,
                 }
             }
@@ -214,6 +297,7 @@ impl From<Construct> for RequiredAccount {
                     is_mut: false,
                     is_signer: false,
                     desc: "The Associated Token Program".to_string(),
+                    is_optional: false,
                     account_type,
                 }
             }
@@ -226,6 +310,7 @@ impl From<Construct> for RequiredAccount {
                     is_mut: false,
                     is_signer: false,
                     desc: "The Token Metadata Program".to_string(),
+                    is_optional: false,
                     account_type,
                 }
             }
@@ -236,34 +321,57 @@ impl From<Construct> for RequiredAccount {
 // TODO: Add support for custom descriptions
 //
 impl RequiredAccount {
+    /// Derives the `ObjectType` of a Nautilus object from its type's name, or
+    /// from its `#[derive(..)]` macro for the user's own types.
+    ///
+    /// Returns a `syn::Error` pointing at the type if it's neither a source
+    /// Nautilus object nor annotated with a Nautilus `#[derive(..)]` macro.
     pub fn derive_object_type(
-        ty_name: &str,
+        ty_ident: &Ident,
         is_mut: bool,
         nautilus_ty: Option<NautilusObjectType>,
-    ) -> ObjectType {
-        if ty_name.eq("NautilusIndex") {
+    ) -> syn::Result<ObjectType> {
+        let ty_name = ty_ident.to_string();
+        let object_type = if ty_name.eq("NautilusIndex") {
             ObjectType::NautilusIndex
         } else if ty_name.eq("Wallet") {
             ObjectType::Wallet
         } else if ty_name.eq("Nft") {
             ObjectType::Nft(false) // TODO: PDA Tokens not supported yet
-        } else if ty_name.eq("Token") {
+        } else if ty_name.eq("Token") || ty_name.eq("Token2022") {
             ObjectType::Token(false) // TODO: PDA Tokens not supported yet
-        } else if ty_name.eq("Mint") {
+        } else if ty_name.eq("Mint") || ty_name.eq("Mint2022") {
             ObjectType::Mint(false) // TODO: PDA Tokens not supported yet
         } else if ty_name.eq("Metadata") {
             ObjectType::Metadata
-        } else if ty_name.eq("AssociatedTokenAccount") {
+        } else if ty_name.eq("AssociatedTokenAccount") || ty_name.eq("AssociatedTokenAccount2022") {
             ObjectType::AssociatedTokenAccount
+        } else if ty_name.eq("Clock") {
+            ObjectType::Sysvar(SysvarType::Clock)
+        } else if ty_name.eq("Instructions") {
+            ObjectType::Sysvar(SysvarType::Instructions)
+        } else if ty_name.eq("Rent") {
+            ObjectType::Sysvar(SysvarType::Rent)
+        } else if ty_name.eq("SlotHashes") {
+            ObjectType::Sysvar(SysvarType::SlotHashes)
         } else {
             match nautilus_ty {
                 Some(t) => match t {
                     NautilusObjectType::Record => ObjectType::Record(is_mut, vec![]), // TODO: PDA authorities not supported yet
                     NautilusObjectType::Account => ObjectType::Account(is_mut, vec![]), // TODO: PDA authorities not supported yet
                 },
-                None => panic!("Could not match object type: {}. Was it annotated with a Nautilus #[derive(..)] macro?", ty_name)
+                None => {
+                    return Err(syn::Error::new_spanned(
+                        ty_ident,
+                        format!(
+                            "Could not match object type: {}. Was it annotated with a Nautilus #[derive(..)] macro?",
+                            ty_name
+                        ),
+                    ))
+                }
             }
-        }
+        };
+        Ok(object_type)
     }
 
     /// Resolves the required accounts for an object name and ObjectType.
@@ -323,12 +431,25 @@ impl RequiredAccount {
                     Construct::AssociatedTokenProgram.into(),
                 ]
             }
-            ObjectType::Record(is_mut, _) => {
-                vec![
+            // Sysvars read with `Sysvar::get()` don't require their account.
+            ObjectType::Sysvar(sysvar_type) => match sysvar_type.has_getter() {
+                true => vec![],
+                false => vec![Construct::Sysvar(sysvar_type).into()],
+            },
+            ObjectType::Record(is_mut, constructs) => {
+                let mut accounts: Vec<Self> = vec![
                     Construct::ProgramId.into(),
                     Construct::SelfAccount(obj_name.clone(), obj_name, is_mut, false).into(),
-                    Construct::Index(is_mut).into(),
-                ]
+                ];
+                // Tables with their own counter don't use the `NautilusIndex`.
+                if !constructs
+                    .iter()
+                    .any(|c| matches!(c, Construct::Counter(..)))
+                {
+                    accounts.push(Construct::Index(is_mut).into());
+                }
+                accounts.extend(constructs.into_iter().map(Self::from));
+                accounts
             }
             ObjectType::Account(is_mut, _) => {
                 vec![
@@ -365,6 +486,7 @@ impl RequiredAccount {
             let entry = map.entry(account.name.clone()).or_insert(account.clone());
             entry.is_mut |= account.is_mut;
             entry.is_signer |= account.is_signer;
+            entry.is_optional &= account.is_optional;
             entry.desc = account.desc;
         }
         let mut res: Vec<RequiredAccount> = map.into_iter().map(|(_, v)| v).collect();
@@ -388,7 +510,13 @@ impl From<&RequiredAccount> for proc_macro2::TokenStream {
     fn from(ast: &RequiredAccount) -> Self {
         match &ast.account_type {
             RequiredAccountType::ProgramId => quote! { program_id },
-            RequiredAccountType::IndexAccount => quote! { nautilus_index.clone() },
+            RequiredAccountType::IndexAccount => quote! { nautilus_index.clone().into() },
+            RequiredAccountType::CounterAccount(table_name) => {
+                let ident_pointer = to_ident_pointer(&ast.ident);
+                quote! {
+                    NautilusCounter::load(program_id, #table_name, #ident_pointer.clone())?.into()
+                }
+            }
             RequiredAccountType::Account(subtype) => match subtype {
                 RequiredAccountSubtype::SelfAccount => {
                     let ident_pointer = self_account_ident_pointer(&ast.ident);
@@ -402,6 +530,10 @@ impl From<&RequiredAccount> for proc_macro2::TokenStream {
                     let ident_pointer = mint_authority_ident_pointer(&ast.ident);
                     quote! { #ident_pointer.clone() }
                 }
+                RequiredAccountSubtype::Lookup => {
+                    let ident_pointer = to_ident_pointer(&ast.ident);
+                    quote! { #ident_pointer.clone() }
+                }
             },
             _ => {
                 let ident_pointer = to_ident_pointer(&ast.ident);
@@ -415,6 +547,7 @@ impl ToString for RequiredAccountType {
     fn to_string(&self) -> String {
         match self {
             RequiredAccountType::IndexAccount => "index".to_string(),
+            RequiredAccountType::CounterAccount(_) => "counter".to_string(),
             RequiredAccountType::FeePayer => "feePayer".to_string(),
             RequiredAccountType::Sysvar => "sysvar".to_string(),
             RequiredAccountType::SystemProgram => "systemProgram".to_string(),
@@ -0,0 +1,327 @@
+//! Builds the required trait implementations for a program's custom errors,
+//! declared on an enum with `#[derive(nautilus::NautilusError)]`.
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Attribute, Expr, ExprLit, Fields, Ident, ItemEnum, Lit, LitInt, LitStr};
+
+/// The default code of a program's first custom error.
+///
+/// Custom error codes start well above those of `NautilusError` (`200` and up)
+/// so they can never collide.
+pub const DEFAULT_ERROR_OFFSET: u32 = 6000;
+
+/// The struct containing the parsed contents of a user's enum, annotated with
+/// `#[derive(nautilus::NautilusError)]`.
+#[derive(Clone, Debug)]
+pub struct NautilusCustomError {
+    pub ident: Ident,
+    pub variants: Vec<NautilusCustomErrorVariant>,
+}
+
+/// A single custom error, with its code and the message declared with
+/// `#[msg(..)]`.
+#[derive(Clone, Debug)]
+pub struct NautilusCustomErrorVariant {
+    pub ident: Ident,
+    pub code: u32,
+    pub msg: Option<String>,
+}
+
+impl NautilusCustomError {
+    /// Converts the user's annotated enum into the `NautilusCustomError`
+    /// struct.
+    ///
+    /// Each variant's code is the enum's offset - `6000` unless declared with
+    /// `#[error_offset(..)]` - plus the variant's discriminant, so variants are
+    /// numbered in order unless given an explicit discriminant.
+    pub fn from_item_enum(value: &ItemEnum) -> syn::Result<Self> {
+        let offset = parse_error_offset(&value.attrs)?;
+        // `None` once the previous discriminant was `u32::MAX`.
+        let mut next_discriminant: Option<u32> = Some(0);
+        let variants = value
+            .variants
+            .iter()
+            .map(|v| {
+                if !matches!(v.fields, Fields::Unit) {
+                    return Err(syn::Error::new_spanned(
+                        &v.fields,
+                        "Custom errors can't hold any data.",
+                    ));
+                }
+                let discriminant: u32 = match &v.discriminant {
+                    Some((_, expr)) => match expr {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Int(lit), ..
+                        }) => lit.base10_parse()?,
+                        expr => {
+                            return Err(syn::Error::new_spanned(
+                                expr,
+                                "Expected the discriminant as an integer literal.",
+                            ))
+                        }
+                    },
+                    None => next_discriminant.ok_or_else(|| {
+                        syn::Error::new_spanned(
+                            &v.ident,
+                            "This error's discriminant overflows a `u32`.",
+                        )
+                    })?,
+                };
+                let code = offset.checked_add(discriminant).ok_or_else(|| {
+                    syn::Error::new_spanned(&v.ident, "This error's code overflows a `u32`.")
+                })?;
+                next_discriminant = discriminant.checked_add(1);
+                Ok(NautilusCustomErrorVariant {
+                    ident: v.ident.clone(),
+                    code,
+                    msg: parse_msg(&v.attrs)?,
+                })
+            })
+            .collect::<syn::Result<Vec<NautilusCustomErrorVariant>>>()?;
+        let mut codes: Vec<u32> = variants.iter().map(|v| v.code).collect();
+        codes.sort();
+        if codes.windows(2).any(|w| w[0] == w[1]) {
+            return Err(syn::Error::new_spanned(
+                &value.ident,
+                "Two of these errors share the same code.",
+            ));
+        }
+        Ok(Self {
+            ident: value.ident.clone(),
+            variants,
+        })
+    }
+}
+
+/// Parses the `#[error_offset(..)]` attribute of an enum.
+fn parse_error_offset(attrs: &[Attribute]) -> syn::Result<u32> {
+    match attrs.iter().find(|attr| attr.path.is_ident("error_offset")) {
+        Some(attr) => {
+            let offset: u32 = attr.parse_args::<LitInt>()?.base10_parse()?;
+            if offset < DEFAULT_ERROR_OFFSET {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    format!(
+                        "Custom error codes must start at {} or above, to stay clear of Nautilus' own errors.",
+                        DEFAULT_ERROR_OFFSET
+                    ),
+                ));
+            }
+            Ok(offset)
+        }
+        None => Ok(DEFAULT_ERROR_OFFSET),
+    }
+}
+
+/// Parses the `#[msg(..)]` attribute of a variant.
+fn parse_msg(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    match attrs.iter().find(|attr| attr.path.is_ident("msg")) {
+        Some(attr) => Ok(Some(attr.parse_args::<LitStr>()?.value())),
+        None => Ok(None),
+    }
+}
+
+impl ToTokens for NautilusCustomError {
+    /// Extends the existing compiler tokens by the tokens generated by the
+    /// `NautilusCustomError`.
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend::<TokenStream>(self.into());
+    }
+}
+
+impl From<&NautilusCustomError> for TokenStream {
+    /// Implements the traits required for the enum to be returned as a
+    /// `ProgramError` and logged through `PrintProgramError`, just like
+    /// `NautilusError`.
+    ///
+    /// `Debug` and `Display` are implemented here as well, so the enum
+    /// shouldn't derive them itself.
+    fn from(ast: &NautilusCustomError) -> Self {
+        let ident = &ast.ident;
+        let type_name = ident.to_string();
+        let variant_idents: Vec<&Ident> = ast.variants.iter().map(|v| &v.ident).collect();
+        let codes: Vec<u32> = ast.variants.iter().map(|v| v.code).collect();
+        let codes_i64: Vec<i64> = codes.iter().map(|c| *c as i64).collect();
+        let names: Vec<String> = ast.variants.iter().map(|v| v.ident.to_string()).collect();
+        let msgs: Vec<LitStr> = ast
+            .variants
+            .iter()
+            .map(|v| {
+                LitStr::new(
+                    v.msg.as_ref().unwrap_or(&v.ident.to_string()),
+                    Span::call_site(),
+                )
+            })
+            .collect();
+        quote! {
+            impl #ident {
+                /// The code of this error, returned as `ProgramError::Custom(code)`.
+                pub fn code(&self) -> u32 {
+                    match self {
+                        #(Self::#variant_idents => #codes,)*
+                    }
+                }
+            }
+
+            impl ::core::fmt::Display for #ident {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        #(Self::#variant_idents => f.write_str(#msgs),)*
+                    }
+                }
+            }
+
+            impl ::core::fmt::Debug for #ident {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        #(Self::#variant_idents => f.write_str(#names),)*
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #ident {}
+
+            impl<T> nautilus::solana_program::decode_error::DecodeError<T> for #ident {
+                fn type_of() -> &'static str {
+                    #type_name
+                }
+            }
+
+            impl nautilus::num_traits::FromPrimitive for #ident {
+                fn from_i64(n: i64) -> Option<Self> {
+                    match n {
+                        #(#codes_i64 => Some(Self::#variant_idents),)*
+                        _ => None,
+                    }
+                }
+
+                fn from_u64(n: u64) -> Option<Self> {
+                    Self::from_i64(n as i64)
+                }
+            }
+
+            impl nautilus::solana_program::program_error::PrintProgramError for #ident {
+                fn print<E>(&self)
+                where
+                    E: 'static
+                        + ::std::error::Error
+                        + nautilus::solana_program::decode_error::DecodeError<E>
+                        + nautilus::solana_program::program_error::PrintProgramError
+                        + nautilus::num_traits::FromPrimitive,
+                {
+                    splogger::error!("{}: {}", #type_name, self);
+                }
+            }
+
+            impl From<#ident> for nautilus::solana_program::program_error::ProgramError {
+                fn from(e: #ident) -> Self {
+                    nautilus::solana_program::program_error::PrintProgramError::print::<#ident>(&e);
+                    nautilus::solana_program::program_error::ProgramError::Custom(e.code())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn codes(error: &NautilusCustomError) -> Vec<u32> {
+        error.variants.iter().map(|v| v.code).collect()
+    }
+
+    #[test]
+    fn numbers_errors_from_default_offset() {
+        let error = NautilusCustomError::from_item_enum(&parse_quote! {
+            enum MyError {
+                #[msg("The hero fainted.")]
+                HeroFainted,
+                VillainEscaped,
+                Skipped = 5,
+                AfterSkipped,
+            }
+        })
+        .unwrap();
+        assert_eq!(codes(&error), vec![6000, 6001, 6005, 6006]);
+        assert_eq!(error.variants[0].msg.as_deref(), Some("The hero fainted."));
+        assert_eq!(error.variants[1].msg, None);
+    }
+
+    #[test]
+    fn numbers_errors_from_declared_offset() {
+        let error = NautilusCustomError::from_item_enum(&parse_quote! {
+            #[error_offset(7000)]
+            enum MyError {
+                HeroFainted,
+                VillainEscaped,
+            }
+        })
+        .unwrap();
+        assert_eq!(codes(&error), vec![7000, 7001]);
+    }
+
+    #[test]
+    fn rejects_offset_below_default() {
+        assert!(NautilusCustomError::from_item_enum(&parse_quote! {
+            #[error_offset(200)]
+            enum MyError {
+                HeroFainted,
+            }
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_code_overflow() {
+        assert!(NautilusCustomError::from_item_enum(&parse_quote! {
+            enum MyError {
+                HeroFainted = 4294967295,
+            }
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_discriminant_overflow() {
+        assert!(NautilusCustomError::from_item_enum(&parse_quote! {
+            #[error_offset(4294967295)]
+            enum MyError {
+                HeroFainted = 0,
+                VillainEscaped,
+            }
+        })
+        .is_err());
+        assert!(NautilusCustomError::from_item_enum(&parse_quote! {
+            enum MyError {
+                HeroFainted = 4294967295,
+                VillainEscaped,
+            }
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_codes() {
+        assert!(NautilusCustomError::from_item_enum(&parse_quote! {
+            enum MyError {
+                HeroFainted = 1,
+                VillainEscaped = 0,
+                Duplicate,
+            }
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_errors_with_data() {
+        assert!(NautilusCustomError::from_item_enum(&parse_quote! {
+            enum MyError {
+                HeroFainted(u8),
+            }
+        })
+        .is_err());
+    }
+}
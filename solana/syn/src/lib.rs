@@ -5,5 +5,7 @@
 // ----------------------------------------------------------------
 //
 //
+pub mod cpi;
 pub mod entry;
+pub mod error;
 pub mod object;
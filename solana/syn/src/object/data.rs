@@ -4,12 +4,19 @@ use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{token::Colon, Fields, FnArg, Ident, Pat, PatIdent, PatType, Type};
 
-use super::seeds::Seed;
+use super::{
+    parser::{ForeignKeyField, LookupField},
+    seeds::Seed,
+    version::{build_tokens_version_consts, build_tokens_versioned_deserialize, Version},
+};
 
 /// Generates tokens to implement `Clone` on a struct.
+///
+/// The struct's fields are always named, since `parse_item_struct(..)` rejects
+/// any other struct.
 pub fn impl_clone(ident: &Ident, fields: &Fields) -> TokenStream {
     let clone_constructors = fields.iter().map(|f| {
-        let ident = &f.ident.as_ref().unwrap();
+        let ident = &f.ident;
         quote! { #ident: ::core::clone::Clone::clone(&self.#ident) }
     });
     quote! {
@@ -25,9 +32,12 @@ pub fn impl_clone(ident: &Ident, fields: &Fields) -> TokenStream {
 }
 
 /// Generates tokens to implement `Default` on a struct.
+///
+/// The struct's fields are always named, since `parse_item_struct(..)` rejects
+/// any other struct.
 pub fn impl_default(ident: &Ident, fields: &Fields) -> TokenStream {
     let fields_default = fields.iter().map(|f| {
-        let i = &f.ident.clone().unwrap();
+        let i = &f.ident;
         quote! { #i: ::core::default::Default::default() }
     });
     quote! {
@@ -44,7 +54,12 @@ pub fn impl_default(ident: &Ident, fields: &Fields) -> TokenStream {
 
 /// Generates tokens to implement `BorshDeserialize` and `BorshSerialize` on a
 /// struct.
-pub fn impl_borsh(ident: &Ident, fields: &Fields) -> TokenStream {
+pub fn impl_borsh(
+    ident: &Ident,
+    fields: &Fields,
+    discrim_str: &str,
+    version: &Option<Version>,
+) -> TokenStream {
     let borsh_ser_where = fields.iter().map(|f| {
         let field_ty = f.ty.clone();
         quote::quote! { #field_ty: nautilus::borsh::ser::BorshSerialize }
@@ -61,6 +76,25 @@ pub fn impl_borsh(ident: &Ident, fields: &Fields) -> TokenStream {
         let field_name = f.ident.clone();
         quote::quote! { #field_name: nautilus::borsh::BorshDeserialize::deserialize(buf)? }
     });
+    // A versioned object writes its version right after the discriminator.
+    let (borsh_ser_version, borsh_deser_body) = match version {
+        Some(version) => {
+            let current = version.current;
+            (
+                quote::quote! { borsh::BorshSerialize::serialize(&#current, writer)?; },
+                build_tokens_versioned_deserialize(version, fields, discrim_str),
+            )
+        }
+        None => (
+            quote::quote!(),
+            quote::quote! {
+                let _discrim: [u8; 8] = borsh::BorshDeserialize::deserialize(buf)?; // Skip the first 8 bytes for discriminator
+                Ok(Self {
+                    #(#borsh_deser_impl,)*
+                })
+            },
+        ),
+    };
     quote::quote! {
         impl nautilus::borsh::ser::BorshSerialize for #ident
         where
@@ -71,6 +105,7 @@ pub fn impl_borsh(ident: &Ident, fields: &Fields) -> TokenStream {
                 writer: &mut W,
             ) -> ::core::result::Result<(), nautilus::borsh::maybestd::io::Error> {
                 borsh::BorshSerialize::serialize(&self.discriminator(), writer)?; // Serialize the discriminator first
+                #borsh_ser_version
                 #(#borsh_ser_impl;)*
                 Ok(())
             }
@@ -82,51 +117,66 @@ pub fn impl_borsh(ident: &Ident, fields: &Fields) -> TokenStream {
             fn deserialize(
                 buf: &mut &[u8],
             ) -> ::core::result::Result<Self, nautilus::borsh::maybestd::io::Error> {
-                let _discrim: [u8; 8] = borsh::BorshDeserialize::deserialize(buf)?; // Skip the first 8 bytes for discriminator
-                Ok(Self {
-                    #(#borsh_deser_impl,)*
-                })
+                #borsh_deser_body
             }
         }
     }
 }
 
 /// Generates tokens to implement `NautilusRecordData` on a struct.
+#[allow(clippy::too_many_arguments)]
 pub fn impl_nautilus_record_data(
     ident: &Ident,
     fields: &Fields,
     table_name: &String,
     autoincrement: bool,
-    primary_key_ident: &Ident,
-    primary_key_ty: &Type,
+    primary_keys: &[(Ident, Type)],
+    authorities: &Vec<Ident>,
+    lookups: &[LookupField],
+    foreign_keys: &[ForeignKeyField],
+    version: &Option<Version>,
+    space: Option<usize>,
 ) -> TokenStream {
     let nautilus_create_obj_trait_ident = &Ident::new(
         &("NautilusCreate".to_owned() + &ident.to_string()),
         Span::call_site(),
     );
 
-    let tokens_primary_key_seed = build_tokens_primary_key_seed(primary_key_ident, primary_key_ty);
+    let space = build_tokens_space(space);
+    let version_consts = build_tokens_version_consts(version);
+    let (tokens_primary_key_seed, impl_seeds) = build_tokens_primary_keys(primary_keys);
+    let impl_authorities = build_tokens_authorities(authorities);
+    let impl_lookups = build_tokens_lookups(lookups);
+    let (foreign_key_fn_args, foreign_key_call_args, impl_check_foreign_keys) =
+        build_tokens_foreign_keys(foreign_keys);
 
     let (data_new_fn_args, data_new_call_args) =
-        get_new_fn_args_for_record(fields, autoincrement, primary_key_ident);
+        get_new_fn_args_for_record(fields, autoincrement, primary_keys);
 
+    // Autoincrement is only ever enabled for a single primary key. Writing the
+    // record fails once the table's count overflows the key's type.
+    let primary_key_ident = &primary_keys[0].0;
     let data_new_fn = match autoincrement {
         true => quote! {
             pub fn new<'a>(
-                mut nautilus_index: NautilusIndex<'a>,
+                mut nautilus_index: NautilusRecordIndex<'a>,
                 fee_payer: impl NautilusSigner<'a>,
                 #(#data_new_fn_args,)*
             ) -> Result<Box<Self>, ProgramError> {
-                let #primary_key_ident = nautilus_index.add_record(
-                    Self::TABLE_NAME,
-                    fee_payer,
-                )?.try_into().unwrap();
+                let #primary_key_ident = nautilus_index
+                    .add_record(Self::TABLE_NAME, fee_payer)?
+                    .try_into()
+                    .map_err(|_| {
+                        nautilus::error::NautilusError::WriteRecordFailed(
+                            Self::TABLE_NAME.to_string(),
+                        )
+                    })?;
                 Ok(Box::new(Self{ #primary_key_ident, #(#data_new_call_args,)* }))
             }
         },
         false => quote! {
             pub fn new<'a>(
-                _nautilus_index: NautilusIndex<'a>,
+                _nautilus_index: NautilusRecordIndex<'a>,
                 fee_payer: impl NautilusSigner<'a>,
                 #(#data_new_fn_args,)*
             ) -> Result<Box<Self>, ProgramError> {
@@ -138,6 +188,8 @@ pub fn impl_nautilus_record_data(
     quote! {
         impl #ident {
             #data_new_fn
+
+            #impl_check_foreign_keys
         }
 
         impl NautilusRecordData for #ident {
@@ -145,26 +197,28 @@ pub fn impl_nautilus_record_data(
 
             const AUTO_INCREMENT: bool = #autoincrement;
 
+            const SPACE: Option<usize> = #space;
+
+            #version_consts
+
             fn primary_key(&self) -> Vec<u8> {
                 #tokens_primary_key_seed
             }
 
-            fn check_authorities(&self, accounts: Vec<AccountInfo>) -> Result<(), ProgramError> {
-                todo!()
-            }
+            #impl_seeds
 
-            fn count_authorities(&self) -> u8 {
-                todo!()
-            }
+            #impl_lookups
+
+            #impl_authorities
         }
 
         pub trait #nautilus_create_obj_trait_ident<'a> {
-            fn create(&mut self, #(#data_new_fn_args,)*) -> ProgramResult;
-            fn create_with_payer(&mut self, #(#data_new_fn_args,)* payer: impl NautilusSigner<'a>) -> ProgramResult;
+            fn create(&mut self, #(#data_new_fn_args,)* #(#foreign_key_fn_args,)*) -> ProgramResult;
+            fn create_with_payer(&mut self, #(#data_new_fn_args,)* #(#foreign_key_fn_args,)* payer: impl NautilusSigner<'a>) -> ProgramResult;
         }
 
         impl<'a> #nautilus_create_obj_trait_ident<'a> for Create<'a, Record<'a, #ident>> {
-            fn create(&mut self, #(#data_new_fn_args,)*) -> ProgramResult {
+            fn create(&mut self, #(#data_new_fn_args,)* #(#foreign_key_fn_args,)*) -> ProgramResult {
                 let rent_payer = Signer::new(Wallet {
                     account_info: self.fee_payer.to_owned(),
                     system_program: self.system_program.to_owned(),
@@ -174,15 +228,17 @@ pub fn impl_nautilus_record_data(
                     rent_payer,
                     #(#data_new_call_args,)*
                 )?;
+                #foreign_key_call_args
                 self.create_record()
             }
 
-            fn create_with_payer(&mut self, #(#data_new_fn_args,)* payer: impl NautilusSigner<'a>) -> ProgramResult {
+            fn create_with_payer(&mut self, #(#data_new_fn_args,)* #(#foreign_key_fn_args,)* payer: impl NautilusSigner<'a>) -> ProgramResult {
                 self.self_account.data = #ident ::new(
                     self.self_account.index.clone(),
                     payer.clone(),
                     #(#data_new_call_args,)*
                 )?;
+                #foreign_key_call_args
                 self.create_record_with_payer(payer)
             }
         }
@@ -194,7 +250,10 @@ pub fn impl_nautilus_account_data(
     ident: &Ident,
     fields: &Fields,
     discrminator_str: &String,
+    authorities: &Vec<Ident>,
     seeds: &Vec<Seed>,
+    version: &Option<Version>,
+    space: Option<usize>,
 ) -> TokenStream {
     let nautilus_inner_trait_ident = &Ident::new(
         &("NautilusInner".to_owned() + &ident.to_string()),
@@ -205,7 +264,10 @@ pub fn impl_nautilus_account_data(
         Span::call_site(),
     );
 
+    let space = build_tokens_space(space);
+    let version_consts = build_tokens_version_consts(version);
     let (data_new_fn_args, data_new_call_args) = get_new_fn_args_for_account(fields);
+    let impl_authorities = build_tokens_authorities(authorities);
 
     let data_new_fn = quote! {
         pub fn new<'a>(
@@ -250,6 +312,16 @@ pub fn impl_nautilus_account_data(
             quote! { &mut self, #(#data_new_fn_args,)* payer: impl NautilusSigner<'a> },
         ),
     };
+    // An account's address can only be checked as it's loaded if its seeds don't
+    // take any parameters.
+    let derived_pda_fn = match &seeds_params_tuple {
+        Some(_) => quote!(),
+        None => quote! {
+            fn derived_pda(&self, program_id: &Pubkey) -> Option<(Pubkey, u8)> {
+                self.pda(program_id).ok()
+            }
+        },
+    };
     // For seeds on inner data `T`.
     let seeds_fn = quote! {
         pub fn seeds(#seeds_args) -> Result<Vec<Vec<u8>>, ProgramError> {
@@ -300,13 +372,13 @@ pub fn impl_nautilus_account_data(
         impl NautilusAccountData for #ident {
             const DISCRIMINATOR_STR: &'static str = #discrminator_str;
 
-            fn check_authorities(&self, accounts: Vec<AccountInfo>) -> Result<(), ProgramError> {
-                todo!()
-            }
+            const SPACE: Option<usize> = #space;
 
-            fn count_authorities(&self) -> u8 {
-                todo!()
-            }
+            #version_consts
+
+            #derived_pda_fn
+
+            #impl_authorities
         }
 
         pub trait #nautilus_inner_trait_ident<'a> {
@@ -385,8 +457,202 @@ pub fn impl_nautilus_account_data(
     }
 }
 
+/// Generates tokens to implement `Default` and the Borsh traits on a
+/// `#[zero_copy]` struct.
+///
+/// Since the struct is `bytemuck::Pod`, its default is all zeroes and it's
+/// serialized as its raw bytes after the discriminator.
+pub fn impl_zero_copy_default_and_borsh(ident: &Ident) -> TokenStream {
+    quote! {
+        impl ::core::default::Default for #ident {
+            #[inline]
+            fn default() -> Self {
+                nautilus::bytemuck::Zeroable::zeroed()
+            }
+        }
+        impl nautilus::borsh::ser::BorshSerialize for #ident {
+            fn serialize<W: nautilus::borsh::maybestd::io::Write>(
+                &self,
+                writer: &mut W,
+            ) -> ::core::result::Result<(), nautilus::borsh::maybestd::io::Error> {
+                borsh::BorshSerialize::serialize(&self.discriminator(), writer)?; // Serialize the discriminator first
+                writer.write_all(nautilus::bytemuck::bytes_of(self))
+            }
+        }
+        impl nautilus::borsh::de::BorshDeserialize for #ident {
+            fn deserialize(
+                buf: &mut &[u8],
+            ) -> ::core::result::Result<Self, nautilus::borsh::maybestd::io::Error> {
+                let _discrim: [u8; 8] = borsh::BorshDeserialize::deserialize(buf)?; // Skip the first 8 bytes for discriminator
+                let size = ::core::mem::size_of::<Self>();
+                if buf.len() < size {
+                    return Err(nautilus::borsh::maybestd::io::Error::new(
+                        nautilus::borsh::maybestd::io::ErrorKind::InvalidData,
+                        "Unexpected length of input",
+                    ));
+                }
+                let data = nautilus::bytemuck::pod_read_unaligned(&buf[..size]);
+                *buf = &buf[size..];
+                Ok(data)
+            }
+        }
+    }
+}
+
+/// Generates tokens to implement `NautilusAccountData` on a `#[zero_copy]`
+/// struct, along with the seeds and creation of its `ZeroCopyAccount`.
+///
+/// A zero-copy account is created with zeroed data rather than from a value,
+/// since its data may be too large to build on the stack. Its seeds are then
+/// associated functions, since they can't depend on its fields.
+pub fn impl_nautilus_zero_copy_account_data(
+    ident: &Ident,
+    discrminator_str: &String,
+    authorities: &Vec<Ident>,
+    seeds: &Vec<Seed>,
+) -> TokenStream {
+    let nautilus_inner_trait_ident = &Ident::new(
+        &("NautilusInner".to_owned() + &ident.to_string()),
+        Span::call_site(),
+    );
+    let nautilus_create_obj_trait_ident = &Ident::new(
+        &("NautilusCreate".to_owned() + &ident.to_string()),
+        Span::call_site(),
+    );
+
+    let impl_authorities = build_tokens_authorities(authorities);
+
+    let (seeds_inner, seeds_params_tuple) = build_seeds_components_for_account(seeds);
+    let (
+        seeds_args,
+        seeds_caller,
+        pda_args,
+        self_seeds_args,
+        create_args,
+        create_with_payer_args,
+        create_with_payer_caller,
+    ) = match &seeds_params_tuple {
+        Some(tuple) => (
+            quote! { seeds: #tuple },
+            quote! { seeds },
+            quote! { program_id: &Pubkey, seeds: #tuple },
+            quote! { &self, seeds: #tuple },
+            quote! { &mut self, seeds: #tuple },
+            quote! { &mut self, seeds: #tuple, payer: impl NautilusSigner<'a> },
+            quote! { seeds, payer },
+        ),
+        None => (
+            quote!(),
+            quote!(),
+            quote! { program_id: &Pubkey },
+            quote! { &self },
+            quote! { &mut self },
+            quote! { &mut self, payer: impl NautilusSigner<'a> },
+            quote! { payer },
+        ),
+    };
+
+    // A zero-copy account's seeds never depend on its data, so its address can
+    // be checked as it's loaded as long as they don't take any parameters.
+    let derived_pda_fn = match &seeds_params_tuple {
+        Some(_) => quote!(),
+        None => quote! {
+            fn derived_pda(&self, program_id: &Pubkey) -> Option<(Pubkey, u8)> {
+                Self::pda(program_id).ok()
+            }
+        },
+    };
+
+    quote! {
+        impl #ident {
+            pub fn seeds(#seeds_args) -> Result<Vec<Vec<u8>>, ProgramError> {
+                Ok(#seeds_inner)
+            }
+
+            pub fn pda(#pda_args) -> Result<(Pubkey, u8), ProgramError> {
+                let seeds_vec = Self::seeds(#seeds_caller)?;
+                let seeds: Vec<&[u8]> = seeds_vec.iter().map(AsRef::as_ref).collect();
+                Ok(Pubkey::find_program_address(&seeds, program_id))
+            }
+        }
+
+        impl NautilusAccountData for #ident {
+            const DISCRIMINATOR_STR: &'static str = #discrminator_str;
+
+            #derived_pda_fn
+
+            #impl_authorities
+        }
+
+        pub trait #nautilus_inner_trait_ident<'a> {
+            fn seeds(#self_seeds_args) -> Result<Vec<Vec<u8>>, ProgramError>;
+            fn pda(#self_seeds_args) -> Result<(Pubkey, u8), ProgramError>;
+        }
+
+        impl<'a> #nautilus_inner_trait_ident<'a> for ZeroCopyAccount<'a, #ident> {
+            fn seeds(#self_seeds_args) -> Result<Vec<Vec<u8>>, ProgramError> {
+                #ident ::seeds(#seeds_caller)
+            }
+
+            fn pda(#self_seeds_args) -> Result<(Pubkey, u8), ProgramError> {
+                let program_id = self.program_id;
+                #ident ::pda(program_id, #seeds_caller)
+            }
+        }
+
+        impl<'a> #nautilus_inner_trait_ident<'a> for Create<'a, ZeroCopyAccount<'a, #ident>> {
+            fn seeds(#self_seeds_args) -> Result<Vec<Vec<u8>>, ProgramError> {
+                self.self_account.seeds(#seeds_caller)
+            }
+
+            fn pda(#self_seeds_args) -> Result<(Pubkey, u8), ProgramError> {
+                self.self_account.pda(#seeds_caller)
+            }
+        }
+
+        pub trait #nautilus_create_obj_trait_ident<'a> {
+            fn create(#create_args) -> ProgramResult;
+            fn create_with_payer(#create_with_payer_args) -> ProgramResult;
+        }
+
+        impl<'a> #nautilus_create_obj_trait_ident<'a> for Create<'a, ZeroCopyAccount<'a, #ident>> {
+            fn create(#create_args) -> ProgramResult {
+                let payer = Signer::new(Wallet {
+                    account_info: self.fee_payer.to_owned(),
+                    system_program: self.system_program.to_owned(),
+                })?;
+                self.create_with_payer(#create_with_payer_caller)
+            }
+
+            fn create_with_payer(#create_with_payer_args) -> ProgramResult {
+                let (pda, bump) = self.pda(#seeds_caller)?;
+                assert_eq!(
+                    &pda,
+                    self.key(),
+                    "Derived PDA does not match data for account {:#?}",
+                    self.key()
+                );
+                let mut signer_seeds_vec = self.seeds(#seeds_caller)?;
+                signer_seeds_vec.push(vec![bump]);
+                let signer_seeds: Vec<&[u8]> = signer_seeds_vec.iter().map(AsRef::as_ref).collect();
+                // The new account is zeroed, so only the discriminator is written.
+                cpi::system::create_pda(
+                    self.self_account.clone(),
+                    self.self_account.program_id,
+                    payer,
+                    Box::new(self.self_account.discriminator()),
+                    signer_seeds,
+                )
+            }
+        }
+    }
+}
+
 /// Helper function to generate tokens for writing the function that returns the
 /// data type's primary key.
+///
+/// The key's type is one of `String`, `u8`, `u16`, `u32`, `u64` or `Pubkey`,
+/// since `parse_item_struct(..)` rejects any other type.
 fn build_tokens_primary_key_seed(key: &syn::Ident, ty: &syn::Type) -> TokenStream {
     match quote::quote!(#ty).to_string().as_str() {
         "String" => quote::quote! {
@@ -395,15 +661,167 @@ fn build_tokens_primary_key_seed(key: &syn::Ident, ty: &syn::Type) -> TokenStrea
         "u8" => quote::quote! {
             vec![self.#key]
         },
-        "u16" | "u32" | "u64" => quote::quote! {
+        "Pubkey" => quote::quote! {
+            self.#key.to_bytes().to_vec()
+        },
+        // `u16`, `u32` or `u64`.
+        _ => quote::quote! {
             self.#key.to_le_bytes().to_vec()
         },
-        "Pubkey" => quote::quote! {
-            self.#key.to_vec()
+    }
+}
+
+/// Helper function to generate tokens for the `primary_key()` function and, for
+/// a composite primary key, an override of the `seeds()` function.
+///
+/// A composite key's fields are each their own seed, in declaration order,
+/// while `primary_key()` returns them concatenated.
+fn build_tokens_primary_keys(primary_keys: &[(Ident, Type)]) -> (TokenStream, TokenStream) {
+    if primary_keys.len() == 1 {
+        let (key, ty) = &primary_keys[0];
+        return (build_tokens_primary_key_seed(key, ty), quote!());
+    }
+    let key_seeds: Vec<TokenStream> = primary_keys
+        .iter()
+        .map(|(key, ty)| build_tokens_primary_key_seed(key, ty))
+        .collect();
+    (
+        quote! {
+            vec![#(#key_seeds,)*].concat()
         },
-        _ => panic!(
-            "Invalid primary key type! Only `String`, `u8`, `u16, `u32`, `u64`, and `Pubkey` are supported."
-        ),
+        quote! {
+            fn seeds(&self) -> Vec<Vec<u8>> {
+                vec![Self::TABLE_NAME.as_bytes().to_vec(), #(#key_seeds,)*]
+            }
+        },
+    )
+}
+
+/// Helper function to generate tokens for the `lookups()` function from the
+/// fields marked `#[index]` or `#[unique]`.
+///
+/// Each field's value is converted to bytes the same way as a primary key, and
+/// `NautilusLookupKey::seeds()` hashes those bytes into the lookup's seed, so a
+/// value of any length - such as a long `String` - stays within the 32-byte
+/// seed limit.
+fn build_tokens_lookups(lookups: &[LookupField]) -> TokenStream {
+    if lookups.is_empty() {
+        return quote!();
+    }
+    let lookup_keys = lookups.iter().map(|l| {
+        let field_name = l.ident.to_string();
+        let value = build_tokens_primary_key_seed(&l.ident, &l.ty);
+        let unique = l.is_unique;
+        quote! {
+            nautilus::NautilusLookupKey {
+                table_name: Self::TABLE_NAME,
+                field_name: #field_name,
+                value: #value,
+                unique: #unique,
+            }
+        }
+    });
+    quote! {
+        fn lookups(&self) -> Vec<nautilus::NautilusLookupKey> {
+            vec![#(#lookup_keys,)*]
+        }
+    }
+}
+
+/// Helper function to generate the tokens for the table's `#[foreign_key(..)]`
+/// fields.
+///
+/// Consider the return type of this function:
+/// * The extra arguments taken by `create(..)`: a reference to the record each
+///   foreign key points to.
+/// * The call to `check_foreign_keys(..)` made by `create(..)` with those
+///   arguments.
+/// * The `check_foreign_keys(..)` function itself, which checks each provided
+///   record against the value of its foreign key.
+fn build_tokens_foreign_keys(
+    foreign_keys: &[ForeignKeyField],
+) -> (Vec<TokenStream>, TokenStream, TokenStream) {
+    if foreign_keys.is_empty() {
+        return (vec![], quote!(), quote!());
+    }
+    let record_idents: Vec<Ident> = foreign_keys
+        .iter()
+        .map(|fk| foreign_key_record_ident(&fk.ident))
+        .collect();
+    let fn_args: Vec<TokenStream> = foreign_keys
+        .iter()
+        .zip(record_idents.iter())
+        .map(|(fk, record_ident)| {
+            let references = &fk.references;
+            quote! { #record_ident: &Record<'a, #references> }
+        })
+        .collect();
+    let checks = foreign_keys
+        .iter()
+        .zip(record_idents.iter())
+        .map(|(fk, record_ident)| {
+            let field_name = fk.ident.to_string();
+            let value = build_tokens_primary_key_seed(&fk.ident, &fk.ty);
+            quote! { #record_ident.check_reference(Self::TABLE_NAME, #field_name, #value)?; }
+        });
+    (
+        fn_args.clone(),
+        quote! { self.self_account.data.check_foreign_keys(#(#record_idents,)*)?; },
+        quote! {
+            pub fn check_foreign_keys<'a>(&self, #(#fn_args,)*) -> ProgramResult {
+                #(#checks)*
+                Ok(())
+            }
+        },
+    )
+}
+
+/// The name of the argument holding the record referenced by a foreign key.
+pub fn foreign_key_record_ident(field_ident: &Ident) -> Ident {
+    Ident::new(&format!("{}_record", field_ident), Span::call_site())
+}
+
+/// Helper function to generate tokens for the `check_authorities(..)` and
+/// `count_authorities()` functions from the fields marked `#[authority]`.
+///
+/// Every declared authority must be present in the provided accounts as a
+/// signer.
+fn build_tokens_authorities(authorities: &Vec<Ident>) -> TokenStream {
+    let count_authorities = authorities.len() as u8;
+    let check_authorities = match authorities.is_empty() {
+        true => quote! {
+            fn check_authorities(&self, _accounts: Vec<AccountInfo>) -> Result<(), ProgramError> {
+                Ok(())
+            }
+        },
+        false => quote! {
+            fn check_authorities(&self, accounts: Vec<AccountInfo>) -> Result<(), ProgramError> {
+                #(
+                    if !accounts.iter().any(|a| a.key.eq(&self.#authorities) && a.is_signer) {
+                        return Err(nautilus::error::NautilusError::AccountNotSigner(
+                            self.#authorities.to_string(),
+                        ).into());
+                    }
+                )*
+                Ok(())
+            }
+        },
+    };
+    quote! {
+        #check_authorities
+
+        fn count_authorities(&self) -> u8 {
+            #count_authorities
+        }
+    }
+}
+
+/// Helper function to generate tokens for the `SPACE` constant from the
+/// object's static space, if it has one.
+fn build_tokens_space(space: Option<usize>) -> TokenStream {
+    match space {
+        Some(space) => quote! { Some(#space) },
+        None => quote! { None },
     }
 }
 
@@ -412,13 +830,13 @@ fn build_tokens_primary_key_seed(key: &syn::Ident, ty: &syn::Type) -> TokenStrea
 fn get_new_fn_args_for_record(
     fields: &Fields,
     autoincrement: bool,
-    primary_key_ident: &Ident,
+    primary_keys: &[(Ident, Type)],
 ) -> (Vec<FnArg>, Vec<Ident>) {
     let mut data_new_fn_args: Vec<FnArg> = vec![];
     let mut data_new_call_args: Vec<Ident> = vec![];
     fields.iter().for_each(|f| match &f.ident {
         Some(ident) => {
-            if !(autoincrement && ident == primary_key_ident) {
+            if !(autoincrement && ident == &primary_keys[0].0) {
                 data_new_call_args.push(ident.clone());
                 data_new_fn_args.push(FnArg::Typed(PatType {
                     attrs: vec![],
@@ -1,4 +1,13 @@
-use syn::{Attribute, NestedMeta};
+use convert_case::{Case, Casing};
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Attribute, Fields, Ident, ItemFn, NestedMeta, Type};
+
+use super::{
+    data::foreign_key_record_ident,
+    parser::{named_field_ident, ForeignKeyField, LookupField, NautilusObjectConfig},
+    NautilusObject,
+};
 
 /// Possible default instructions for records.
 #[derive(Clone, Debug)]
@@ -11,23 +20,153 @@ pub enum DefaultInstruction {
 impl DefaultInstruction {
     pub fn parse(nested_meta: &NestedMeta, struct_name: &str) -> syn::Result<Self> {
         if let syn::NestedMeta::Meta(syn::Meta::Path(ref path)) = nested_meta {
-            let variant_string = path.get_ident().unwrap().to_string();
-            if variant_string.eq("Create") {
-                return Ok(DefaultInstruction::Create(struct_name.to_string()));
-            } else if variant_string.eq("Delete") {
-                return Ok(DefaultInstruction::Delete(struct_name.to_string()));
-            } else if variant_string.eq("Update") {
-                return Ok(DefaultInstruction::Update(struct_name.to_string()));
-            } else {
-                return Err(syn_error(&format!(
-                    "Unknown default instruction: {}",
-                    variant_string
-                )));
+            match path.get_ident().map(|ident| ident.to_string()).as_deref() {
+                Some("Create") => Ok(DefaultInstruction::Create(struct_name.to_string())),
+                Some("Delete") => Ok(DefaultInstruction::Delete(struct_name.to_string())),
+                Some("Update") => Ok(DefaultInstruction::Update(struct_name.to_string())),
+                _ => Err(syn::Error::new_spanned(
+                    path,
+                    "Unknown default instruction. Expected `Create`, `Delete` or `Update`.",
+                )),
             }
         } else {
-            return Err(syn_error(
+            Err(syn::Error::new_spanned(
+                nested_meta,
                 "Invalid format for `default_instructions` attribute",
-            ));
+            ))
+        }
+    }
+
+    /// Builds the function for this default instruction, which is then treated
+    /// the same as a function declared by the user in the `#[nautilus]`
+    /// module.
+    ///
+    /// Every declared `#[authority]` field is required as a signer. For
+    /// `Create`, the signers' addresses are written into those fields, while
+    /// `Update` and `Delete` check them against the existing record with
+    /// `check_authorities(..)` before going through `NautilusUpdate` or
+    /// `NautilusDelete`.
+    ///
+    /// `Update` leaves `#[index]` and `#[unique]` fields untouched, since their
+    /// lookups are only maintained when a record is created or deleted.
+    ///
+    /// `Create` and `Update` also require the record referenced by each
+    /// `#[foreign_key(..)]` field, which is checked against the field's value.
+    ///
+    /// Returns a `syn::Error` pointing at any field of the table that isn't
+    /// named.
+    pub fn to_item_fn(
+        &self,
+        data_fields: &Fields,
+        autoincrement_enabled: bool,
+        primary_keys: &[(Ident, Type)],
+        authorities: &[Ident],
+        lookups: &[LookupField],
+        foreign_keys: &[ForeignKeyField],
+    ) -> syn::Result<ItemFn> {
+        let (prefix, struct_name) = match self {
+            DefaultInstruction::Create(name) => ("create", name),
+            DefaultInstruction::Delete(name) => ("delete", name),
+            DefaultInstruction::Update(name) => ("update", name),
+        };
+        let struct_ident = Ident::new(struct_name, Span::call_site());
+        let snake_name = struct_name.to_case(Case::Snake);
+        let fn_ident = Ident::new(&format!("{}_{}", prefix, snake_name), Span::call_site());
+        let authority_params = authorities
+            .iter()
+            .map(|a| quote! { #a: Signer<Wallet<'a>> });
+        let authority_accounts = authorities.iter().map(|a| quote! { *#a.account_info() });
+        let foreign_key_records: Vec<Ident> = foreign_keys
+            .iter()
+            .map(|fk| foreign_key_record_ident(&fk.ident))
+            .collect();
+        let foreign_key_params =
+            foreign_keys
+                .iter()
+                .zip(foreign_key_records.iter())
+                .map(|(fk, record_ident)| {
+                    let references = &fk.references;
+                    quote! { #record_ident: Record<'a, #references> }
+                });
+        match self {
+            DefaultInstruction::Create(_) => {
+                let record_ident = Ident::new(&format!("new_{}", snake_name), Span::call_site());
+                let mut data_params = vec![];
+                let mut create_args = vec![];
+                for f in data_fields.iter() {
+                    let ident = named_field_ident(f)?;
+                    let ty = &f.ty;
+                    if autoincrement_enabled && ident == &primary_keys[0].0 {
+                        continue;
+                    }
+                    if authorities.contains(ident) {
+                        create_args.push(quote! { *#ident.key() });
+                    } else {
+                        data_params.push(quote! { #ident: #ty });
+                        create_args.push(quote! { #ident });
+                    }
+                }
+                Ok(syn::parse_quote! {
+                    fn #fn_ident<'a>(
+                        mut #record_ident: Create<'a, Record<'a, #struct_ident>>,
+                        #(#authority_params,)*
+                        #(#foreign_key_params,)*
+                        #(#data_params,)*
+                    ) -> ProgramResult {
+                        #record_ident.create(#(#create_args,)* #(&#foreign_key_records,)*)
+                    }
+                })
+            }
+            DefaultInstruction::Delete(_) => {
+                let record_ident = Ident::new(&snake_name, Span::call_site());
+                Ok(syn::parse_quote! {
+                    fn #fn_ident<'a>(
+                        #record_ident: Mut<Record<'a, #struct_ident>>,
+                        #(#authority_params,)*
+                        receiver: Mut<Wallet<'a>>,
+                    ) -> ProgramResult {
+                        #record_ident.delete_with_authorities(vec![#(#authority_accounts,)*], receiver)
+                    }
+                })
+            }
+            DefaultInstruction::Update(_) => {
+                let record_ident = Ident::new(&snake_name, Span::call_site());
+                let check_foreign_keys = match foreign_keys.is_empty() {
+                    true => quote!(),
+                    false => quote! {
+                        #record_ident.self_account.data.check_foreign_keys(#(&#foreign_key_records,)*)?;
+                    },
+                };
+                let mut data_params = vec![];
+                let mut data_assignments = vec![];
+                for f in data_fields.iter() {
+                    let ident = named_field_ident(f)?;
+                    let ty = &f.ty;
+                    if primary_keys.iter().any(|(k, _)| k == ident)
+                        || authorities.contains(ident)
+                        || lookups.iter().any(|l| &l.ident == ident)
+                    {
+                        continue;
+                    }
+                    data_params.push(quote! { #ident: #ty });
+                    data_assignments
+                        .push(quote! { #record_ident.self_account.data.#ident = #ident; });
+                }
+                Ok(syn::parse_quote! {
+                    fn #fn_ident<'a>(
+                        mut #record_ident: Mut<Record<'a, #struct_ident>>,
+                        #(#authority_params,)*
+                        #(#foreign_key_params,)*
+                        payer: Signer<Wallet<'a>>,
+                        #(#data_params,)*
+                    ) -> ProgramResult {
+                        #record_ident.self_account.check_authorities(vec![#(#authority_accounts,)*])?;
+                        #(#data_assignments)*
+                        #check_foreign_keys
+                        #record_ident.update_with_payer(payer)
+                    }
+                })
+            }
         }
     }
 }
@@ -39,12 +178,13 @@ pub struct DefaultInstructionParser {
 impl DefaultInstructionParser {
     pub fn parse(attr: &Attribute, struct_name: &str) -> syn::Result<Self> {
         let mut instructions: Vec<DefaultInstruction> = vec![];
-        if let Ok(syn::Meta::List(ref meta_list)) = attr.parse_meta() {
+        if let syn::Meta::List(ref meta_list) = attr.parse_meta()? {
             for nested_meta in meta_list.nested.iter() {
                 instructions.push(DefaultInstruction::parse(nested_meta, struct_name)?)
             }
         } else {
-            return Err(syn_error(
+            return Err(syn::Error::new_spanned(
+                attr,
                 "Invalid format for `default_instructions` attribute",
             ));
         };
@@ -52,6 +192,45 @@ impl DefaultInstructionParser {
     }
 }
 
-fn syn_error(msg: &str) -> syn::Error {
-    syn::Error::new_spanned("default_instructions", msg)
+/// Builds the functions for every default instruction declared on a table with
+/// `#[default_instructions(..)]`.
+///
+/// Any default instruction whose function name was already declared by the
+/// user is skipped, so a user can always override one with their own logic.
+pub fn build_default_instruction_fns(
+    nautilus_objects: &Vec<NautilusObject>,
+    declared_functions: &[ItemFn],
+) -> syn::Result<Vec<ItemFn>> {
+    let mut default_instruction_fns = vec![];
+    for obj in nautilus_objects {
+        if let Some(NautilusObjectConfig::RecordConfig {
+            data_fields,
+            autoincrement_enabled,
+            primary_keys,
+            authorities,
+            lookups,
+            foreign_keys,
+            default_instructions,
+            ..
+        }) = &obj.object_config
+        {
+            for instruction in default_instructions {
+                let default_fn = instruction.to_item_fn(
+                    data_fields,
+                    *autoincrement_enabled,
+                    primary_keys,
+                    authorities,
+                    lookups,
+                    foreign_keys,
+                )?;
+                if !declared_functions
+                    .iter()
+                    .any(|f| f.sig.ident == default_fn.sig.ident)
+                {
+                    default_instruction_fns.push(default_fn);
+                }
+            }
+        }
+    }
+    Ok(default_instruction_fns)
 }
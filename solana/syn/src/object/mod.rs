@@ -4,16 +4,20 @@ pub mod default_instructions;
 pub mod parser;
 pub mod seeds;
 pub mod source;
+pub mod space;
+pub mod version;
 
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::{Ident, ItemEnum, ItemStruct};
 
-use crate::entry::required_account::RequiredAccount;
+use crate::entry::required_account::{Construct, ObjectType, RequiredAccount, RequiredAccountType};
 
 use self::{
     data::{
-        impl_borsh, impl_clone, impl_default, impl_nautilus_account_data, impl_nautilus_record_data,
+        impl_borsh, impl_clone, impl_default, impl_nautilus_account_data,
+        impl_nautilus_record_data, impl_nautilus_zero_copy_account_data,
+        impl_zero_copy_default_and_borsh,
     },
     parser::{parse_item_struct, NautilusObjectConfig},
 };
@@ -56,6 +60,8 @@ pub struct ObjectEntryConfig {
     pub is_create: bool,
     pub is_signer: bool,
     pub is_mut: bool,
+    pub is_optional: bool,
+    pub is_remaining: bool,
 }
 
 impl NautilusObject {
@@ -72,36 +78,100 @@ impl NautilusObject {
     /// don't require these configurations when comprising a list of types -
     /// both for the IDL and to compare from later. This is done in the
     /// entrypoint `#[nautilus]` macro.
-    pub fn from_item_struct(value: ItemStruct, nautilus_ty: NautilusObjectType) -> Self {
+    ///
+    /// Returns a `syn::Error` pointing at the offending tokens if the struct or
+    /// its attributes are malformed.
+    pub fn from_item_struct(
+        value: ItemStruct,
+        nautilus_ty: NautilusObjectType,
+    ) -> syn::Result<Self> {
         let ident = value.ident.clone();
-        let object_config = parse_item_struct(&value, nautilus_ty);
-        Self {
+        let object_config = parse_item_struct(&value, nautilus_ty)?;
+        Ok(Self {
             ident,
-            raw_type: NautilusObjectRawType::Struct(value.clone()),
+            raw_type: NautilusObjectRawType::Struct(value),
             entry_config: None,
-            object_config,
-        }
+            object_config: Some(object_config),
+        })
     }
 
     /// Resolve the required accounts for this object type based on its
     /// configurations and object type (`Record`, `Account`, `Mint`, etc.).
-    pub fn get_required_accounts(&self) -> (Vec<RequiredAccount>, Option<Vec<RequiredAccount>>) {
+    ///
+    /// A record of a table with its own counter requires that counter in place
+    /// of the `NautilusIndex`. A record being created or mutated also requires
+    /// the lookup accounts for its table's `#[index]` and `#[unique]` fields,
+    /// so they can be kept in sync.
+    ///
+    /// The accounts belonging to an object declared as `Option<..>` are marked
+    /// optional.
+    ///
+    /// Returns a `syn::Error` pointing at the object's type if it's invoked
+    /// before `entry_config` is set, or if the type isn't a Nautilus object.
+    pub fn get_required_accounts(
+        &self,
+    ) -> syn::Result<(Vec<RequiredAccount>, Option<Vec<RequiredAccount>>)> {
         match &self.entry_config {
-            Some(config) => RequiredAccount::resolve_accounts(
-                config.arg_ident.to_string(),
-                RequiredAccount::derive_object_type(
-                    &self.ident.to_string(),
+            Some(config) => {
+                let mut object_type = RequiredAccount::derive_object_type(
+                    &self.ident,
                     config.is_mut,
                     self.object_config.as_ref().map(|config| match config {
                         NautilusObjectConfig::RecordConfig { .. } => NautilusObjectType::Record,
                         NautilusObjectConfig::AccountConfig { .. } => NautilusObjectType::Account,
-                    })
-                ),
-                config.is_create,
-                config.is_signer,
-                config.is_mut,
-            ),
-            None => panic!("Error: `get_required_accounts` was invoked before setting the value for `entry_config`!"),
+                    }),
+                )?;
+                if let (
+                    ObjectType::Record(_, constructs),
+                    Some(NautilusObjectConfig::RecordConfig {
+                        table_name,
+                        table_counter,
+                        lookups,
+                        ..
+                    }),
+                ) = (&mut object_type, &self.object_config)
+                {
+                    if *table_counter {
+                        constructs.push(Construct::Counter(table_name.clone(), config.is_mut));
+                    }
+                    if config.is_mut {
+                        let obj_name = config.arg_ident.to_string();
+                        constructs.extend(lookups.iter().map(|l| {
+                            Construct::Lookup(
+                                format!("{}_{}_lookup", obj_name, l.ident),
+                                format!("Lookup account for `{}` of: {}", l.ident, obj_name),
+                                true,
+                            )
+                        }));
+                    }
+                }
+                let (mut read_accounts, create_accounts) = RequiredAccount::resolve_accounts(
+                    config.arg_ident.to_string(),
+                    object_type,
+                    config.is_create,
+                    config.is_signer,
+                    config.is_mut,
+                );
+                // Only the object's own accounts can be omitted. Programs and the
+                // `NautilusIndex` may be shared with other objects.
+                if config.is_optional {
+                    read_accounts
+                        .iter_mut()
+                        .filter(|r| {
+                            matches!(
+                                r.account_type,
+                                RequiredAccountType::Account(_)
+                                    | RequiredAccountType::CounterAccount(_)
+                            )
+                        })
+                        .for_each(|r| r.is_optional = true);
+                }
+                Ok((read_accounts, create_accounts))
+            }
+            None => Err(syn::Error::new_spanned(
+                &self.ident,
+                "`get_required_accounts` was invoked before setting the value for `entry_config`.",
+            )),
         }
     }
 }
@@ -122,32 +192,46 @@ impl From<&NautilusObject> for TokenStream {
         let ident = &ast.ident;
         let object_config = match &ast.object_config {
             Some(object_config) => object_config,
-            None => panic!(
-                "No object_config was derived for this Nautilus object: {}",
-                ident.to_string()
-            ),
+            None => {
+                return syn::Error::new_spanned(
+                    ident,
+                    format!(
+                        "No object_config was derived for this Nautilus object: {}",
+                        ident
+                    ),
+                )
+                .to_compile_error()
+            }
         };
         match object_config {
             NautilusObjectConfig::RecordConfig {
                 table_name,
                 data_fields,
                 autoincrement_enabled,
-                primary_key_ident,
-                primary_key_ty,
-                authorities: _,          // TODO: Add authority function creation
-                default_instructions: _, // TODO: Add default instructions to
+                table_counter: _, // Only used to resolve required accounts.
+                primary_keys,
+                authorities,
+                lookups,
+                foreign_keys,
+                default_instructions: _, // Built into functions by the `#[nautilus]` macro.
+                version,
+                space,
             } => {
                 let fields = &data_fields;
                 let impl_clone = impl_clone(ident, fields);
                 let impl_default = impl_default(ident, fields);
-                let impl_borsh = impl_borsh(ident, fields);
+                let impl_borsh = impl_borsh(ident, fields, table_name, version);
                 let impl_nautilus_record_data = impl_nautilus_record_data(
                     ident,
                     fields,
                     &table_name,
                     *autoincrement_enabled,
-                    &primary_key_ident,
-                    &primary_key_ty,
+                    primary_keys,
+                    authorities,
+                    lookups,
+                    foreign_keys,
+                    version,
+                    *space,
                 );
                 quote! {
                     #impl_clone
@@ -160,15 +244,40 @@ impl From<&NautilusObject> for TokenStream {
             NautilusObjectConfig::AccountConfig {
                 discrminator_str,
                 data_fields,
-                authorities: _, // TODO: Add authority function creation
+                authorities,
                 seeds,
+                version,
+                zero_copy,
+                space,
             } => {
                 let fields = &data_fields;
+                // A zero-copy struct derives `Clone` and `Copy` itself to be
+                // `bytemuck::Pod`.
+                if *zero_copy {
+                    let impl_default_and_borsh = impl_zero_copy_default_and_borsh(ident);
+                    let impl_nautilus_account_data = impl_nautilus_zero_copy_account_data(
+                        ident,
+                        discrminator_str,
+                        authorities,
+                        seeds,
+                    );
+                    return quote! {
+                        #impl_default_and_borsh
+                        #impl_nautilus_account_data
+                    };
+                }
                 let impl_clone = impl_clone(ident, fields);
                 let impl_default = impl_default(ident, fields);
-                let impl_borsh = impl_borsh(ident, fields);
-                let impl_nautilus_account_data =
-                    impl_nautilus_account_data(ident, fields, &discrminator_str, seeds);
+                let impl_borsh = impl_borsh(ident, fields, discrminator_str, version);
+                let impl_nautilus_account_data = impl_nautilus_account_data(
+                    ident,
+                    fields,
+                    discrminator_str,
+                    authorities,
+                    seeds,
+                    version,
+                    *space,
+                );
                 quote! {
                     #impl_clone
                     #impl_default
@@ -7,6 +7,8 @@ use crate::object::seeds::SeedParser;
 use super::{
     default_instructions::{DefaultInstruction, DefaultInstructionParser},
     seeds::Seed,
+    space::{parse_max_len, parse_space},
+    version::{parse_version, Version},
     NautilusObjectType,
 };
 
@@ -18,10 +20,14 @@ pub enum NautilusObjectConfig {
         table_name: String,
         data_fields: Fields,
         autoincrement_enabled: bool,
-        primary_key_ident: Ident,
-        primary_key_ty: Type,
+        table_counter: bool,
+        primary_keys: Vec<(Ident, Type)>,
         authorities: Vec<Ident>,
+        lookups: Vec<LookupField>,
+        foreign_keys: Vec<ForeignKeyField>,
         default_instructions: Vec<DefaultInstruction>,
+        version: Option<Version>,
+        space: Option<usize>,
     },
     /// Object configurations for an `Account<T>`.
     AccountConfig {
@@ -29,13 +35,39 @@ pub enum NautilusObjectConfig {
         data_fields: Fields,
         authorities: Vec<Ident>,
         seeds: Vec<Seed>,
+        version: Option<Version>,
+        zero_copy: bool,
+        space: Option<usize>,
     },
 }
 
+/// A field declared with `#[index]` or `#[unique]`, which is kept in a lookup
+/// account mapping the field's value to the records holding it.
+#[derive(Clone, Debug)]
+pub struct LookupField {
+    pub ident: Ident,
+    pub ty: Type,
+    pub is_unique: bool,
+}
+
+/// A field declared with `#[foreign_key(..)]`, which holds the primary key of a
+/// record in the referenced table.
+#[derive(Clone, Debug)]
+pub struct ForeignKeyField {
+    pub ident: Ident,
+    pub ty: Type,
+    pub references: Ident,
+}
+
 pub struct NautilusAccountFieldAttributes {
     pub is_primary_key: bool,
-    pub autoincrement_enabled: bool,
+    pub autoincrement: Option<bool>,
+    pub table_counter: bool,
     pub is_authority: bool,
+    pub is_index: bool,
+    pub is_unique: bool,
+    pub foreign_key: Option<Ident>,
+    pub max_len: Option<Vec<usize>>,
 }
 
 /// Parse out a `syn::ItemStruct` according to whichever type of Nautilus object
@@ -43,134 +75,449 @@ pub struct NautilusAccountFieldAttributes {
 pub fn parse_item_struct(
     item_struct: &ItemStruct,
     nautilus_ty: NautilusObjectType,
-) -> Option<NautilusObjectConfig> {
+) -> syn::Result<NautilusObjectConfig> {
     let ident_string = item_struct.ident.to_string();
     let discrminator_str = ident_string.clone().to_lowercase();
     let data_fields = item_struct.fields.clone();
+    let version = parse_version(&item_struct.attrs)?;
+
+    if !matches!(data_fields, Fields::Named(_)) {
+        return Err(syn::Error::new_spanned(
+            &item_struct.ident,
+            "Nautilus objects must be structs with named fields.",
+        ));
+    }
 
     match nautilus_ty {
         NautilusObjectType::Record => {
             let default_instructions =
-                parse_top_level_attributes_for_record(&ident_string, &item_struct.attrs);
+                parse_top_level_attributes_for_record(&ident_string, &item_struct.attrs)?;
 
-            let mut primary_key_ident_opt: Option<(Ident, Type)> = None;
-            let mut autoincrement_enabled: bool = true;
+            let mut primary_keys: Vec<(Ident, Type)> = vec![];
+            let mut autoincrement: Option<bool> = None;
+            let mut table_counter: bool = false;
+            // The first primary key declaring `autoincrement = true` or
+            // `counter = "table"`, if any.
+            let mut autoincrement_key: Option<Ident> = None;
             let mut authorities: Vec<Ident> = vec![];
+            let mut lookups: Vec<LookupField> = vec![];
+            let mut foreign_keys: Vec<ForeignKeyField> = vec![];
+            let mut max_lens: Vec<Option<Vec<usize>>> = vec![];
             let mut _optionized_struct_fields: Vec<(Ident, TokenStream, TokenStream)> = vec![];
 
             for f in data_fields.iter() {
-                let parsed_attributes = parse_field_attributes(&f);
-                if !parsed_attributes.autoincrement_enabled {
-                    autoincrement_enabled = parsed_attributes.autoincrement_enabled;
+                let field_ident = named_field_ident(f)?;
+                let parsed_attributes = parse_field_attributes(f)?;
+                if parsed_attributes.is_index || parsed_attributes.is_unique {
+                    if parsed_attributes.is_primary_key {
+                        return Err(syn::Error::new_spanned(
+                            field_ident,
+                            "The `#[primary_key]` already addresses records. Remove `#[index]` or `#[unique]`.",
+                        ));
+                    }
+                    check_seed_type(&f.ty, "Invalid index type!")?;
+                    lookups.push(LookupField {
+                        ident: field_ident.clone(),
+                        ty: f.ty.clone(),
+                        is_unique: parsed_attributes.is_unique,
+                    });
+                }
+                if let Some(references) = parsed_attributes.foreign_key {
+                    if parsed_attributes.is_primary_key {
+                        return Err(syn::Error::new_spanned(
+                            field_ident,
+                            "A `#[primary_key]` can't also be a `#[foreign_key(..)]`.",
+                        ));
+                    }
+                    check_seed_type(&f.ty, "Invalid foreign key type!")?;
+                    foreign_keys.push(ForeignKeyField {
+                        ident: field_ident.clone(),
+                        ty: f.ty.clone(),
+                        references,
+                    });
                 }
                 if parsed_attributes.is_primary_key {
-                    primary_key_ident_opt = Some((f.ident.clone().unwrap(), f.ty.clone()));
+                    check_seed_type(&f.ty, "Invalid primary key type!")?;
+                    if parsed_attributes.autoincrement.is_some() {
+                        autoincrement = parsed_attributes.autoincrement;
+                    }
+                    if parsed_attributes.table_counter {
+                        table_counter = true;
+                    }
+                    if autoincrement_key.is_none()
+                        && (parsed_attributes.autoincrement == Some(true)
+                            || parsed_attributes.table_counter)
+                    {
+                        autoincrement_key = Some(field_ident.clone());
+                    }
+                    primary_keys.push((field_ident.clone(), f.ty.clone()));
                 }
                 if parsed_attributes.is_authority {
-                    authorities.push(f.ident.clone().unwrap());
+                    authorities.push(field_ident.clone());
                 }
+                max_lens.push(parsed_attributes.max_len);
             }
 
-            let (primary_key_ident, primary_key_ty) = match primary_key_ident_opt {
-                Some((ident, ty)) => (ident, ty),
-                None => return None,
-            };
+            if primary_keys.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    &item_struct.ident,
+                    "A `Table` requires a field annotated with `#[primary_key]`.",
+                ));
+            }
+            // Composite primary keys are provided by the user, since there's no
+            // single counter to autoincrement.
+            if primary_keys.len() > 1 {
+                if let Some(key) = &autoincrement_key {
+                    return Err(syn::Error::new_spanned(
+                        key,
+                        "Autoincrement isn't supported with a composite primary key. Use `#[primary_key(autoincrement = false)]` or a single `#[primary_key]`.",
+                    ));
+                }
+            }
+            let autoincrement_enabled = primary_keys.len() == 1 && autoincrement.unwrap_or(true);
+            let space = parse_space(&data_fields, &max_lens, version.is_some())?;
 
-            Some(NautilusObjectConfig::RecordConfig {
+            Ok(NautilusObjectConfig::RecordConfig {
                 table_name: discrminator_str,
                 data_fields,
                 autoincrement_enabled,
-                primary_key_ident,
-                primary_key_ty,
+                table_counter,
+                primary_keys,
                 authorities,
+                lookups,
+                foreign_keys,
                 default_instructions,
+                version,
+                space,
             })
         }
         NautilusObjectType::Account => {
-            let seeds = parse_top_level_attributes_for_account(&item_struct.attrs);
+            let (seeds, zero_copy) = parse_top_level_attributes_for_account(&item_struct.attrs)?;
+            if zero_copy {
+                if version.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &item_struct.ident,
+                        "`#[version(..)]` isn't supported with `#[zero_copy]`, since zero-copy data has a fixed layout.",
+                    ));
+                }
+                if let Some(Seed::Field { ident }) =
+                    seeds.iter().find(|s| matches!(s, Seed::Field { .. }))
+                {
+                    return Err(syn::Error::new_spanned(
+                        ident,
+                        "A `#[zero_copy]` account is created with zeroed data, so its seeds can't come from its fields. Use a parameter seed instead.",
+                    ));
+                }
+            }
 
             let mut authorities: Vec<Ident> = vec![];
+            let mut max_lens: Vec<Option<Vec<usize>>> = vec![];
             let mut _optionized_struct_fields: Vec<(Ident, TokenStream, TokenStream)> = vec![];
 
             for f in data_fields.iter() {
-                let parsed_attributes = parse_field_attributes(&f);
+                let field_ident = named_field_ident(f)?;
+                let parsed_attributes = parse_field_attributes(f)?;
+                if parsed_attributes.is_primary_key {
+                    return Err(syn::Error::new_spanned(
+                        field_ident,
+                        "`#[primary_key]` is only supported on a `Table`.",
+                    ));
+                }
+                if zero_copy && parsed_attributes.max_len.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        field_ident,
+                        "`#[max_len(..)]` isn't supported with `#[zero_copy]`, since zero-copy data has a fixed layout.",
+                    ));
+                }
+                if parsed_attributes.is_index || parsed_attributes.is_unique {
+                    return Err(syn::Error::new_spanned(
+                        field_ident,
+                        "`#[index]` and `#[unique]` are only supported on a `Table`.",
+                    ));
+                }
+                if parsed_attributes.foreign_key.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        field_ident,
+                        "`#[foreign_key(..)]` is only supported on a `Table`.",
+                    ));
+                }
                 if parsed_attributes.is_authority {
-                    authorities.push(f.ident.clone().unwrap());
+                    authorities.push(field_ident.clone());
                 }
+                max_lens.push(parsed_attributes.max_len);
             }
+            // A zero-copy account's space is the size of its struct, which is
+            // known by `ZeroCopyAccount<T>` itself.
+            let space = match zero_copy {
+                true => None,
+                false => parse_space(&data_fields, &max_lens, version.is_some())?,
+            };
 
-            Some(NautilusObjectConfig::AccountConfig {
+            Ok(NautilusObjectConfig::AccountConfig {
                 discrminator_str,
                 data_fields,
                 authorities,
                 seeds,
+                version,
+                zero_copy,
+                space,
             })
         }
     }
 }
 
-/// Parses the field attributes of the struct, such as `#[authority]` and
-/// `#[primary_key(..)]`.
-pub fn parse_field_attributes(field: &syn::Field) -> NautilusAccountFieldAttributes {
+/// Parses the field attributes of the struct, such as `#[authority]`,
+/// `#[primary_key(..)]`, `#[index]`, `#[unique]`, `#[foreign_key(..)]` and
+/// `#[max_len(..)]`.
+pub fn parse_field_attributes(field: &syn::Field) -> syn::Result<NautilusAccountFieldAttributes> {
     let mut is_primary_key = false;
-    let mut autoincrement_enabled = true;
+    let mut autoincrement = None;
+    let mut table_counter = false;
     let mut is_authority = false;
+    let mut is_index = false;
+    let mut is_unique = false;
+    let mut foreign_key = None;
+    let mut max_len = None;
     for attr in field.attrs.iter() {
-        if let Ok(syn::Meta::List(meta_list)) = attr.parse_meta() {
-            if meta_list.path.is_ident("primary_key") {
-                is_primary_key = true;
-                for nested_meta in &meta_list.nested {
-                    if let syn::NestedMeta::Meta(syn::Meta::NameValue(meta_name_value)) =
-                        nested_meta
-                    {
-                        if meta_name_value.path.is_ident("autoincrement") {
-                            if let syn::Lit::Bool(lit_bool) = &meta_name_value.lit {
-                                autoincrement_enabled = lit_bool.value();
+        if attr.path.is_ident("primary_key") {
+            is_primary_key = true;
+            match attr.parse_meta()? {
+                syn::Meta::Path(_) => (),
+                syn::Meta::List(meta_list) => {
+                    for nested_meta in &meta_list.nested {
+                        match nested_meta {
+                            syn::NestedMeta::Meta(syn::Meta::NameValue(meta_name_value))
+                                if meta_name_value.path.is_ident("autoincrement") =>
+                            {
+                                match &meta_name_value.lit {
+                                    syn::Lit::Bool(lit_bool) => {
+                                        autoincrement = Some(lit_bool.value())
+                                    }
+                                    lit => {
+                                        return Err(syn::Error::new_spanned(
+                                            lit,
+                                            "Expected `autoincrement = true` or `autoincrement = false`.",
+                                        ))
+                                    }
+                                }
+                            }
+                            syn::NestedMeta::Meta(syn::Meta::NameValue(meta_name_value))
+                                if meta_name_value.path.is_ident("counter") =>
+                            {
+                                match &meta_name_value.lit {
+                                    syn::Lit::Str(lit_str) if lit_str.value() == "table" => {
+                                        table_counter = true
+                                    }
+                                    syn::Lit::Str(lit_str) if lit_str.value() == "global" => {
+                                        table_counter = false
+                                    }
+                                    lit => {
+                                        return Err(syn::Error::new_spanned(
+                                            lit,
+                                            "Expected `counter = \"global\"` or `counter = \"table\"`.",
+                                        ))
+                                    }
+                                }
+                            }
+                            _ => {
+                                return Err(syn::Error::new_spanned(
+                                    nested_meta,
+                                    "Unknown argument. Expected `autoincrement = <bool>` or `counter = \"global\" | \"table\"`.",
+                                ))
                             }
                         }
                     }
+                    if table_counter && autoincrement == Some(false) {
+                        return Err(syn::Error::new_spanned(
+                            &meta_list,
+                            "`counter = \"table\"` requires `autoincrement = true`.",
+                        ));
+                    }
+                }
+                meta => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "Invalid format for `primary_key` attribute.",
+                    ))
                 }
             }
-        } else if attr.path.is_ident("primary_key") {
-            is_primary_key = true;
         } else if attr.path.is_ident("authority") {
+            if !attr.tokens.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    &attr.tokens,
+                    "`#[authority]` doesn't take any arguments.",
+                ));
+            }
             is_authority = true;
+        } else if attr.path.is_ident("index") || attr.path.is_ident("unique") {
+            if !attr.tokens.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    &attr.tokens,
+                    "`#[index]` and `#[unique]` don't take any arguments.",
+                ));
+            }
+            match attr.path.is_ident("unique") {
+                true => is_unique = true,
+                false => is_index = true,
+            }
+        } else if attr.path.is_ident("foreign_key") {
+            foreign_key = Some(attr.parse_args::<Ident>().map_err(|_| {
+                syn::Error::new_spanned(
+                    attr,
+                    "Expected the referenced table, such as `#[foreign_key(Person)]`.",
+                )
+            })?);
+        } else if attr.path.is_ident("max_len") {
+            max_len = Some(parse_max_len(attr)?);
         }
     }
-    NautilusAccountFieldAttributes {
+    Ok(NautilusAccountFieldAttributes {
         is_primary_key,
-        autoincrement_enabled,
+        autoincrement,
+        table_counter,
         is_authority,
-    }
+        is_index,
+        is_unique,
+        foreign_key,
+        max_len,
+    })
 }
 
 /// Attempts to parse the top-level macro attributes for
 /// `#[derive(nautilus::Table)]`, such as `#[default_instructions(..)]`.
 pub fn parse_top_level_attributes_for_record(
     struct_name: &str,
-    attrs: &Vec<syn::Attribute>,
-) -> Vec<DefaultInstruction> {
+    attrs: &[syn::Attribute],
+) -> syn::Result<Vec<DefaultInstruction>> {
     let mut default_instructions = Vec::new();
     for attr in attrs.iter() {
         if attr.path.is_ident("default_instructions") {
-            let mut parsed_instructions = DefaultInstructionParser::parse(attr, struct_name)
-                .expect("Invalid format for `default_instructions` attribute");
+            let mut parsed_instructions = DefaultInstructionParser::parse(attr, struct_name)?;
             default_instructions.append(&mut parsed_instructions.instructions);
         }
     }
-    default_instructions
+    Ok(default_instructions)
 }
 
 /// Attempts to parse the top-level macro attributes for
-/// `#[derive(nautilus::State)]`, such as `#[seeds(..)]`.
-pub fn parse_top_level_attributes_for_account(attrs: &Vec<syn::Attribute>) -> Vec<Seed> {
+/// `#[derive(nautilus::State)]`, such as `#[seeds(..)]` and `#[zero_copy]`.
+pub fn parse_top_level_attributes_for_account(
+    attrs: &[syn::Attribute],
+) -> syn::Result<(Vec<Seed>, bool)> {
     let mut seeds = Vec::new();
+    let mut zero_copy = false;
     for attr in attrs.iter() {
         if attr.path.is_ident("seeds") {
-            let mut parsed_seeds: SeedParser =
-                syn::parse2(attr.tokens.clone()).expect("Invalid format for `seeds` attribute");
+            let mut parsed_seeds: SeedParser = syn::parse2(attr.tokens.clone())?;
             seeds.append(&mut parsed_seeds.seeds);
+        } else if attr.path.is_ident("zero_copy") {
+            zero_copy = true;
+        };
+    }
+    Ok((seeds, zero_copy))
+}
+
+/// Returns the identifier of a named field.
+pub fn named_field_ident(field: &syn::Field) -> syn::Result<&Ident> {
+    field
+        .ident
+        .as_ref()
+        .ok_or_else(|| syn::Error::new_spanned(field, "Expected named field."))
+}
+
+/// Checks that a primary key's or indexed field's type is one Nautilus can
+/// derive PDA seeds from.
+fn check_seed_type(ty: &Type, message: &str) -> syn::Result<()> {
+    match quote::quote!(#ty).to_string().as_str() {
+        "String" | "u8" | "u16" | "u32" | "u64" | "Pubkey" => Ok(()),
+        _ => Err(syn::Error::new_spanned(
+            ty,
+            format!(
+                "{} Only `String`, `u8`, `u16`, `u32`, `u64`, and `Pubkey` are supported.",
+                message
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn parse_table(item_struct: ItemStruct) -> syn::Result<NautilusObjectConfig> {
+        parse_item_struct(&item_struct, NautilusObjectType::Record)
+    }
+
+    fn autoincrement_enabled(config: NautilusObjectConfig) -> bool {
+        match config {
+            NautilusObjectConfig::RecordConfig {
+                autoincrement_enabled,
+                ..
+            } => autoincrement_enabled,
+            _ => panic!("Expected a record config."),
+        }
+    }
+
+    #[test]
+    fn rejects_autoincrement_on_composite_primary_key() {
+        let first: ItemStruct = parse_quote! {
+            struct Membership {
+                #[primary_key(autoincrement = true)]
+                group: u32,
+                #[primary_key(autoincrement = false)]
+                member: Pubkey,
+            }
+        };
+        let last: ItemStruct = parse_quote! {
+            struct Membership {
+                #[primary_key(autoincrement = false)]
+                group: u32,
+                #[primary_key(counter = "table")]
+                member: u32,
+            }
+        };
+        for item_struct in [first, last] {
+            let err = parse_table(item_struct).unwrap_err();
+            assert!(err
+                .to_string()
+                .starts_with("Autoincrement isn't supported with a composite primary key."));
+        }
+    }
+
+    #[test]
+    fn composite_primary_key_disables_autoincrement() {
+        let item_struct: ItemStruct = parse_quote! {
+            struct Membership {
+                #[primary_key]
+                group: u32,
+                #[primary_key]
+                member: Pubkey,
+            }
+        };
+        assert!(!autoincrement_enabled(parse_table(item_struct).unwrap()));
+    }
+
+    #[test]
+    fn single_primary_key_autoincrements_by_default() {
+        let item_struct: ItemStruct = parse_quote! {
+            struct Person {
+                #[primary_key]
+                id: u32,
+                name: String,
+            }
+        };
+        assert!(autoincrement_enabled(parse_table(item_struct).unwrap()));
+    }
+
+    #[test]
+    fn rejects_missing_primary_key() {
+        let item_struct: ItemStruct = parse_quote! {
+            struct Person {
+                name: String,
+            }
         };
+        assert!(parse_table(item_struct).is_err());
     }
-    seeds
 }
@@ -67,7 +67,7 @@ fn source_struct(name: &str, source_fields: Vec<SourceField>) -> ItemStruct {
 
 /// Uses helpers to return a vector of all Nautilus objects from Nautilus's
 /// `src/objects/.` as `syn::ItemStruct` types.
-pub fn source_nautilus_objects() -> Vec<NautilusObject> {
+pub fn source_nautilus_objects() -> syn::Result<Vec<NautilusObject>> {
     [
         source_struct(
             "NautilusIndex",
@@ -81,6 +81,10 @@ pub fn source_nautilus_objects() -> Vec<NautilusObject> {
             "Mint",
             vec![SourceField::AccountInfo, SourceField::TokenProgram],
         ),
+        source_struct(
+            "Mint2022",
+            vec![SourceField::AccountInfo, SourceField::TokenProgram],
+        ),
         source_struct(
             "Metadata",
             vec![SourceField::AccountInfo, SourceField::TokenMetadataProgram],
@@ -93,6 +97,14 @@ pub fn source_nautilus_objects() -> Vec<NautilusObject> {
                 SourceField::AssociatedTokenProgram,
             ],
         ),
+        source_struct(
+            "AssociatedTokenAccount2022",
+            vec![
+                SourceField::AccountInfo,
+                SourceField::TokenProgram,
+                SourceField::AssociatedTokenProgram,
+            ],
+        ),
         source_struct(
             "Token",
             vec![
@@ -102,6 +114,15 @@ pub fn source_nautilus_objects() -> Vec<NautilusObject> {
                 SourceField::TokenMetadataProgram,
             ],
         ),
+        source_struct(
+            "Token2022",
+            vec![
+                SourceField::AccountInfo,
+                SourceField::Metadata,
+                SourceField::TokenProgram,
+                SourceField::TokenMetadataProgram,
+            ],
+        ),
         source_struct(
             "Nft",
             vec![
@@ -111,6 +132,10 @@ pub fn source_nautilus_objects() -> Vec<NautilusObject> {
                 SourceField::TokenMetadataProgram,
             ],
         ),
+        source_struct("Clock", vec![]),
+        source_struct("Instructions", vec![SourceField::AccountInfo]),
+        source_struct("Rent", vec![]),
+        source_struct("SlotHashes", vec![SourceField::AccountInfo]),
     ]
     .into_iter()
     .map(|s| NautilusObject::from_item_struct(s, super::NautilusObjectType::Account))
@@ -124,9 +149,16 @@ pub fn source_nautilus_names() -> Vec<String> {
         "NautilusIndex".to_string(),
         "Wallet".to_string(),
         "Mint".to_string(),
+        "Mint2022".to_string(),
         "Metadata".to_string(),
         "AssociatedTokenAccount".to_string(),
+        "AssociatedTokenAccount2022".to_string(),
         "Token".to_string(),
+        "Token2022".to_string(),
         "Nft".to_string(),
+        "Clock".to_string(),
+        "Instructions".to_string(),
+        "Rent".to_string(),
+        "SlotHashes".to_string(),
     ]
 }
@@ -0,0 +1,249 @@
+//! Computes the static space of a Nautilus object's data, with its dynamic
+//! fields bounded by `#[max_len(..)]`.
+use syn::{punctuated::Punctuated, token::Comma, Attribute, Fields, GenericArgument, LitInt, Type};
+
+/// Parses the lengths of a `#[max_len(..)]` attribute.
+///
+/// A `String` or `Vec<T>` takes one length each, from the outermost type in:
+/// `#[max_len(10, 32)]` on a `Vec<String>` holds up to 10 strings of up to 32
+/// bytes.
+pub fn parse_max_len(attr: &Attribute) -> syn::Result<Vec<usize>> {
+    let lens = attr
+        .parse_args_with(Punctuated::<LitInt, Comma>::parse_terminated)
+        .map_err(|_| {
+            syn::Error::new_spanned(
+                attr,
+                "Expected one or more lengths, such as `#[max_len(32)]`.",
+            )
+        })?;
+    if lens.is_empty() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "Expected one or more lengths, such as `#[max_len(32)]`.",
+        ));
+    }
+    lens.iter().map(|l| l.base10_parse()).collect()
+}
+
+/// Computes the space of an object's serialized data: the discriminator, the
+/// version byte if it's versioned, and the maximum size of every field.
+///
+/// Returns `None` if any field has no maximum size - such as a `String`
+/// without `#[max_len(..)]` - in which case the object's space is the size of
+/// its current data. Once any field declares `#[max_len(..)]`, every field
+/// must have a maximum size.
+pub fn parse_space(
+    fields: &Fields,
+    max_lens: &Vec<Option<Vec<usize>>>,
+    versioned: bool,
+) -> syn::Result<Option<usize>> {
+    let bounded = max_lens.iter().any(Option::is_some);
+    let mut space = 8 + versioned as usize;
+    for (f, max_len) in fields.iter().zip(max_lens) {
+        let mut lens = max_len.iter().flatten().copied();
+        let field_space = type_space(&f.ty, &mut lens);
+        if lens.next().is_some() {
+            return Err(syn::Error::new_spanned(
+                &f.ty,
+                "`#[max_len(..)]` declares more lengths than this type has `String` or `Vec` types.",
+            ));
+        }
+        match field_space {
+            Some(field_space) => space += field_space,
+            None if bounded => {
+                return Err(syn::Error::new_spanned(
+                    &f.ty,
+                    "This field has no maximum size. Declare one with `#[max_len(..)]`, or remove `#[max_len(..)]` from every field.",
+                ))
+            }
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(space))
+}
+
+/// The maximum Borsh-serialized size of a type, taking the lengths of any
+/// `String` or `Vec` from `lens`.
+fn type_space(ty: &Type, lens: &mut impl Iterator<Item = usize>) -> Option<usize> {
+    match ty {
+        Type::Array(array) => {
+            let len: usize = match &array.len {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(len),
+                    ..
+                }) => len.base10_parse().ok()?,
+                _ => return None,
+            };
+            Some(len * type_space(&array.elem, lens)?)
+        }
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            match segment.ident.to_string().as_str() {
+                "u8" | "i8" | "bool" => Some(1),
+                "u16" | "i16" => Some(2),
+                "u32" | "i32" | "f32" => Some(4),
+                "u64" | "i64" | "f64" => Some(8),
+                "u128" | "i128" => Some(16),
+                "Pubkey" => Some(32),
+                "String" => Some(4 + lens.next()?),
+                "Vec" => {
+                    let len = lens.next()?;
+                    Some(4 + len * type_space(generic_arg(&segment.arguments)?, lens)?)
+                }
+                "Option" => Some(1 + type_space(generic_arg(&segment.arguments)?, lens)?),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The first generic type argument of a path segment, such as `T` in `Vec<T>`.
+fn generic_arg(arguments: &syn::PathArguments) -> Option<&Type> {
+    match arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, ItemStruct};
+
+    use super::*;
+
+    /// Computes the space of a struct's fields from their `#[max_len(..)]`
+    /// attributes.
+    fn space_of(item_struct: ItemStruct, versioned: bool) -> syn::Result<Option<usize>> {
+        let max_lens = item_struct
+            .fields
+            .iter()
+            .map(|f| {
+                f.attrs
+                    .iter()
+                    .find(|attr| attr.path.is_ident("max_len"))
+                    .map(parse_max_len)
+                    .transpose()
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        parse_space(&item_struct.fields, &max_lens, versioned)
+    }
+
+    #[test]
+    fn parses_lengths() {
+        let attr: Attribute = parse_quote!(#[max_len(10, 32)]);
+        assert_eq!(parse_max_len(&attr).unwrap(), vec![10, 32]);
+    }
+
+    #[test]
+    fn rejects_malformed_max_len() {
+        let attrs: [Attribute; 4] = [
+            parse_quote!(#[max_len]),
+            parse_quote!(#[max_len()]),
+            parse_quote!(#[max_len("32")]),
+            parse_quote!(#[max_len(len = 32)]),
+        ];
+        for attr in attrs.iter() {
+            assert!(parse_max_len(attr).is_err());
+        }
+    }
+
+    #[test]
+    fn computes_bounded_space() {
+        let space = space_of(
+            parse_quote! {
+                struct Person {
+                    id: u32,
+                    #[max_len(16)]
+                    name: String,
+                    #[max_len(4, 8)]
+                    tags: Vec<String>,
+                    friend: Option<Pubkey>,
+                    scores: [u16; 3],
+                }
+            },
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            space,
+            Some(8 + 4 + (4 + 16) + (4 + 4 * (4 + 8)) + (1 + 32) + 6)
+        );
+    }
+
+    #[test]
+    fn versioned_space_includes_version_byte() {
+        let space = space_of(
+            parse_quote! {
+                struct Person {
+                    #[max_len(16)]
+                    name: String,
+                }
+            },
+            true,
+        )
+        .unwrap();
+        assert_eq!(space, Some(8 + 1 + 4 + 16));
+    }
+
+    #[test]
+    fn unbounded_struct_has_no_space() {
+        let space = space_of(
+            parse_quote! {
+                struct Person {
+                    id: u32,
+                    name: String,
+                }
+            },
+            false,
+        )
+        .unwrap();
+        assert_eq!(space, None);
+    }
+
+    #[test]
+    fn rejects_unbounded_field_next_to_bounded_field() {
+        assert!(space_of(
+            parse_quote! {
+                struct Person {
+                    #[max_len(16)]
+                    name: String,
+                    bio: String,
+                }
+            },
+            false,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_extra_lengths() {
+        assert!(space_of(
+            parse_quote! {
+                struct Person {
+                    #[max_len(16, 8)]
+                    name: String,
+                }
+            },
+            false,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_max_len_on_fixed_size_field() {
+        assert!(space_of(
+            parse_quote! {
+                struct Person {
+                    #[max_len(16)]
+                    id: u32,
+                }
+            },
+            false,
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,316 @@
+//! Parses and builds the schema versioning of a Nautilus object, declared with
+//! `#[version(..)]` and `#[migrate(..)]`.
+use convert_case::{Case, Casing};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Attribute, Fields, Ident, ItemFn, LitInt, Type,
+};
+
+use super::{parser::NautilusObjectConfig, NautilusObject};
+
+/// The schema version of an object's data, along with the older layouts it can
+/// be migrated from.
+///
+/// A versioned object's data begins with its versioned discriminator, distinct
+/// from its unversioned one, followed by its `current` version as a single
+/// byte.
+#[derive(Clone, Debug)]
+pub struct Version {
+    pub current: u8,
+    pub migrations: Vec<Migration>,
+}
+
+/// An older layout of an object's data, declared with
+/// `#[migrate(from = N, layout = T)]`.
+///
+/// The layout `T` must implement `BorshDeserialize`, and the object must
+/// implement `From<T>` to convert it into the current layout. Version `0` is
+/// the layout from before the object was versioned, which begins with the
+/// object's unversioned discriminator and has no version byte.
+#[derive(Clone, Debug)]
+pub struct Migration {
+    pub from: u8,
+    pub layout: Type,
+}
+
+impl Parse for Migration {
+    /// Parses the arguments of a `#[migrate(from = N, layout = T)]` attribute.
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut from: Option<u8> = None;
+        let mut layout: Option<Type> = None;
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            match key.to_string().as_str() {
+                "from" => from = Some(input.parse::<LitInt>()?.base10_parse()?),
+                "layout" => layout = Some(input.parse()?),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        key,
+                        "Invalid `migrate` argument. Expected `from` or `layout`.",
+                    ))
+                }
+            }
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+        match (from, layout) {
+            (Some(from), Some(layout)) => Ok(Migration { from, layout }),
+            _ => Err(input.error("`migrate` requires both `from = ..` and `layout = ..`.")),
+        }
+    }
+}
+
+/// Parses the `#[version(..)]` and `#[migrate(..)]` attributes of a struct.
+pub fn parse_version(attrs: &[Attribute]) -> syn::Result<Option<Version>> {
+    let mut current: Option<u8> = None;
+    let mut migrations: Vec<Migration> = vec![];
+    for attr in attrs.iter() {
+        if attr.path.is_ident("version") {
+            current = Some(attr.parse_args::<LitInt>()?.base10_parse()?);
+        } else if attr.path.is_ident("migrate") {
+            let migration: Migration = attr.parse_args()?;
+            if migrations.iter().any(|m| m.from == migration.from) {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    format!(
+                        "A migration from version {} is already declared.",
+                        migration.from
+                    ),
+                ));
+            }
+            migrations.push(migration);
+        }
+    }
+    match current {
+        Some(current) => {
+            if current == 0 {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "Version `0` is reserved for data from before the object was versioned. Start at `#[version(1)]`.",
+                ));
+            }
+            if let Some(m) = migrations.iter().find(|m| m.from >= current) {
+                return Err(syn::Error::new_spanned(
+                    &m.layout,
+                    format!("Can only migrate from a version older than {}.", current),
+                ));
+            }
+            Ok(Some(Version {
+                current,
+                migrations,
+            }))
+        }
+        None => match migrations.first() {
+            Some(m) => Err(syn::Error::new_spanned(
+                &m.layout,
+                "`#[migrate(..)]` requires a `#[version(..)]`.",
+            )),
+            None => Ok(None),
+        },
+    }
+}
+
+/// Builds the body of a versioned object's `BorshDeserialize` implementation.
+///
+/// Versioned data begins with the object's versioned discriminator followed by
+/// its version. Data in the current version is deserialized as-is, while data
+/// in an older version is deserialized with its declared layout and converted
+/// with `From`. If a migration from version `0` is declared, data beginning
+/// with the object's unversioned discriminator is read as the original,
+/// unversioned layout.
+pub fn build_tokens_versioned_deserialize(
+    version: &Version,
+    fields: &Fields,
+    discrim_str: &str,
+) -> TokenStream {
+    let current = version.current;
+    let borsh_deser_impl = fields.iter().map(|f| {
+        let field_name = f.ident.clone();
+        quote! { #field_name: nautilus::borsh::BorshDeserialize::deserialize(buf)? }
+    });
+    let migrations = version.migrations.iter().filter(|m| m.from != 0).map(|m| {
+        let from = m.from;
+        let layout = &m.layout;
+        quote! {
+            #from => <#layout as nautilus::borsh::BorshDeserialize>::deserialize(buf).map(Self::from)
+        }
+    });
+    let unversioned = match version.migrations.iter().find(|m| m.from == 0) {
+        Some(m) => {
+            let layout = &m.layout;
+            quote! {
+                if discrim == nautilus::discriminator(#discrim_str) {
+                    return <#layout as nautilus::borsh::BorshDeserialize>::deserialize(buf).map(Self::from);
+                }
+            }
+        }
+        None => quote!(),
+    };
+    quote! {
+        let discrim: [u8; 8] = nautilus::borsh::BorshDeserialize::deserialize(buf)?;
+        if discrim == nautilus::versioned_discriminator(#discrim_str) {
+            return match <u8 as nautilus::borsh::BorshDeserialize>::deserialize(buf)? {
+                #current => Ok(Self {
+                    #(#borsh_deser_impl,)*
+                }),
+                #(#migrations,)*
+                version => Err(nautilus::borsh::maybestd::io::Error::new(
+                    nautilus::borsh::maybestd::io::ErrorKind::InvalidData,
+                    format!("Unknown account data version: {}", version),
+                )),
+            };
+        }
+        #unversioned
+        Err(nautilus::borsh::maybestd::io::Error::new(
+            nautilus::borsh::maybestd::io::ErrorKind::InvalidData,
+            "Unknown account data discriminator",
+        ))
+    }
+}
+
+/// Builds the `VERSIONED` and `MIGRATES_UNVERSIONED` constants of a versioned
+/// object's `NautilusRecordData` or `NautilusAccountData` implementation.
+pub fn build_tokens_version_consts(version: &Option<Version>) -> TokenStream {
+    match version {
+        Some(version) => {
+            let migrates_unversioned = version.migrations.iter().any(|m| m.from == 0);
+            quote! {
+                const VERSIONED: bool = true;
+
+                const MIGRATES_UNVERSIONED: bool = #migrates_unversioned;
+            }
+        }
+        None => quote!(),
+    }
+}
+
+/// Builds the `migrate_<object>` instruction for every versioned object.
+///
+/// The instruction rewrites the account in the object's current layout,
+/// reallocating it and topping up or refunding its rent with the payer. Since
+/// an account is migrated in memory whenever it's loaded, and written back in
+/// its current layout the next time it's updated, this is only needed to
+/// migrate accounts ahead of time, such as in bulk after an upgrade.
+///
+/// Any migrate instruction whose function name was already declared by the user
+/// is skipped.
+pub fn build_migrate_instruction_fns(
+    nautilus_objects: &Vec<NautilusObject>,
+    declared_functions: &[ItemFn],
+) -> Vec<ItemFn> {
+    let mut migrate_fns = vec![];
+    for obj in nautilus_objects {
+        let (version, wrapper) = match &obj.object_config {
+            Some(NautilusObjectConfig::RecordConfig { version, .. }) => (version, quote!(Record)),
+            Some(NautilusObjectConfig::AccountConfig { version, .. }) => (version, quote!(Account)),
+            None => continue,
+        };
+        if version.is_none() {
+            continue;
+        }
+        let struct_ident = &obj.ident;
+        let snake_name = struct_ident.to_string().to_case(Case::Snake);
+        let fn_ident = Ident::new(&format!("migrate_{}", snake_name), Span::call_site());
+        let account_ident = Ident::new(&snake_name, Span::call_site());
+        if declared_functions.iter().any(|f| f.sig.ident == fn_ident) {
+            continue;
+        }
+        migrate_fns.push(syn::parse_quote! {
+            fn #fn_ident<'a>(
+                #account_ident: Mut<#wrapper<'a, #struct_ident>>,
+                payer: Signer<Wallet<'a>>,
+            ) -> ProgramResult {
+                #account_ident.update_with_payer(payer)
+            }
+        });
+    }
+    migrate_fns
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, ItemStruct};
+
+    use super::*;
+
+    fn version_of(item_struct: ItemStruct) -> syn::Result<Option<Version>> {
+        parse_version(&item_struct.attrs)
+    }
+
+    #[test]
+    fn parses_version_and_migrations() {
+        let version = version_of(parse_quote! {
+            #[version(2)]
+            #[migrate(from = 0, layout = PersonV0)]
+            #[migrate(from = 1, layout = PersonV1)]
+            struct Person {}
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(version.current, 2);
+        let froms: Vec<u8> = version.migrations.iter().map(|m| m.from).collect();
+        assert_eq!(froms, vec![0, 1]);
+    }
+
+    #[test]
+    fn unversioned_struct_has_no_version() {
+        assert!(version_of(parse_quote!(
+            struct Person {}
+        ))
+        .unwrap()
+        .is_none());
+    }
+
+    #[test]
+    fn rejects_version_zero() {
+        assert!(version_of(parse_quote! {
+            #[version(0)]
+            struct Person {}
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_migration_from_current_or_newer_version() {
+        assert!(version_of(parse_quote! {
+            #[version(2)]
+            #[migrate(from = 2, layout = PersonV2)]
+            struct Person {}
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_migration() {
+        assert!(version_of(parse_quote! {
+            #[version(2)]
+            #[migrate(from = 1, layout = PersonV1)]
+            #[migrate(from = 1, layout = OtherPersonV1)]
+            struct Person {}
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_migration_without_version() {
+        assert!(version_of(parse_quote! {
+            #[migrate(from = 0, layout = PersonV0)]
+            struct Person {}
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_incomplete_migration() {
+        assert!(version_of(parse_quote! {
+            #[version(1)]
+            #[migrate(from = 0)]
+            struct Person {}
+        })
+        .is_err());
+    }
+}
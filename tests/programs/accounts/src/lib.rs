@@ -52,6 +52,33 @@ mod program_nautilus {
         Ok(())
     }
 
+    fn update_home<'a>(
+        mut home: Mut<Account<'a, Home>>,
+        payer: Signer<Wallet<'a>>,
+        street: String,
+    ) -> ProgramResult {
+        info!(" * New Street: {}", &street);
+        //
+        // /* Business Logic */
+        //
+        home.self_account.data.street = street;
+        home.update_with_payer(payer)?;
+        //
+        home.self_account.print();
+        Ok(())
+    }
+
+    fn delete_home<'a>(home: Mut<Account<'a, Home>>, receiver: Mut<Wallet<'a>>) -> ProgramResult {
+        home.self_account.print();
+        info!(" * Receiver: {}", &receiver.key());
+        //
+        // /* Business Logic */
+        //
+        home.delete(receiver)?;
+        //
+        Ok(())
+    }
+
     fn create_car<'a>(
         mut new_car: Create<'a, Account<'a, Car>>,
         make: String,
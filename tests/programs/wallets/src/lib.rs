@@ -2,6 +2,8 @@
 use nautilus::splogger::{info, Splog};
 use nautilus::*;
 
+declare_program!("idls/treasury.json");
+
 #[nautilus]
 mod program_nautilus {
 
@@ -139,6 +141,54 @@ mod program_nautilus {
         //
         Ok(())
     }
+
+    /// Opens a vault in the `treasury` program, declared from its IDL.
+    fn open_treasury_vault<'a>(
+        authority: Signer<Wallet<'a>>,
+        vault: Create<'a, Wallet<'a>>,
+        index: Mut<Wallet<'a>>,
+        label: String,
+    ) -> ProgramResult {
+        let data = treasury::Vault {
+            id: 0,
+            authority: *authority.key(),
+            label,
+        };
+        treasury::open_vault(
+            &treasury::ID,
+            vault.clone(),
+            authority,
+            index,
+            vault.rent.clone(),
+            None::<Wallet>,
+            data,
+            treasury::Tier::Basic,
+            &[],
+        )
+    }
+
+    /// Sweeps the `treasury` program's tokens to a set of recipients.
+    fn sweep_treasury<'a>(
+        authority: Signer<Wallet<'a>>,
+        mint: Mint<'a>,
+        recipients: Remaining<'a, Mut<Wallet<'a>>>,
+        amount: u64,
+    ) -> ProgramResult {
+        let settings = treasury::Settings {
+            fee_basis_points: 50,
+            tier: treasury::Tier::Premium,
+        };
+        treasury::sweep(
+            &treasury::ID,
+            authority,
+            mint.clone(),
+            mint.token_program.clone(),
+            &recipients,
+            settings,
+            amount,
+            &[],
+        )
+    }
 }
 
 fn print_wallet_details<'a>(wallet: &impl NautilusAccountInfo<'a>, desc: &str) {
@@ -101,4 +101,72 @@ export function createReadHomeInstruction(
             {pubkey: account, isSigner: false, isWritable: false},
         ],
     )
+}
+
+class UpdateHomeInstructionData {
+    instruction: MyInstructions
+    street: string
+    constructor(props: {
+        instruction: MyInstructions,
+        street: string,
+    }) {
+        this.instruction = props.instruction
+        this.street = props.street
+    }
+    toBuffer() { 
+        return Buffer.from(borsh.serialize(UpdateHomeInstructionDataSchema, this)) 
+    }
+}
+
+const UpdateHomeInstructionDataSchema = new Map([
+    [ UpdateHomeInstructionData, { 
+        kind: 'struct', 
+        fields: [ 
+            ['instruction', 'u8'],
+            ['street', 'string'],
+        ],
+    }]
+])
+
+export function createUpdateHomeInstruction(
+    payer: PublicKey,
+    programId: PublicKey,
+    street: string,
+    somePubkey: PublicKey,
+): TransactionInstruction {
+    const account = deriveHomeAddress(programId, somePubkey)
+
+    const myInstructionObject = new UpdateHomeInstructionData({
+        instruction: MyInstructions.UpdateHome, 
+        street,
+    })
+
+    const keys = [
+        {pubkey: account, isSigner: false, isWritable: true},
+        {pubkey: payer, isSigner: true, isWritable: true},
+        {pubkey: SystemProgram.programId, isSigner: false, isWritable: false},
+    ]
+
+    return new TransactionInstruction({
+        keys,
+        programId,
+        data: myInstructionObject.toBuffer(),
+    })
+}
+
+export function createDeleteHomeInstruction(
+    receiver: PublicKey,
+    programId: PublicKey,
+    somePubkey: PublicKey,
+): TransactionInstruction {
+    const account = deriveHomeAddress(programId, somePubkey)
+    return createBaseInstruction(
+        programId, 
+        MyInstructions.DeleteHome,
+        [
+            {pubkey: account, isSigner: false, isWritable: true},
+            {pubkey: receiver, isSigner: false, isWritable: true},
+            {pubkey: SystemProgram.programId, isSigner: false, isWritable: false},
+        ],
+    )
 }
\ No newline at end of file
@@ -12,6 +12,8 @@ export enum MyInstructions {
     ReadPerson,
     CreateHome,
     ReadHome,
+    UpdateHome,
+    DeleteHome,
     CreateCar,
     ReadCar,
 }
@@ -13,9 +13,11 @@ import {
     createCreateCarInstruction,
     createCreateHomeInstruction,
     createCreatePersonInstruction, 
+    createDeleteHomeInstruction,
     createReadCarInstruction,
     createReadHomeInstruction,
     createReadPersonInstruction,
+    createUpdateHomeInstruction,
 } from './instructions'
 
 describe("Nautilus Unit Tests: Create Accounts", async () => {
@@ -28,6 +30,7 @@ describe("Nautilus Unit Tests: Create Accounts", async () => {
     const personAuthority = Keypair.generate().publicKey
     const homeHouseNumber = 15
     const homeStreet = "Solana St."
+    const homeNewStreet = "Solana Boulevard"
     const homeSomeRandomPubkey = Keypair.generate().publicKey
     const carMake = "Chevrolet"
     const carModel = "Corvette"
@@ -65,6 +68,21 @@ describe("Nautilus Unit Tests: Create Accounts", async () => {
         [payer],
     ))
 
+    it("Update Home", async () => test(
+        createUpdateHomeInstruction(payer.publicKey, program.publicKey, homeNewStreet, homeSomeRandomPubkey),
+        [payer],
+    ))
+
+    it("Read Home", async () => test(
+        createReadHomeInstruction(program.publicKey, homeSomeRandomPubkey),
+        [payer],
+    ))
+
+    it("Delete Home", async () => test(
+        createDeleteHomeInstruction(payer.publicKey, program.publicKey, homeSomeRandomPubkey),
+        [payer],
+    ))
+
     it("Create Car", async () => test(
         await createCreateCarInstruction(payer.publicKey, program.publicKey, carMake, carModel, carPurchaseAuthority, carOperatingAuthority),
         [payer],
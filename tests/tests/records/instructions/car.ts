@@ -117,4 +117,91 @@ export async function createReadCarInstruction(
             {pubkey: record, isSigner: false, isWritable: false},
         ],
     )
-}
\ No newline at end of file
+}
+class UpdateCarInstructionData {
+    instruction: MyInstructions
+    make: string
+    model: string
+    constructor(props: {
+        instruction: MyInstructions,
+        make: string,
+        model: string,
+    }) {
+        this.instruction = props.instruction
+        this.make = props.make
+        this.model = props.model
+    }
+    toBuffer() { 
+        return Buffer.from(borsh.serialize(UpdateCarInstructionDataSchema, this)) 
+    }
+}
+
+const UpdateCarInstructionDataSchema = new Map([
+    [ UpdateCarInstructionData, { 
+        kind: 'struct', 
+        fields: [ 
+            ['instruction', 'u8'],
+            ['make', 'string'],
+            ['model', 'string'],
+        ],
+    }]
+])
+
+export async function createUpdateCarInstruction(
+    payer: PublicKey,
+    programId: PublicKey,
+    make: string,
+    model: string,
+    purchase_authority: PublicKey,
+    operating_authority: PublicKey,
+): Promise<TransactionInstruction> {
+    const index = await fetchIndex(programId)
+    const currentId = index[1].get("car");
+    assert(currentId != undefined)
+    const record = deriveCarAddress(programId, currentId + 1)
+
+    const myInstructionObject = new UpdateCarInstructionData({
+        instruction: MyInstructions.UpdateCar, 
+        make,
+        model,
+    })
+
+    const keys = [
+        {pubkey: index[0], isSigner: false, isWritable: true},
+        {pubkey: record, isSigner: false, isWritable: true},
+        {pubkey: operating_authority, isSigner: true, isWritable: true},
+        {pubkey: payer, isSigner: true, isWritable: true},
+        {pubkey: purchase_authority, isSigner: true, isWritable: true},
+        {pubkey: SystemProgram.programId, isSigner: false, isWritable: false},
+    ]
+
+    return new TransactionInstruction({
+        keys,
+        programId,
+        data: myInstructionObject.toBuffer(),
+    })
+}
+
+export async function createDeleteCarInstruction(
+    receiver: PublicKey,
+    programId: PublicKey,
+    purchase_authority: PublicKey,
+    operating_authority: PublicKey,
+): Promise<TransactionInstruction> {
+    const index = await fetchIndex(programId)
+    const currentId = index[1].get("car");
+    assert(currentId != undefined)
+    const record = deriveCarAddress(programId, currentId + 1)
+    return createBaseInstruction(
+        programId, 
+        MyInstructions.DeleteCar,
+        [
+            {pubkey: index[0], isSigner: false, isWritable: true},
+            {pubkey: record, isSigner: false, isWritable: true},
+            {pubkey: operating_authority, isSigner: true, isWritable: true},
+            {pubkey: purchase_authority, isSigner: true, isWritable: true},
+            {pubkey: receiver, isSigner: false, isWritable: true},
+            {pubkey: SystemProgram.programId, isSigner: false, isWritable: false},
+        ],
+    )
+}
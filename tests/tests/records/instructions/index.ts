@@ -22,6 +22,8 @@ export enum MyInstructions {
     TransferFromHome,
     FundCar,
     TransferFromCar,
+    DeleteCar,
+    UpdateCar,
 }
 
 export class BaseInstructionData {
@@ -15,6 +15,7 @@ import {
     createCreateCarInstruction,
     createCreateHomeInstruction,
     createCreatePersonInstruction, 
+    createDeleteCarInstruction,
     createFundCarInstruction, 
     createFundHomeInstruction, 
     createFundPersonInstruction, 
@@ -25,6 +26,7 @@ import {
     createTransferFromCarInstruction,
     createTransferFromHomeInstruction,
     createTransferFromPersonInstruction,
+    createUpdateCarInstruction,
 } from './instructions'
 
 describe("Nautilus Unit Tests: Create Records", async () => {
@@ -39,6 +41,7 @@ describe("Nautilus Unit Tests: Create Records", async () => {
     const homeStreet = "Solana St."
     const carMake = "Chevrolet"
     const carModel = "Corvette"
+    const carNewModel = "Corvette Stingray"
 
     const fundTransferAmount = LAMPORTS_PER_SOL / 1000
 
@@ -117,5 +120,15 @@ describe("Nautilus Unit Tests: Create Records", async () => {
         await createTransferFromCarInstruction(payer.publicKey, program.publicKey, fundTransferAmount),
         [payer],
     ))
+
+    it("Update Car", async () => test(
+        await createUpdateCarInstruction(payer.publicKey, program.publicKey, carMake, carNewModel, payer.publicKey, payer.publicKey),
+        [payer],
+    ))
+
+    it("Delete Car", async () => test(
+        await createDeleteCarInstruction(payer.publicKey, program.publicKey, payer.publicKey, payer.publicKey),
+        [payer],
+    ))
   })
   
\ No newline at end of file